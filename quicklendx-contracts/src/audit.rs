@@ -17,6 +17,7 @@ pub enum AuditOperation {
     BidPlaced,
     BidAccepted,
     BidWithdrawn,
+    BidAmended,
     EscrowCreated,
     EscrowReleased,
     EscrowRefunded,
@@ -634,6 +635,20 @@ pub fn log_bid_withdrawn(env: &Env, invoice_id: BytesN<32>, actor: Address, _bid
     );
 }
 
+/// Log bid amended.
+pub fn log_bid_amended(env: &Env, invoice_id: BytesN<32>, actor: Address, bid_amount: i128) {
+    log_operation(
+        env,
+        invoice_id,
+        AuditOperation::BidAmended,
+        actor,
+        None,
+        Some(String::from_str(env, "Bid amended")),
+        Some(bid_amount),
+        None,
+    );
+}
+
 /// Log escrow created.
 pub fn log_escrow_created(
     env: &Env,
@@ -0,0 +1,193 @@
+//! Auto-accept policies for bids.
+//!
+//! A business can register a standing policy, either for all of its invoices or for a
+//! single invoice, describing bids it is willing to take without reviewing them. The
+//! first bid placed through `place_bid` that satisfies the policy is funded through the
+//! same escrow/investment flow as `accept_bid`. An invoice policy takes precedence over
+//! the business-wide one.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_auto_accept_cleared, emit_auto_accept_set};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+use crate::verification::InvestorTier;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum AutoAcceptKey {
+    Business(Address),
+    Invoice(BytesN<32>),
+}
+
+/// Bids a business accepts automatically. Amounts are relative to the invoice face
+/// value so that one business-wide policy fits invoices of any size.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoAcceptPolicy {
+    /// Smallest accepted `bid_amount`, in bps of the invoice amount.
+    pub min_advance_bps: u32,
+    /// Largest accepted `expected_return`, in bps of the invoice amount.
+    pub max_return_bps: u32,
+    /// Lowest accepted investor tier; `Basic` accepts any verified investor.
+    pub min_investor_tier: InvestorTier,
+}
+
+pub struct AutoAcceptStorage;
+
+impl AutoAcceptStorage {
+    pub fn get_business_policy(env: &Env, business: &Address) -> Option<AutoAcceptPolicy> {
+        env.storage()
+            .persistent()
+            .get(&AutoAcceptKey::Business(business.clone()))
+    }
+
+    pub fn get_invoice_policy(env: &Env, invoice_id: &BytesN<32>) -> Option<AutoAcceptPolicy> {
+        env.storage()
+            .persistent()
+            .get(&AutoAcceptKey::Invoice(invoice_id.clone()))
+    }
+
+    /// Policy that applies to an invoice: its own, falling back to its business's.
+    pub fn get_effective_policy(env: &Env, invoice: &Invoice) -> Option<AutoAcceptPolicy> {
+        Self::get_invoice_policy(env, &invoice.id)
+            .or_else(|| Self::get_business_policy(env, &invoice.business))
+    }
+}
+
+pub struct AutoAccept;
+
+impl AutoAccept {
+    /// Set the business-wide auto-accept policy (business only).
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if the policy is inconsistent (see `validate_policy`)
+    pub fn set_business_policy(
+        env: &Env,
+        business: &Address,
+        policy: &AutoAcceptPolicy,
+    ) -> Result<(), QuickLendXError> {
+        business.require_auth();
+        Self::validate_policy(policy)?;
+        env.storage()
+            .persistent()
+            .set(&AutoAcceptKey::Business(business.clone()), policy);
+        emit_auto_accept_set(env, business, None, policy);
+        Ok(())
+    }
+
+    /// Revoke the business-wide auto-accept policy (business only).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no policy is set
+    pub fn clear_business_policy(env: &Env, business: &Address) -> Result<(), QuickLendXError> {
+        business.require_auth();
+        let key = AutoAcceptKey::Business(business.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+        env.storage().persistent().remove(&key);
+        emit_auto_accept_cleared(env, business, None);
+        Ok(())
+    }
+
+    /// Set the auto-accept policy of one invoice (business only).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is no longer open for bids
+    /// * `InvalidAmount` if the policy is inconsistent
+    pub fn set_invoice_policy(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        policy: &AutoAcceptPolicy,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = Self::require_open_invoice(env, invoice_id)?;
+        invoice.business.require_auth();
+        Self::validate_policy(policy)?;
+        env.storage()
+            .persistent()
+            .set(&AutoAcceptKey::Invoice(invoice_id.clone()), policy);
+        emit_auto_accept_set(env, &invoice.business, Some(invoice_id), policy);
+        Ok(())
+    }
+
+    /// Revoke the auto-accept policy of one invoice (business only).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `StorageKeyNotFound` if no policy is set
+    pub fn clear_invoice_policy(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        let key = AutoAcceptKey::Invoice(invoice_id.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+        env.storage().persistent().remove(&key);
+        emit_auto_accept_cleared(env, &invoice.business, Some(invoice_id));
+        Ok(())
+    }
+
+    /// Whether a bid qualifies for automatic acceptance under the invoice's policy.
+    pub fn qualifies(
+        env: &Env,
+        invoice: &Invoice,
+        bid_amount: i128,
+        expected_return: i128,
+        investor_tier: &InvestorTier,
+    ) -> bool {
+        let policy = match AutoAcceptStorage::get_effective_policy(env, invoice) {
+            Some(policy) => policy,
+            None => return false,
+        };
+
+        let min_advance = invoice
+            .amount
+            .saturating_mul(policy.min_advance_bps as i128)
+            / BPS_DENOMINATOR;
+        let max_return =
+            invoice.amount.saturating_mul(policy.max_return_bps as i128) / BPS_DENOMINATOR;
+        if bid_amount < min_advance || expected_return > max_return {
+            return false;
+        }
+
+        Self::tier_rank(investor_tier) >= Self::tier_rank(&policy.min_investor_tier)
+    }
+
+    /// The advance floor must be a real fraction of face value and leave room for a
+    /// positive return under the return cap.
+    fn validate_policy(policy: &AutoAcceptPolicy) -> Result<(), QuickLendXError> {
+        if policy.min_advance_bps == 0 || policy.min_advance_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if policy.max_return_bps <= policy.min_advance_bps {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    fn require_open_invoice(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Result<Invoice, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        match invoice.status {
+            InvoiceStatus::Pending | InvoiceStatus::Verified => Ok(invoice),
+            _ => Err(QuickLendXError::InvalidStatus),
+        }
+    }
+
+    fn tier_rank(tier: &InvestorTier) -> u32 {
+        match tier {
+            InvestorTier::Basic => 0,
+            InvestorTier::Silver => 1,
+            InvestorTier::Gold => 2,
+            InvestorTier::Platinum => 3,
+            InvestorTier::VIP => 4,
+        }
+    }
+}
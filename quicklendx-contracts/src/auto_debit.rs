@@ -0,0 +1,120 @@
+//! Pull-based settlement from pre-authorized allowances.
+//!
+//! An obligor (the business, or the buyer of a reverse-factored invoice) can opt into
+//! auto-debit and grant the contract a token allowance. From the due date on, anyone can
+//! then call `collect_due` on its funded invoices to pull what is owed and settle them.
+//! A pull the obligor's balance or allowance cannot cover is recorded instead, and the
+//! business and investor are notified that the payment is overdue.
+
+use soroban_sdk::{contracttype, token, Address, BytesN, Env, Vec};
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_auto_debit_collected, emit_auto_debit_failed, emit_auto_debit_set};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::notifications::NotificationSystem;
+use crate::reverse_factoring::ReverseFactoringStorage;
+use crate::settlement::{obligor_settlement_amount, settle_from_allowance};
+
+/// Most failed pulls kept per invoice; the oldest are dropped first.
+pub const MAX_COLLECTION_FAILURES: u32 = 20;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum AutoDebitKey {
+    AutoDebitEnabled(Address),
+    CollectionFailures(BytesN<32>),
+}
+
+/// A pull that the obligor's balance or allowance did not cover.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionFailure {
+    pub attempted_at: u64,
+    pub amount: i128,
+    pub balance: i128,
+    pub allowance: i128,
+}
+
+pub struct AutoDebit;
+
+impl AutoDebit {
+    pub fn is_enabled(env: &Env, obligor: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&AutoDebitKey::AutoDebitEnabled(obligor.clone()))
+            .unwrap_or(false)
+    }
+
+    /// Opt in or out of having amounts due pulled from the allowance `obligor` grants the
+    /// contract.
+    pub fn set_enabled(env: &Env, obligor: &Address, enabled: bool) {
+        obligor.require_auth();
+        let key = AutoDebitKey::AutoDebitEnabled(obligor.clone());
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        emit_auto_debit_set(env, obligor, enabled);
+    }
+
+    pub fn get_failures(env: &Env, invoice_id: &BytesN<32>) -> Vec<CollectionFailure> {
+        env.storage()
+            .persistent()
+            .get(&AutoDebitKey::CollectionFailures(invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Pull what a funded invoice's obligor owes from its allowance and settle the invoice.
+    /// Returns false, after recording the failure and notifying the parties, when the
+    /// obligor's balance or allowance does not cover the pull.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Funded
+    /// * `OperationNotAllowed` if the obligor has not opted into auto-debit, or the invoice
+    ///   is not due yet
+    pub fn collect_due(env: &Env, invoice_id: &BytesN<32>) -> Result<bool, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let obligor = ReverseFactoringStorage::obligor(env, &invoice);
+        if !Self::is_enabled(env, &obligor) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let now = env.ledger().timestamp();
+        if now < invoice.due_date {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let amount = obligor_settlement_amount(env, &invoice)?;
+        let token_client = token::Client::new(env, &invoice.currency);
+        let balance = token_client.balance(&obligor);
+        let allowance = token_client.allowance(&obligor, &env.current_contract_address());
+        if balance < amount || allowance < amount {
+            let mut failures = Self::get_failures(env, invoice_id);
+            if failures.len() >= MAX_COLLECTION_FAILURES {
+                failures.pop_front();
+            }
+            failures.push_back(CollectionFailure {
+                attempted_at: now,
+                amount,
+                balance,
+                allowance,
+            });
+            env.storage().persistent().set(
+                &AutoDebitKey::CollectionFailures(invoice_id.clone()),
+                &failures,
+            );
+            emit_auto_debit_failed(env, invoice_id, &obligor, amount);
+            let _ = NotificationSystem::notify_payment_overdue(env, &invoice);
+            return Ok(false);
+        }
+
+        settle_from_allowance(env, invoice_id)?;
+        emit_auto_debit_collected(env, invoice_id, &obligor, amount);
+        Ok(true)
+    }
+}
@@ -0,0 +1,190 @@
+//! Batch payments across several invoices.
+//!
+//! A payer with many invoices can pay them in one call, either as a list of
+//! `(invoice, amount, nonce)` entries or as one lump sum allocated across invoices in a
+//! chosen order. Each payment is checked before it is applied, so a rejected one is
+//! reported in its result and leaves nothing behind, while the others go through. A
+//! payment covering what is left due settles its invoice, as `settle_invoice` would.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Vec};
+
+use crate::errors::QuickLendXError;
+use crate::events::emit_batch_payment;
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::settlement::{check_payment, get_invoice_progress, process_authorized_payment};
+
+/// Most invoices paid in one batch.
+pub const MAX_BATCH_PAYMENTS: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchPayment {
+    pub invoice_id: BytesN<32>,
+    pub amount: i128,
+    /// Replay protection per invoice and payer; empty to skip it
+    pub nonce: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchPaymentResult {
+    pub invoice_id: BytesN<32>,
+    /// Amount applied to the invoice, after capping at what was left due
+    pub applied: i128,
+    /// Whether the payment completed and settled the invoice
+    pub settled: bool,
+    /// Code of the `QuickLendXError` the payment was rejected with; 0 when it was applied
+    /// or, in a lump sum, when nothing was left to allocate to the invoice
+    pub error: u32,
+}
+
+/// Order a lump sum is allocated across invoices in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AllocationOrder {
+    AsListed,
+    OldestDueFirst,
+    SmallestBalanceFirst,
+}
+
+pub struct BatchPayments;
+
+impl BatchPayments {
+    /// Apply each payment in turn from `payer`, who authorizes the batch once. Returns a
+    /// result per payment, in order.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if there are no payments or more than `MAX_BATCH_PAYMENTS`
+    pub fn process(
+        env: &Env,
+        payer: &Address,
+        payments: &Vec<BatchPayment>,
+    ) -> Result<Vec<BatchPaymentResult>, QuickLendXError> {
+        if payments.is_empty() || payments.len() > MAX_BATCH_PAYMENTS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        payer.require_auth();
+
+        let mut results = Vec::new(env);
+        for payment in payments.iter() {
+            results.push_back(Self::pay(
+                env,
+                payer,
+                &payment.invoice_id,
+                payment.amount,
+                payment.nonce,
+            )?);
+        }
+        emit_batch_payment(env, payer, &results);
+        Ok(results)
+    }
+
+    /// Allocate `total` from `payer` across invoices in `order`, paying each what is left
+    /// due until the sum runs out. Rejected invoices do not use up any of it. Returns a
+    /// result per invoice, in allocation order.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `total` is not positive, or there are no invoices or more than
+    ///   `MAX_BATCH_PAYMENTS`
+    pub fn allocate(
+        env: &Env,
+        payer: &Address,
+        invoice_ids: &Vec<BytesN<32>>,
+        total: i128,
+        order: AllocationOrder,
+        nonce: String,
+    ) -> Result<Vec<BatchPaymentResult>, QuickLendXError> {
+        if total <= 0 || invoice_ids.is_empty() || invoice_ids.len() > MAX_BATCH_PAYMENTS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        payer.require_auth();
+
+        let mut results = Vec::new(env);
+        let mut left = total;
+        for invoice_id in Self::in_order(env, invoice_ids, &order).iter() {
+            if left <= 0 {
+                results.push_back(BatchPaymentResult {
+                    invoice_id,
+                    applied: 0,
+                    settled: false,
+                    error: 0,
+                });
+                continue;
+            }
+            // Invoices with nothing left due get the rest, to be rejected with the reason
+            let share = match get_invoice_progress(env, &invoice_id) {
+                Ok(progress) if progress.remaining_due > 0 => left.min(progress.remaining_due),
+                _ => left,
+            };
+            let result = Self::pay(env, payer, &invoice_id, share, nonce.clone())?;
+            left -= result.applied;
+            results.push_back(result);
+        }
+        emit_batch_payment(env, payer, &results);
+        Ok(results)
+    }
+
+    /// Apply one payment if it passes `check_payment`, or report why it did not. Errors
+    /// only if applying a checked payment fails, which aborts the whole batch.
+    fn pay(
+        env: &Env,
+        payer: &Address,
+        invoice_id: &BytesN<32>,
+        amount: i128,
+        nonce: String,
+    ) -> Result<BatchPaymentResult, QuickLendXError> {
+        let applied = match check_payment(env, invoice_id, payer, amount, &nonce) {
+            Ok(applied) => applied,
+            Err(error) => {
+                return Ok(BatchPaymentResult {
+                    invoice_id: invoice_id.clone(),
+                    applied: 0,
+                    settled: false,
+                    error: error as u32,
+                })
+            }
+        };
+        process_authorized_payment(env, invoice_id, payer, amount, nonce)?;
+        let settled = InvoiceStorage::get_invoice(env, invoice_id)
+            .map(|invoice| invoice.status == InvoiceStatus::Paid)
+            .unwrap_or(false);
+        Ok(BatchPaymentResult {
+            invoice_id: invoice_id.clone(),
+            applied,
+            settled,
+            error: 0,
+        })
+    }
+
+    /// Invoices sorted by `order`; the sort is stable and unknown invoices go last.
+    fn in_order(
+        env: &Env,
+        invoice_ids: &Vec<BytesN<32>>,
+        order: &AllocationOrder,
+    ) -> Vec<BytesN<32>> {
+        let mut keyed: Vec<(i128, BytesN<32>)> = Vec::new(env);
+        for invoice_id in invoice_ids.iter() {
+            let key = match order {
+                AllocationOrder::AsListed => 0,
+                AllocationOrder::OldestDueFirst => InvoiceStorage::get_invoice(env, &invoice_id)
+                    .map(|invoice| invoice.due_date as i128)
+                    .unwrap_or(i128::MAX),
+                AllocationOrder::SmallestBalanceFirst => get_invoice_progress(env, &invoice_id)
+                    .map(|progress| progress.remaining_due)
+                    .unwrap_or(i128::MAX),
+            };
+            // Insert after every entry with a key no greater, keeping ties as listed
+            let mut index = keyed.len();
+            while index > 0 && keyed.get(index - 1).unwrap().0 > key {
+                index -= 1;
+            }
+            keyed.insert(index, (key, invoice_id));
+        }
+
+        let mut ordered = Vec::new(env);
+        for (_, invoice_id) in keyed.iter() {
+            ordered.push_back(invoice_id);
+        }
+        ordered
+    }
+}
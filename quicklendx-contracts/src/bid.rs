@@ -4,6 +4,7 @@ use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec}
 use crate::admin::AdminStorage;
 use crate::errors::QuickLendXError;
 use crate::events::emit_bid_expired;
+use crate::invoice::{Invoice, InvoiceStorage};
 
 // TTL stored in days (admin configurable). Defaults to 7 days. Bounds: 1..=30
 const DEFAULT_BID_TTL_DAYS: u64 = 7;
@@ -13,6 +14,8 @@ const BID_TTL_KEY: Symbol = symbol_short!("bid_ttl");
 const MAX_ACTIVE_BIDS_PER_INVESTOR_KEY: Symbol = symbol_short!("mx_actbd");
 const DEFAULT_MAX_ACTIVE_BIDS_PER_INVESTOR: u32 = 20;
 const SECONDS_PER_DAY: u64 = 86400;
+/// Maximum number of times a single bid can be amended.
+pub const MAX_BID_AMENDMENTS: u32 = 10;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -37,6 +40,16 @@ pub struct Bid {
     pub expiration_timestamp: u64,
 }
 
+/// Terms a bid had before an amendment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidAmendment {
+    pub previous_amount: i128,
+    pub previous_expected_return: i128,
+    pub previous_expiration: u64,
+    pub amended_at: u64,
+}
+
 impl Bid {
     pub fn is_expired(&self, current_timestamp: u64) -> bool {
         current_timestamp > self.expiration_timestamp
@@ -100,6 +113,89 @@ impl BidStorage {
     pub fn update_bid(env: &Env, bid: &Bid) {
         env.storage().instance().set(&bid.bid_id, bid);
     }
+
+    fn amendments_key(bid_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("bid_amds"), bid_id.clone())
+    }
+
+    /// Amendment history of a bid, oldest first.
+    pub fn get_amendments(env: &Env, bid_id: &BytesN<32>) -> Vec<BidAmendment> {
+        env.storage()
+            .instance()
+            .get(&Self::amendments_key(bid_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Append to a bid's amendment history, enforcing `MAX_BID_AMENDMENTS`.
+    pub fn record_amendment(
+        env: &Env,
+        bid_id: &BytesN<32>,
+        amendment: &BidAmendment,
+    ) -> Result<(), QuickLendXError> {
+        let mut history = Self::get_amendments(env, bid_id);
+        if history.len() >= MAX_BID_AMENDMENTS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        history.push_back(amendment.clone());
+        env.storage()
+            .instance()
+            .set(&Self::amendments_key(bid_id), &history);
+        Ok(())
+    }
+
+    fn debtor_condition_key(bid_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("bid_dcnf"), bid_id.clone())
+    }
+
+    /// Whether the investor made the bid conditional on the debtor confirming the invoice.
+    pub fn requires_debtor_confirmation(env: &Env, bid_id: &BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .get(&Self::debtor_condition_key(bid_id))
+            .unwrap_or(false)
+    }
+
+    pub fn set_requires_debtor_confirmation(env: &Env, bid_id: &BytesN<32>, required: bool) {
+        let key = Self::debtor_condition_key(bid_id);
+        if required {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+
+    /// Conditional bids cannot be accepted until the invoice's debtor has confirmed it.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the bid is conditional and the invoice is unconfirmed
+    pub fn require_debtor_condition_met(
+        env: &Env,
+        invoice: &Invoice,
+        bid: &Bid,
+    ) -> Result<(), QuickLendXError> {
+        if !invoice.is_debtor_confirmed() && Self::requires_debtor_confirmation(env, &bid.bid_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Placed bids that can currently be accepted, i.e. excluding conditional bids on an
+    /// invoice the debtor has not confirmed.
+    fn get_acceptable_bids(env: &Env, invoice_id: &BytesN<32>) -> Vec<Bid> {
+        let confirmed = InvoiceStorage::get_invoice(env, invoice_id)
+            .map(|invoice| invoice.is_debtor_confirmed())
+            .unwrap_or(false);
+        let mut acceptable = Vec::new(env);
+        for bid in Self::get_bid_records_for_invoice(env, invoice_id).iter() {
+            if bid.status == BidStatus::Placed
+                && (confirmed || !Self::requires_debtor_confirmation(env, &bid.bid_id))
+            {
+                acceptable.push_back(bid);
+            }
+        }
+        acceptable
+    }
+
     pub fn get_bids_for_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<BytesN<32>> {
         env.storage()
             .instance()
@@ -284,15 +380,11 @@ impl BidStorage {
         Ordering::Equal
     }
     pub fn get_best_bid(env: &Env, invoice_id: &BytesN<32>) -> Option<Bid> {
-        let records = Self::get_bid_records_for_invoice(env, invoice_id);
+        let records = Self::get_acceptable_bids(env, invoice_id);
         let mut best: Option<Bid> = None;
         let mut idx: u32 = 0;
         while idx < records.len() {
             let candidate = records.get(idx).unwrap();
-            if candidate.status != BidStatus::Placed {
-                idx += 1;
-                continue;
-            }
             best = match best {
                 None => Some(candidate),
                 Some(current) => {
@@ -308,17 +400,7 @@ impl BidStorage {
         best
     }
     pub fn rank_bids(env: &Env, invoice_id: &BytesN<32>) -> Vec<Bid> {
-        let records = Self::get_bid_records_for_invoice(env, invoice_id);
-        let mut remaining = Vec::new(env);
-        let mut idx: u32 = 0;
-        while idx < records.len() {
-            let bid = records.get(idx).unwrap();
-            if bid.status == BidStatus::Placed {
-                remaining.push_back(bid);
-            }
-            idx += 1;
-        }
-
+        let mut remaining = Self::get_acceptable_bids(env, invoice_id);
         let mut ranked = Vec::new(env);
 
         while remaining.len() > 0 {
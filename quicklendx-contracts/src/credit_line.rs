@@ -0,0 +1,567 @@
+//! Revolving credit lines for verified businesses.
+//!
+//! A verified investor, or the curator of a liquidity vault on the vault's behalf,
+//! proposes a credit limit to a business, which has to accept the line before drawing on
+//! it. Instead of auctioning each invoice, the business draws against the line by
+//! pledging a Verified invoice: the draw is a bid from the lender at the line's advance
+//! rate, funded at once through the regular escrow, `Investment` and receipt flow.
+//! Payments collected on a drawn invoice (paid out through progressive distribution, or
+//! at settlement) repay its principal first and restore the available credit by the same
+//! amount; refunding the invoice releases the draw.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::admin::AdminStorage;
+use crate::bid::{Bid, BidStatus, BidStorage};
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_bid_placed, emit_credit_line_accepted, emit_credit_line_closed, emit_credit_line_drawn,
+    emit_credit_line_opened, emit_credit_line_repaid, emit_credit_line_updated,
+};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+use crate::vault::{LiquidityVaults, VaultStorage};
+use crate::verification::{
+    get_business_verification_status, validate_investor_investment, BusinessVerificationStatus,
+    InvestorVerificationStorage,
+};
+
+/// Maximum number of Active credit lines a business can hold.
+pub const MAX_CREDIT_LINES_PER_BUSINESS: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum CreditLineKey {
+    CreditLine(u64),
+    LinesByBusiness(Address),
+    LinesByLender(Address),
+    /// Pledged invoice -> draw
+    CreditDraw(BytesN<32>),
+    CreditLineCounter,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CreditLineStatus {
+    /// Proposed by the lender and waiting for the business to accept it.
+    Proposed,
+    /// Open for draws until it expires.
+    Active,
+    /// Closed by the lender, before or after acceptance; outstanding draws are still
+    /// repaid.
+    Closed,
+}
+
+/// Terms a lender commits to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineTerms {
+    pub currency: Address,
+    /// Maximum principal outstanding at any time.
+    pub limit: i128,
+    /// Amount advanced per draw, in bps of the pledged invoice's face value.
+    pub advance_bps: u32,
+    /// Expected return per draw, in bps of the pledged invoice's face value.
+    pub return_bps: u32,
+    /// No draws are accepted from this timestamp on.
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLine {
+    pub line_id: u64,
+    /// Funding address: the investor, or the contract for a vault line.
+    pub lender: Address,
+    /// Vault the line draws on, if it is a vault line.
+    pub vault_id: Option<u64>,
+    pub business: Address,
+    pub terms: CreditLineTerms,
+    /// Principal drawn and not yet repaid.
+    pub utilized: i128,
+    pub status: CreditLineStatus,
+    pub created_at: u64,
+}
+
+impl CreditLine {
+    pub fn available(&self) -> i128 {
+        self.terms.limit.saturating_sub(self.utilized).max(0)
+    }
+
+    /// Share of the limit in use, in bps.
+    pub fn utilization_bps(&self) -> u32 {
+        if self.terms.limit <= 0 {
+            return 0;
+        }
+        (self.utilized.saturating_mul(BPS_DENOMINATOR) / self.terms.limit).clamp(0, BPS_DENOMINATOR)
+            as u32
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.terms.expires_at
+    }
+}
+
+/// An invoice pledged against a credit line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditDraw {
+    pub line_id: u64,
+    pub invoice_id: BytesN<32>,
+    pub amount: i128,
+    pub outstanding: i128,
+    pub drawn_at: u64,
+}
+
+pub struct CreditLineStorage;
+
+impl CreditLineStorage {
+    pub fn get_line(env: &Env, line_id: u64) -> Option<CreditLine> {
+        env.storage()
+            .persistent()
+            .get(&CreditLineKey::CreditLine(line_id))
+    }
+
+    pub fn get_lines_by_business(env: &Env, business: &Address) -> Vec<CreditLine> {
+        Self::load_all(env, &CreditLineKey::LinesByBusiness(business.clone()))
+    }
+
+    pub fn get_lines_by_lender(env: &Env, lender: &Address) -> Vec<CreditLine> {
+        Self::load_all(env, &CreditLineKey::LinesByLender(lender.clone()))
+    }
+
+    pub fn get_draw(env: &Env, invoice_id: &BytesN<32>) -> Option<CreditDraw> {
+        env.storage()
+            .persistent()
+            .get(&CreditLineKey::CreditDraw(invoice_id.clone()))
+    }
+
+    fn get_ids(env: &Env, index_key: &CreditLineKey) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(index_key)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn load_all(env: &Env, index_key: &CreditLineKey) -> Vec<CreditLine> {
+        let mut lines = Vec::new(env);
+        for line_id in Self::get_ids(env, index_key).iter() {
+            if let Some(line) = Self::get_line(env, line_id) {
+                lines.push_back(line);
+            }
+        }
+        lines
+    }
+
+    fn append_index(env: &Env, index_key: &CreditLineKey, line_id: u64) {
+        let mut ids = Self::get_ids(env, index_key);
+        ids.push_back(line_id);
+        env.storage().persistent().set(index_key, &ids);
+    }
+
+    fn store_line(env: &Env, line: &CreditLine) {
+        env.storage()
+            .persistent()
+            .set(&CreditLineKey::CreditLine(line.line_id), line);
+    }
+
+    fn store_draw(env: &Env, draw: &CreditDraw) {
+        env.storage()
+            .persistent()
+            .set(&CreditLineKey::CreditDraw(draw.invoice_id.clone()), draw);
+    }
+
+    fn next_line_id(env: &Env) -> u64 {
+        let next: u64 = env
+            .storage()
+            .persistent()
+            .get(&CreditLineKey::CreditLineCounter)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage()
+            .persistent()
+            .set(&CreditLineKey::CreditLineCounter, &next);
+        next
+    }
+}
+
+pub struct CreditLines;
+
+impl CreditLines {
+    /// Propose a credit limit to a business (verified investor). The line opens once the
+    /// business accepts it.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the lender is not a verified
+    ///   investor, or the business is not a verified business
+    /// * `OperationNotAllowed` if the lender names itself, or the business already holds
+    ///   `MAX_CREDIT_LINES_PER_BUSINESS` Active lines
+    /// * `InvalidAmount` / `InvalidTimestamp` for invalid terms
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    pub fn open(
+        env: &Env,
+        lender: &Address,
+        business: &Address,
+        terms: &CreditLineTerms,
+    ) -> Result<CreditLine, QuickLendXError> {
+        lender.require_auth();
+        let verification = InvestorVerificationStorage::get(env, lender)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        if lender == business {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Self::store_new(env, lender, None, business, terms)
+    }
+
+    /// Propose a credit limit to a business out of a liquidity vault (vault curator or
+    /// admin). Once accepted, draws are funded from the vault's idle liquidity and count
+    /// towards its business exposure cap.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the vault does not exist
+    /// * `Unauthorized` if `caller` is neither the vault's curator nor the admin
+    /// * `InvalidCurrency` if the terms use another currency than the vault
+    /// * Same as `open` otherwise
+    pub fn open_for_vault(
+        env: &Env,
+        caller: &Address,
+        vault_id: u64,
+        business: &Address,
+        terms: &CreditLineTerms,
+    ) -> Result<CreditLine, QuickLendXError> {
+        caller.require_auth();
+        let vault =
+            VaultStorage::get_vault(env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if vault.curator != *caller && !AdminStorage::is_admin(env, caller) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if vault.currency != terms.currency {
+            return Err(QuickLendXError::InvalidCurrency);
+        }
+        Self::store_new(
+            env,
+            &env.current_contract_address(),
+            Some(vault_id),
+            business,
+            terms,
+        )
+    }
+
+    /// Open a proposed line for draws (business only).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the line does not exist
+    /// * `Unauthorized` if the line was not proposed to `business`
+    /// * `InvalidStatus` if the line is not Proposed
+    /// * `InvalidTimestamp` if the line has expired
+    /// * `OperationNotAllowed` if the business already holds
+    ///   `MAX_CREDIT_LINES_PER_BUSINESS` Active lines
+    pub fn accept(env: &Env, business: &Address, line_id: u64) -> Result<(), QuickLendXError> {
+        business.require_auth();
+        let mut line =
+            CreditLineStorage::get_line(env, line_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if line.business != *business {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if line.status != CreditLineStatus::Proposed {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if line.is_expired(env.ledger().timestamp()) {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Self::require_line_capacity(env, business)?;
+
+        line.status = CreditLineStatus::Active;
+        CreditLineStorage::store_line(env, &line);
+        emit_credit_line_accepted(env, &line);
+        Ok(())
+    }
+
+    /// Change the limit and expiry of a line (lender only; the vault's curator or the
+    /// admin for a vault line). A limit below the current utilization only blocks new
+    /// draws.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the line does not exist
+    /// * `Unauthorized` if `caller` does not manage the line
+    /// * `InvalidStatus` if the line is closed
+    /// * `InvalidAmount` / `InvalidTimestamp` for an invalid limit or expiry
+    pub fn update(
+        env: &Env,
+        caller: &Address,
+        line_id: u64,
+        limit: i128,
+        expires_at: u64,
+    ) -> Result<CreditLine, QuickLendXError> {
+        let mut line = Self::managed_line(env, caller, line_id)?;
+        if line.status != CreditLineStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if limit <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        line.terms.limit = limit;
+        line.terms.expires_at = expires_at;
+        CreditLineStorage::store_line(env, &line);
+        emit_credit_line_updated(env, &line);
+        Ok(line)
+    }
+
+    /// Close a line to new draws, or withdraw a proposed one (lender only; the vault's
+    /// curator or the admin for a vault line). Outstanding draws are unaffected.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the line does not exist
+    /// * `Unauthorized` if `caller` does not manage the line
+    /// * `InvalidStatus` if the line is already closed
+    pub fn close(env: &Env, caller: &Address, line_id: u64) -> Result<(), QuickLendXError> {
+        let mut line = Self::managed_line(env, caller, line_id)?;
+        if line.status == CreditLineStatus::Closed {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        line.status = CreditLineStatus::Closed;
+        CreditLineStorage::store_line(env, &line);
+        emit_credit_line_closed(env, &line);
+        Ok(())
+    }
+
+    /// Draw on a line by pledging a Verified invoice of the business. Places the
+    /// lender's bid at the line's advance rate and returns it, with the invoice, for the
+    /// caller to fund.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the line does not exist
+    /// * `Unauthorized` if `business` does not hold the line
+    /// * `InvalidStatus` if the line is not Active, or the invoice is not Verified
+    /// * `InvalidTimestamp` if the line has expired
+    /// * `BusinessNotVerified` if the business or the investor lender is no longer verified
+    /// * `InvalidCurrency` if the currency is no longer whitelisted or the invoice uses
+    ///   another one
+    /// * `InvoiceNotFound` / `NotBusinessOwner` if the invoice is missing or not the
+    ///   business's
+    /// * `OperationNotAllowed` if the invoice was already pledged
+    /// * `InsufficientFunds` if the draw exceeds the available credit
+    /// * `InvalidAmount` if the draw is zero or exceeds the investor lender's limits
+    pub fn draw(
+        env: &Env,
+        business: &Address,
+        line_id: u64,
+        invoice_id: &BytesN<32>,
+    ) -> Result<(Invoice, Bid), QuickLendXError> {
+        business.require_auth();
+        let mut line =
+            CreditLineStorage::get_line(env, line_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if line.business != *business {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if line.status != CreditLineStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let now = env.ledger().timestamp();
+        if line.is_expired(now) {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Self::require_verified_business(env, business)?;
+        CurrencyWhitelist::require_allowed_currency(env, &line.terms.currency)?;
+
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.business != *business {
+            return Err(QuickLendXError::NotBusinessOwner);
+        }
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if invoice.currency != line.terms.currency {
+            return Err(QuickLendXError::InvalidCurrency);
+        }
+        if CreditLineStorage::get_draw(env, invoice_id).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let amount = invoice
+            .amount
+            .saturating_mul(line.terms.advance_bps as i128)
+            / BPS_DENOMINATOR;
+        let expected_return =
+            invoice.amount.saturating_mul(line.terms.return_bps as i128) / BPS_DENOMINATOR;
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if amount > line.available() {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        if line.vault_id.is_none() {
+            validate_investor_investment(env, &line.lender, amount)?;
+        }
+
+        let bid = Bid {
+            bid_id: BidStorage::generate_unique_bid_id(env),
+            invoice_id: invoice_id.clone(),
+            investor: line.lender.clone(),
+            bid_amount: amount,
+            expected_return,
+            timestamp: now,
+            status: BidStatus::Placed,
+            expiration_timestamp: Bid::default_expiration_with_env(env, now),
+        };
+        BidStorage::store_bid(env, &bid);
+        BidStorage::add_bid_to_invoice(env, invoice_id, &bid.bid_id);
+        if let Some(vault_id) = line.vault_id {
+            LiquidityVaults::register_bid(env, vault_id, &bid.bid_id);
+        }
+        emit_bid_placed(env, &bid);
+
+        line.utilized = line.utilized.saturating_add(amount);
+        CreditLineStorage::store_line(env, &line);
+        CreditLineStorage::store_draw(
+            env,
+            &CreditDraw {
+                line_id,
+                invoice_id: invoice_id.clone(),
+                amount,
+                outstanding: amount,
+                drawn_at: now,
+            },
+        );
+        emit_credit_line_drawn(env, &line, invoice_id, amount);
+        Ok((invoice, bid))
+    }
+
+    /// Restore credit for a payment collected on a pledged invoice. Payments repay the
+    /// draw's principal first; does nothing for invoices not drawn on a line.
+    pub fn record_repayment(env: &Env, invoice_id: &BytesN<32>, amount: i128) {
+        let mut draw = match CreditLineStorage::get_draw(env, invoice_id) {
+            Some(draw) if draw.outstanding > 0 => draw,
+            _ => return,
+        };
+        let mut line = match CreditLineStorage::get_line(env, draw.line_id) {
+            Some(line) => line,
+            None => return,
+        };
+        let repaid = amount.min(draw.outstanding).max(0);
+        draw.outstanding -= repaid;
+        line.utilized = line.utilized.saturating_sub(repaid).max(0);
+        CreditLineStorage::store_draw(env, &draw);
+        CreditLineStorage::store_line(env, &line);
+        emit_credit_line_repaid(env, &line, invoice_id, repaid);
+    }
+
+    /// Restore whatever credit a pledged invoice still uses, once it has settled or its
+    /// escrow was refunded to the lender.
+    pub fn release_draw(env: &Env, invoice_id: &BytesN<32>) {
+        if let Some(draw) = CreditLineStorage::get_draw(env, invoice_id) {
+            Self::record_repayment(env, invoice_id, draw.outstanding);
+        }
+    }
+
+    fn store_new(
+        env: &Env,
+        lender: &Address,
+        vault_id: Option<u64>,
+        business: &Address,
+        terms: &CreditLineTerms,
+    ) -> Result<CreditLine, QuickLendXError> {
+        Self::require_verified_business(env, business)?;
+        Self::validate_terms(env, terms)?;
+        CurrencyWhitelist::require_allowed_currency(env, &terms.currency)?;
+        Self::require_line_capacity(env, business)?;
+
+        let line = CreditLine {
+            line_id: CreditLineStorage::next_line_id(env),
+            lender: lender.clone(),
+            vault_id,
+            business: business.clone(),
+            terms: terms.clone(),
+            utilized: 0,
+            status: CreditLineStatus::Proposed,
+            created_at: env.ledger().timestamp(),
+        };
+        CreditLineStorage::store_line(env, &line);
+        CreditLineStorage::append_index(
+            env,
+            &CreditLineKey::LinesByBusiness(business.clone()),
+            line.line_id,
+        );
+        CreditLineStorage::append_index(
+            env,
+            &CreditLineKey::LinesByLender(lender.clone()),
+            line.line_id,
+        );
+
+        emit_credit_line_opened(env, &line);
+        Ok(line)
+    }
+
+    fn managed_line(
+        env: &Env,
+        caller: &Address,
+        line_id: u64,
+    ) -> Result<CreditLine, QuickLendXError> {
+        caller.require_auth();
+        let line =
+            CreditLineStorage::get_line(env, line_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let authorized = match line.vault_id {
+            Some(vault_id) => {
+                AdminStorage::is_admin(env, caller)
+                    || VaultStorage::get_vault(env, vault_id)
+                        .map(|vault| vault.curator == *caller)
+                        .unwrap_or(false)
+            }
+            None => line.lender == *caller,
+        };
+        if !authorized {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        Ok(line)
+    }
+
+    /// Errors with `OperationNotAllowed` once the business holds
+    /// `MAX_CREDIT_LINES_PER_BUSINESS` Active lines.
+    fn require_line_capacity(env: &Env, business: &Address) -> Result<(), QuickLendXError> {
+        let active = CreditLineStorage::get_lines_by_business(env, business)
+            .iter()
+            .filter(|line| line.status == CreditLineStatus::Active)
+            .count() as u32;
+        if active >= MAX_CREDIT_LINES_PER_BUSINESS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+
+    fn require_verified_business(env: &Env, business: &Address) -> Result<(), QuickLendXError> {
+        match get_business_verification_status(env, business) {
+            Some(verification) if verification.status == BusinessVerificationStatus::Verified => {
+                Ok(())
+            }
+            _ => Err(QuickLendXError::BusinessNotVerified),
+        }
+    }
+
+    fn validate_terms(env: &Env, terms: &CreditLineTerms) -> Result<(), QuickLendXError> {
+        if terms.limit <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.advance_bps == 0 || terms.advance_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.return_bps < terms.advance_bps {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.expires_at <= env.ledger().timestamp() {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::admin::AdminStorage;
+use crate::distribution::ProgressiveDistribution;
 use crate::errors::QuickLendXError;
 use crate::events::{
     emit_dispute_created, emit_dispute_resolved, emit_dispute_under_review, emit_insurance_claimed,
@@ -8,10 +9,15 @@ use crate::init::ProtocolInitializer;
 use crate::investment::{InvestmentStatus, InvestmentStorage};
 use crate::invoice::{Dispute, DisputeStatus, InvoiceStatus, InvoiceStorage};
 use crate::notifications::NotificationSystem;
+use crate::pool::InvoicePools;
 use crate::protocol_limits::{
     check_string_length, MAX_DISPUTE_EVIDENCE_LENGTH, MAX_DISPUTE_REASON_LENGTH,
     MAX_DISPUTE_RESOLUTION_LENGTH,
 };
+use crate::receipt::Receipts;
+use crate::recourse::{Recourse, RecourseClaim};
+use crate::settlement::return_third_party_payments;
+use crate::vault::LiquidityVaults;
 use soroban_sdk::{Address, BytesN, Env, String, Vec};
 
 /// Default grace period in seconds (7 days)
@@ -99,8 +105,12 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
     // Emit expiration event
     emit_invoice_expired(env, &invoice);
 
-    // Update investment status and process insurance claims
-    if let Some(mut investment) = InvestmentStorage::get_investment_by_invoice(env, invoice_id) {
+    // Update investment status and process insurance claims for every participant
+    let mut recourse_claims = Vec::new(env);
+    for mut investment in InvestmentStorage::get_investments_by_invoice(env, invoice_id).iter() {
+        if investment.status != InvestmentStatus::Active {
+            continue;
+        }
         investment.status = InvestmentStatus::Defaulted;
 
         let claim_details = investment
@@ -115,6 +125,10 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
 
         InvestmentStorage::update_investment(env, &investment);
 
+        let covered = claim_details
+            .as_ref()
+            .map(|(_, amount)| *amount)
+            .unwrap_or(0);
         if let Some((provider, coverage_amount)) = claim_details {
             emit_insurance_claimed(
                 env,
@@ -124,8 +138,26 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
                 coverage_amount,
             );
         }
+        Receipts::redeem(env, &investment.investment_id);
+
+        // Principal already recovered through progressive distribution is not lost
+        let recovered = ProgressiveDistribution::principal_recovered(env, &investment);
+        let unrecovered = investment.amount.saturating_sub(recovered);
+        let uncovered = unrecovered.saturating_sub(covered).max(0);
+        InvoicePools::record_loss(env, &investment, uncovered);
+        LiquidityVaults::record_loss(env, &investment);
+        recourse_claims.push_back(RecourseClaim {
+            investment_id: investment.investment_id.clone(),
+            amount: uncovered,
+            repaid: 0,
+        });
     }
 
+    // Recourse invoices leave the business owing investors the uncovered principal
+    Recourse::open_obligation(env, &invoice, recourse_claims);
+    // Third-party payments held for settlement go back to their payers
+    return_third_party_payments(env, &invoice)?;
+
     // Emit default event
     emit_invoice_defaulted(env, &invoice);
 
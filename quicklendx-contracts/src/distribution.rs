@@ -0,0 +1,189 @@
+//! Progressive distribution of partial payments.
+//!
+//! By default partial payments are only recorded, and investors are paid once the invoice
+//! settles. A business can instead opt an invoice into progressive distribution before it
+//! is funded: every partial payment that does not complete the invoice is then collected
+//! into the contract and paid out to the positions pro rata right away. Each position
+//! recovers its principal first and earns its return after, with the platform fee taken
+//! from profit only, so settlement pays out whatever is still owed and a default only
+//! loses the principal not yet recovered.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::credit_line::CreditLines;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_platform_fee_routed, emit_progressive_distribution, emit_progressive_distribution_set,
+};
+use crate::fees::FeeManager;
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::payments::transfer_funds;
+use crate::receipt::Receipts;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum DistributionKey {
+    ProgressiveDistribution(BytesN<32>),
+    PositionDistributed(BytesN<32>),
+}
+
+pub struct ProgressiveDistribution;
+
+impl ProgressiveDistribution {
+    pub fn is_enabled(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DistributionKey::ProgressiveDistribution(
+                invoice_id.clone(),
+            ))
+            .unwrap_or(false)
+    }
+
+    /// Gross amount of payments already paid out to a position, platform fee included.
+    pub fn distributed(env: &Env, investment_id: &BytesN<32>) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DistributionKey::PositionDistributed(investment_id.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Principal a position has recovered through progressive distribution.
+    pub fn principal_recovered(env: &Env, investment: &Investment) -> i128 {
+        Self::distributed(env, &investment.investment_id).min(investment.amount)
+    }
+
+    /// Opt an invoice in or out of progressive distribution (business only, before funding
+    /// and before any bid is placed on the terms it changes).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    pub fn set(env: &Env, invoice_id: &BytesN<32>, enabled: bool) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let key = DistributionKey::ProgressiveDistribution(invoice_id.clone());
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        emit_progressive_distribution_set(env, invoice_id, enabled);
+        Ok(())
+    }
+
+    /// Split the gross payments owed to a position into the holders' return and the
+    /// platform fee on its profit.
+    pub fn return_and_fee(
+        env: &Env,
+        principal: i128,
+        gross: i128,
+    ) -> Result<(i128, i128), QuickLendXError> {
+        match FeeManager::calculate_platform_fee(env, principal, gross) {
+            Ok(result) => Ok(result),
+            // Backward-compatible fallback for environments/tests without fee config.
+            Err(QuickLendXError::StorageKeyNotFound) => {
+                Ok(crate::profits::calculate_profit(env, principal, gross))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// The part of a position's return and platform fee not paid out yet, once its gross
+    /// payments reach `gross`.
+    pub fn outstanding_return_and_fee(
+        env: &Env,
+        investment: &Investment,
+        gross: i128,
+    ) -> Result<(i128, i128), QuickLendXError> {
+        let distributed = Self::distributed(env, &investment.investment_id);
+        let (total_return, total_fee) = Self::return_and_fee(env, investment.amount, gross)?;
+        if distributed <= 0 {
+            return Ok((total_return, total_fee));
+        }
+        let (paid_return, paid_fee) = Self::return_and_fee(env, investment.amount, distributed)?;
+        Ok((
+            total_return.saturating_sub(paid_return),
+            total_fee.saturating_sub(paid_fee),
+        ))
+    }
+
+    /// Collect a partial payment from `payer` and pay it out to the invoice's positions
+    /// pro rata by principal; the last position absorbs rounding. Restores the credit of
+    /// a line the invoice was drawn on by the amount collected.
+    pub fn distribute(
+        env: &Env,
+        invoice: &Invoice,
+        payer: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let mut investments = Vec::new(env);
+        let mut funded_total: i128 = 0;
+        for investment in InvestmentStorage::get_investments_by_invoice(env, &invoice.id).iter() {
+            if investment.status == InvestmentStatus::Active {
+                funded_total = funded_total.saturating_add(investment.amount);
+                investments.push_back(investment);
+            }
+        }
+        if funded_total <= 0 {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+
+        let contract = env.current_contract_address();
+        transfer_funds(env, &invoice.currency, payer, &contract, amount)?;
+        CreditLines::record_repayment(env, &invoice.id, amount);
+
+        let mut allocated: i128 = 0;
+        let mut investor_total: i128 = 0;
+        let mut platform_fee: i128 = 0;
+        let last_index = investments.len() - 1;
+        for (index, investment) in investments.iter().enumerate() {
+            let share = if index as u32 == last_index {
+                amount - allocated
+            } else {
+                amount.saturating_mul(investment.amount) / funded_total
+            };
+            allocated = allocated.saturating_add(share);
+
+            let distributed = Self::distributed(env, &investment.investment_id);
+            let gross = distributed.saturating_add(share);
+            let (holder_share, fee) = Self::outstanding_return_and_fee(env, &investment, gross)?;
+            if holder_share > 0 {
+                Receipts::pay_holders(
+                    env,
+                    &invoice.currency,
+                    &contract,
+                    &investment,
+                    holder_share,
+                )?;
+            }
+            env.storage().persistent().set(
+                &DistributionKey::PositionDistributed(investment.investment_id.clone()),
+                &gross,
+            );
+            investor_total = investor_total.saturating_add(holder_share);
+            platform_fee = platform_fee.saturating_add(fee);
+        }
+
+        if platform_fee > 0 {
+            let fee_recipient =
+                FeeManager::route_platform_fee(env, &invoice.currency, &contract, platform_fee)?;
+            emit_platform_fee_routed(env, &invoice.id, &fee_recipient, platform_fee);
+        }
+        emit_progressive_distribution(env, invoice, amount, investor_total, platform_fee);
+        Ok(())
+    }
+}
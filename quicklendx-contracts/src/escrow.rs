@@ -5,11 +5,20 @@
 
 use crate::admin::AdminStorage;
 use crate::bid::{BidStatus, BidStorage};
+use crate::credit_line::CreditLines;
 use crate::errors::QuickLendXError;
+use crate::escrow_terms::EscrowReleaseTerms;
 use crate::events::{emit_escrow_refunded, emit_invoice_funded};
 use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
-use crate::invoice::{InvoiceStatus, InvoiceStorage};
-use crate::payments::{create_escrow, refund_escrow};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::milestone::EscrowMilestones;
+use crate::payments::{create_escrow, refund_escrow, EscrowStorage};
+use crate::pool::InvoicePools;
+use crate::receipt::{ReceiptStorage, Receipts};
+use crate::recourse::{Recourse, RecourseClaim};
+use crate::sealed_bid::SealedBidding;
+use crate::settlement::return_third_party_payments;
+use crate::vault::LiquidityVaults;
 use soroban_sdk::{Address, BytesN, Env, Vec};
 
 /// Accept a bid and fund the invoice: transfer in from investor, create escrow, update state.
@@ -22,6 +31,10 @@ use soroban_sdk::{Address, BytesN, Env, Vec};
 /// # Errors
 /// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `InvoiceAlreadyFunded`,
 ///   `InvoiceNotAvailableForFunding`, `Unauthorized`, or errors from `create_escrow`
+/// * `OperationNotAllowed` if the bid awaits the debtor's confirmation of the invoice, or
+///   the invoice is reserved for an invoice pool, or a vault bid would exceed the vault's
+///   business exposure cap
+/// * `InsufficientFunds` if a vault bid is no longer covered by the vault's idle liquidity
 pub fn accept_bid_and_fund(
     env: &Env,
     invoice_id: &BytesN<32>,
@@ -63,6 +76,12 @@ pub fn accept_bid_and_fund(
         return Err(QuickLendXError::InvalidStatus);
     }
 
+    // Sealed-bid invoices cannot be funded before the reveal window closes
+    SealedBidding::require_bidding_closed(env, invoice_id)?;
+    BidStorage::require_debtor_condition_met(env, &invoice, &bid)?;
+    InvoicePools::require_unpooled(env, invoice_id)?;
+    LiquidityVaults::allocate(env, &invoice, &bid)?;
+
     // 5. Lock funds in escrow
     // This calls payments::create_escrow which calls token transfer and emits emit_escrow_created
     let escrow_id = create_escrow(
@@ -108,6 +127,7 @@ pub fn accept_bid_and_fund(
         insurance: Vec::new(env),
     };
     InvestmentStorage::store_investment(env, &investment);
+    Receipts::mint(env, &investment, &escrow_id);
 
     // 7. Events
     emit_invoice_funded(env, invoice_id, &bid.investor, bid.bid_amount);
@@ -143,15 +163,85 @@ pub fn refund_escrow_funds(
     // Explicitly require auth from the caller
     caller.require_auth();
 
+    refund_funded_invoice(env, &mut invoice, caller)
+}
+
+/// Reclaim escrowed funds as an investor once the invoice's escrow terms let held funds
+/// be refunded.
+///
+/// Every held slice is refunded, as with `refund_escrow_funds`.
+///
+/// # Errors
+/// * `InvoiceNotFound` if the invoice does not exist
+/// * `InvalidStatus` if the invoice is not Funded, or its escrow was already released
+/// * `Unauthorized` if `investor` has no held escrow slice on the invoice
+/// * `OperationNotAllowed` if the escrow terms have no refund timeout, or it has not
+///   passed yet
+pub fn reclaim_escrow_funds(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    investor: &Address,
+) -> Result<(), QuickLendXError> {
+    investor.require_auth();
+    let mut invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+
+    let held = EscrowStorage::get_held_escrows_by_invoice(env, invoice_id);
+    if held.is_empty() {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let escrow = held
+        .iter()
+        .find(|escrow| escrow.investor == *investor)
+        .ok_or(QuickLendXError::Unauthorized)?;
+    let available_at = EscrowReleaseTerms::refund_available_at(env, invoice_id, escrow.created_at)
+        .ok_or(QuickLendXError::OperationNotAllowed)?;
+    if env.ledger().timestamp() < available_at {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    refund_funded_invoice(env, &mut invoice, investor)
+}
+
+/// Refund every held escrow slice of a funded invoice and mark it Refunded.
+///
+/// Only the balance each slice still holds is refunded. Escrow already released through
+/// milestones stays owed by the business as an obligation, and those positions stay
+/// Active until it is repaid.
+fn refund_funded_invoice(
+    env: &Env,
+    invoice: &mut Invoice,
+    caller: &Address,
+) -> Result<(), QuickLendXError> {
+    let invoice_id = &invoice.id.clone();
+
     // 3. State check
     // Invoice must be in Funded status to be eligible for refund
     if invoice.status != InvoiceStatus::Funded {
         return Err(QuickLendXError::InvalidStatus);
     }
 
-    // 4. Retrieve Escrow
-    let escrow = crate::payments::EscrowStorage::get_escrow_by_invoice(env, invoice_id)
-        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    // 4. Retrieve Escrow slices (one per investor for syndicated invoices), what each
+    // still holds and what its milestones already released to the business
+    let mut held = Vec::new(env);
+    let mut released_claims = Vec::new(env);
+    for escrow in EscrowStorage::get_held_escrows_by_invoice(env, invoice_id).iter() {
+        let amount = EscrowMilestones::held_balance(env, &escrow);
+        let released = escrow.amount.saturating_sub(amount);
+        if released > 0 {
+            let investment_id = ReceiptStorage::get_investment_for_escrow(env, &escrow.escrow_id)
+                .ok_or(QuickLendXError::StorageKeyNotFound)?;
+            released_claims.push_back(RecourseClaim {
+                investment_id,
+                amount: released,
+                repaid: 0,
+            });
+        }
+        held.push_back((escrow, amount));
+    }
 
     // 5. Transfer funds and update escrow state
     // This calls payments::refund_escrow which handles the token transfer and status update
@@ -162,37 +252,41 @@ pub fn refund_escrow_funds(
     // Update Invoice status to Refunded
     let previous_status = invoice.status.clone();
     invoice.mark_as_refunded(env, caller.clone());
-    InvoiceStorage::update_invoice(env, &invoice);
+    InvoiceStorage::update_invoice(env, invoice);
 
     // Update status indices
     InvoiceStorage::remove_from_status_invoices(env, &previous_status, invoice_id);
     InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Refunded, invoice_id);
 
-    // Update Bid status to Cancelled (find the accepted bid first)
-    // In our protocol, a Funded invoice has exactly one Accepted bid
+    // Update Bid status to Cancelled for every accepted bid
     let bids = BidStorage::get_bid_records_for_invoice(env, invoice_id);
     for mut bid in bids.iter() {
         if bid.status == BidStatus::Accepted {
             bid.status = BidStatus::Cancelled;
             BidStorage::update_bid(env, &bid);
-            break;
         }
     }
 
-    // Update Investment status to Refunded
-    if let Some(mut investment) = InvestmentStorage::get_investment_by_invoice(env, invoice_id) {
-        investment.status = InvestmentStatus::Refunded;
-        InvestmentStorage::update_investment(env, &investment);
+    // Update Investment status to Refunded, except for positions still owed released escrow
+    for mut investment in InvestmentStorage::get_investments_by_invoice(env, invoice_id).iter() {
+        let still_owed = released_claims
+            .iter()
+            .any(|claim| claim.investment_id == investment.investment_id);
+        if investment.status == InvestmentStatus::Active && !still_owed {
+            investment.status = InvestmentStatus::Refunded;
+            InvestmentStorage::update_investment(env, &investment);
+            Receipts::redeem(env, &investment.investment_id);
+        }
     }
+    Recourse::open_refund_obligation(env, invoice, released_claims);
+    // The lender got its principal back, so a credit line draw no longer uses credit
+    CreditLines::release_draw(env, invoice_id);
+    return_third_party_payments(env, invoice)?;
 
     // 7. Emit events
-    emit_escrow_refunded(
-        env,
-        &escrow.escrow_id,
-        invoice_id,
-        &escrow.investor,
-        escrow.amount,
-    );
+    for (escrow, amount) in held.iter() {
+        emit_escrow_refunded(env, &escrow.escrow_id, invoice_id, &escrow.investor, amount);
+    }
 
     Ok(())
 }
@@ -0,0 +1,115 @@
+//! Release conditions and timeout refunds for escrowed funding.
+//!
+//! Before an invoice is funded, its business can attach escrow terms to it: a condition
+//! the escrow must meet before the funds are released to the business, and a timeout
+//! after funding past which an investor can reclaim the funds while they are still held.
+//! Invoices without terms keep the default: released on request, no timeout.
+
+use soroban_sdk::{contracttype, BytesN, Env};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::emit_escrow_terms_set;
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum EscrowTermsKey {
+    EscrowTerms(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReleaseCondition {
+    /// Released whenever a release is requested.
+    OnRequest,
+    /// Released when the admin verifies the funded invoice.
+    InvoiceVerified,
+    /// Released once the invoice's debtor has confirmed it.
+    DebtorConfirmed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowTerms {
+    pub release_condition: ReleaseCondition,
+    /// Seconds after funding from which investors can reclaim held funds; 0 for never.
+    pub refund_timeout: u64,
+}
+
+impl EscrowTerms {
+    pub fn default_terms() -> Self {
+        EscrowTerms {
+            release_condition: ReleaseCondition::OnRequest,
+            refund_timeout: 0,
+        }
+    }
+}
+
+pub struct EscrowReleaseTerms;
+
+impl EscrowReleaseTerms {
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> EscrowTerms {
+        env.storage()
+            .persistent()
+            .get(&EscrowTermsKey::EscrowTerms(invoice_id.clone()))
+            .unwrap_or_else(EscrowTerms::default_terms)
+    }
+
+    /// Attach escrow terms to an invoice (business only, before funding and before any
+    /// bid is placed on the terms they replace).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids, or the release waits
+    ///   on a debtor confirmation and the invoice has no debtor
+    pub fn set(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        terms: &EscrowTerms,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if terms.release_condition == ReleaseCondition::DebtorConfirmed && invoice.debtor.is_none()
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&EscrowTermsKey::EscrowTerms(invoice_id.clone()), terms);
+        emit_escrow_terms_set(env, invoice_id, terms);
+        Ok(())
+    }
+
+    /// Whether the invoice's escrow may be released now; `admin_verified` is set when the
+    /// release comes from the admin verifying the funded invoice.
+    pub fn is_releasable(env: &Env, invoice: &Invoice, admin_verified: bool) -> bool {
+        match Self::get(env, &invoice.id).release_condition {
+            ReleaseCondition::OnRequest => true,
+            ReleaseCondition::InvoiceVerified => admin_verified,
+            ReleaseCondition::DebtorConfirmed => invoice.is_debtor_confirmed(),
+        }
+    }
+
+    /// When investors can start reclaiming an escrow created at `created_at`, if ever.
+    pub fn refund_available_at(env: &Env, invoice_id: &BytesN<32>, created_at: u64) -> Option<u64> {
+        let timeout = Self::get(env, invoice_id).refund_timeout;
+        if timeout == 0 {
+            None
+        } else {
+            Some(created_at.saturating_add(timeout))
+        }
+    }
+}
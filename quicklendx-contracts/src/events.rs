@@ -1,9 +1,28 @@
-use crate::bid::Bid;
+use crate::auto_accept::AutoAcceptPolicy;
+use crate::batch_payment::BatchPaymentResult;
+use crate::bid::{Bid, BidAmendment};
+use crate::credit_line::CreditLine;
+use crate::errors::QuickLendXError;
+use crate::escrow_terms::EscrowTerms;
+use crate::installment::Installment;
+use crate::investment::Investment;
 use crate::invoice::{Invoice, InvoiceMetadata};
+use crate::keeper::KeeperConfig;
+use crate::late_payment::LateInterestConfig;
+use crate::listing::ListingTerms;
+use crate::milestone::Milestone;
+use crate::negotiation::{Negotiation, NegotiationParty};
 use crate::payments::Escrow;
+use crate::pool::{InvoicePool, TrancheKind};
+use crate::pricing::TimePricing;
 use crate::profits::PlatformFeeConfig;
+use crate::recourse::{FactoringMode, RecourseObligation};
+use crate::reverse_factoring::ApprovedPayable;
+use crate::secondary_market::PositionListing;
+use crate::standing_order::StandingOrder;
+use crate::vault::LiquidityVault;
 use crate::verification::InvestorVerification;
-use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol, Vec};
 
 // Standardized event topics for off-chain indexers. These constants mirror the
 // short-symbol topics used in `env.events().publish` so subscribers can
@@ -86,6 +105,26 @@ pub fn emit_invoice_metadata_cleared(env: &Env, invoice: &Invoice) {
     );
 }
 
+pub fn emit_invoice_debtor_set(env: &Env, invoice: &Invoice, debtor: &Address) {
+    env.events().publish(
+        (symbol_short!("inv_dbt"),),
+        (invoice.id.clone(), invoice.business.clone(), debtor.clone()),
+    );
+}
+
+pub fn emit_invoice_confirmed(env: &Env, invoice: &Invoice, debtor: &Address) {
+    env.events().publish(
+        (symbol_short!("inv_cnf"),),
+        (
+            invoice.id.clone(),
+            debtor.clone(),
+            invoice.amount,
+            invoice.due_date,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
 pub fn emit_investor_verified(env: &Env, verification: &InvestorVerification) {
     env.events().publish(
         (symbol_short!("inv_veri"),),
@@ -322,6 +361,35 @@ pub fn emit_bid_withdrawn(env: &Env, bid: &Bid) {
     );
 }
 
+/// Emit event when a placed bid is amended in place
+pub fn emit_bid_amended(env: &Env, bid: &Bid, amendment: &BidAmendment) {
+    env.events().publish(
+        (symbol_short!("bid_amd"),),
+        (
+            bid.bid_id.clone(),
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            amendment.previous_amount,
+            bid.bid_amount,
+            bid.expected_return,
+            bid.expiration_timestamp,
+        ),
+    );
+}
+
+/// Emit event when an investor makes a bid conditional on debtor confirmation, or lifts it
+pub fn emit_bid_condition_set(env: &Env, bid: &Bid, requires_debtor_confirmation: bool) {
+    env.events().publish(
+        (symbol_short!("bid_cond"),),
+        (
+            bid.bid_id.clone(),
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            requires_debtor_confirmation,
+        ),
+    );
+}
+
 /// Emit event when a bid is accepted
 pub fn emit_bid_accepted(env: &Env, bid: &Bid, invoice_id: &BytesN<32>, business: &Address) {
     env.events().publish(
@@ -515,6 +583,24 @@ pub fn emit_invoice_funded(env: &Env, invoice_id: &BytesN<32>, investor: &Addres
     );
 }
 
+/// Emit event when several bids jointly fund an invoice
+pub fn emit_invoice_syndicated(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    participants: u32,
+    total_funded: i128,
+) {
+    env.events().publish(
+        (symbol_short!("inv_synd"),),
+        (
+            invoice_id.clone(),
+            participants,
+            total_funded,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
 // Analytics Events
 
 /// Emit event when platform metrics are updated
@@ -786,3 +872,842 @@ pub fn emit_profit_fee_breakdown(
         ),
     );
 }
+
+// Sealed Bid Events
+
+/// Emit event when an invoice is put up for a sealed-bid auction
+pub fn emit_sealed_auction_opened(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    commit_deadline: u64,
+    reveal_deadline: u64,
+) {
+    env.events().publish(
+        (symbol_short!("seal_opn"),),
+        (invoice_id.clone(), commit_deadline, reveal_deadline),
+    );
+}
+
+/// Emit event when an investor commits (or re-commits) a hidden bid
+pub fn emit_bid_committed(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    investor: &Address,
+    commitment: &BytesN<32>,
+    committed_at: u64,
+) {
+    env.events().publish(
+        (symbol_short!("bid_cmt"),),
+        (
+            invoice_id.clone(),
+            investor.clone(),
+            commitment.clone(),
+            committed_at,
+        ),
+    );
+}
+
+/// Emit event when a committed bid is revealed and placed
+pub fn emit_bid_revealed(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    investor: &Address,
+    bid_id: &BytesN<32>,
+) {
+    env.events().publish(
+        (symbol_short!("bid_rvl"),),
+        (invoice_id.clone(), investor.clone(), bid_id.clone()),
+    );
+}
+
+/// Emit event when a commitment expires without being revealed
+pub fn emit_commitment_expired(env: &Env, invoice_id: &BytesN<32>, investor: &Address) {
+    env.events().publish(
+        (symbol_short!("cmt_exp"),),
+        (invoice_id.clone(), investor.clone()),
+    );
+}
+
+// Listing Events
+
+/// Emit event when a business sets the listing terms of an invoice
+pub fn emit_listing_terms_set(env: &Env, invoice_id: &BytesN<32>, terms: &ListingTerms) {
+    env.events().publish(
+        (symbol_short!("lst_set"),),
+        (
+            invoice_id.clone(),
+            terms.min_advance,
+            terms.max_expected_return,
+            terms.bidding_deadline,
+            terms.buy_now_price,
+        ),
+    );
+}
+
+/// Emit event when a business removes the listing terms of an invoice
+pub fn emit_listing_terms_cleared(env: &Env, invoice_id: &BytesN<32>) {
+    env.events()
+        .publish((symbol_short!("lst_clr"),), (invoice_id.clone(),));
+}
+
+// Auto-Accept Events
+
+/// Emit event when a business sets an auto-accept policy, business-wide when
+/// `invoice_id` is `None`
+pub fn emit_auto_accept_set(
+    env: &Env,
+    business: &Address,
+    invoice_id: Option<&BytesN<32>>,
+    policy: &AutoAcceptPolicy,
+) {
+    env.events().publish(
+        (symbol_short!("aa_set"),),
+        (
+            business.clone(),
+            invoice_id.cloned(),
+            policy.min_advance_bps,
+            policy.max_return_bps,
+        ),
+    );
+}
+
+/// Emit event when a business revokes an auto-accept policy, business-wide when
+/// `invoice_id` is `None`
+pub fn emit_auto_accept_cleared(env: &Env, business: &Address, invoice_id: Option<&BytesN<32>>) {
+    env.events().publish(
+        (symbol_short!("aa_clr"),),
+        (business.clone(), invoice_id.cloned()),
+    );
+}
+
+// Standing Order Events
+
+/// Emit event when an investor creates a standing order
+pub fn emit_standing_order_created(env: &Env, order: &StandingOrder) {
+    env.events().publish(
+        (symbol_short!("so_new"),),
+        (
+            order.order_id,
+            order.investor.clone(),
+            order.terms.currency.clone(),
+            order.terms.max_exposure,
+        ),
+    );
+}
+
+/// Emit event when an investor cancels a standing order
+pub fn emit_standing_order_cancelled(env: &Env, order: &StandingOrder) {
+    env.events().publish(
+        (symbol_short!("so_cncl"),),
+        (order.order_id, order.investor.clone()),
+    );
+}
+
+/// Emit event when a bid is placed on behalf of a standing order
+pub fn emit_standing_order_bid(
+    env: &Env,
+    order_id: u64,
+    invoice_id: &BytesN<32>,
+    bid_id: &BytesN<32>,
+) {
+    env.events().publish(
+        (symbol_short!("so_bid"),),
+        (order_id, invoice_id.clone(), bid_id.clone()),
+    );
+}
+
+/// Emit event when a standing order is skipped for an invoice because its bid was
+/// rejected; `error` is the `QuickLendXError` code
+pub fn emit_standing_order_skipped(
+    env: &Env,
+    order_id: u64,
+    invoice_id: &BytesN<32>,
+    error: QuickLendXError,
+) {
+    env.events().publish(
+        (symbol_short!("so_skip"),),
+        (order_id, invoice_id.clone(), error as u32),
+    );
+}
+
+// Negotiation Events
+
+/// Emit event when a business or investor makes a counter-offer on a bid
+pub fn emit_counter_offer_made(env: &Env, negotiation: &Negotiation) {
+    env.events().publish(
+        (symbol_short!("neg_ofr"),),
+        (
+            negotiation.bid_id.clone(),
+            negotiation.round,
+            negotiation.proposed_by,
+            negotiation.bid_amount,
+            negotiation.expected_return,
+            negotiation.expires_at,
+        ),
+    );
+}
+
+/// Emit event when `party` accepts the other side's counter-offer
+pub fn emit_counter_offer_accepted(env: &Env, negotiation: &Negotiation, party: NegotiationParty) {
+    env.events().publish(
+        (symbol_short!("neg_acc"),),
+        (
+            negotiation.bid_id.clone(),
+            negotiation.round,
+            party,
+            negotiation.bid_amount,
+            negotiation.expected_return,
+        ),
+    );
+}
+
+/// Emit event when `party` ends a negotiation without agreement
+pub fn emit_counter_offer_rejected(env: &Env, negotiation: &Negotiation, party: NegotiationParty) {
+    env.events().publish(
+        (symbol_short!("neg_rej"),),
+        (negotiation.bid_id.clone(), negotiation.round, party),
+    );
+}
+
+// Secondary Market Events
+
+/// Emit event when an investor lists a position for sale
+pub fn emit_position_listed(env: &Env, listing: &PositionListing) {
+    env.events().publish(
+        (symbol_short!("pos_list"),),
+        (
+            listing.investment_id.clone(),
+            listing.invoice_id.clone(),
+            listing.seller.clone(),
+            listing.asking_price,
+        ),
+    );
+}
+
+/// Emit event when a seller withdraws a position listing
+pub fn emit_position_unlisted(env: &Env, listing: &PositionListing) {
+    env.events().publish(
+        (symbol_short!("pos_cncl"),),
+        (listing.investment_id.clone(), listing.seller.clone()),
+    );
+}
+
+/// Emit event when a listed position is bought
+pub fn emit_position_sold(env: &Env, listing: &PositionListing, buyer: &Address) {
+    env.events().publish(
+        (symbol_short!("pos_sold"),),
+        (
+            listing.investment_id.clone(),
+            listing.invoice_id.clone(),
+            listing.seller.clone(),
+            buyer.clone(),
+            listing.asking_price,
+        ),
+    );
+}
+
+// Receipt Events
+
+/// Emit event when a receipt is minted to the investor of a funded position
+pub fn emit_receipt_minted(env: &Env, investment: &Investment) {
+    env.events().publish(
+        (symbol_short!("rcpt_mnt"),),
+        (
+            investment.investment_id.clone(),
+            investment.investor.clone(),
+            investment.amount,
+        ),
+    );
+}
+
+/// Emit event when a holder approves a spender for receipt units
+pub fn emit_receipt_approved(
+    env: &Env,
+    investment_id: &BytesN<32>,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    env.events().publish(
+        (symbol_short!("rcpt_apr"),),
+        (
+            investment_id.clone(),
+            from.clone(),
+            spender.clone(),
+            amount,
+            expiration_ledger,
+        ),
+    );
+}
+
+/// Emit event when a receipt holder is paid its share of a position
+pub fn emit_receipt_paid(env: &Env, investment_id: &BytesN<32>, holder: &Address, share: i128) {
+    env.events().publish(
+        (symbol_short!("rcpt_pay"),),
+        (investment_id.clone(), holder.clone(), share),
+    );
+}
+
+/// Emit event when receipt units move between holders
+pub fn emit_receipt_transferred(
+    env: &Env,
+    investment_id: &BytesN<32>,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("rcpt_xfr"),),
+        (investment_id.clone(), from.clone(), to.clone(), amount),
+    );
+}
+
+// Recourse Events
+
+/// Emit event when a business chooses the factoring mode of an invoice
+pub fn emit_factoring_mode_set(env: &Env, invoice: &Invoice, mode: FactoringMode) {
+    env.events().publish(
+        (symbol_short!("rc_mode"),),
+        (invoice.id.clone(), invoice.business.clone(), mode),
+    );
+}
+
+/// Emit event when a recourse obligation is opened against a business
+pub fn emit_recourse_opened(env: &Env, obligation: &RecourseObligation) {
+    env.events().publish(
+        (symbol_short!("rc_open"),),
+        (
+            obligation.invoice_id.clone(),
+            obligation.business.clone(),
+            obligation.total_amount,
+        ),
+    );
+}
+
+/// Emit event when a business repays part of a recourse obligation
+pub fn emit_recourse_repaid(env: &Env, obligation: &RecourseObligation, amount: i128) {
+    env.events().publish(
+        (symbol_short!("rc_pay"),),
+        (
+            obligation.invoice_id.clone(),
+            obligation.business.clone(),
+            amount,
+            obligation.outstanding(),
+        ),
+    );
+}
+
+// Reverse Factoring Events
+
+/// Emit event when a buyer registers an approved payable
+pub fn emit_payable_registered(env: &Env, payable: &ApprovedPayable) {
+    env.events().publish(
+        (symbol_short!("rf_reg"),),
+        (
+            payable.payable_id,
+            payable.buyer.clone(),
+            payable.supplier.clone(),
+            payable.amount,
+            payable.due_date,
+        ),
+    );
+}
+
+/// Emit event when a buyer cancels a payable
+pub fn emit_payable_cancelled(env: &Env, payable: &ApprovedPayable) {
+    env.events().publish(
+        (symbol_short!("rf_cncl"),),
+        (payable.payable_id, payable.buyer.clone()),
+    );
+}
+
+/// Emit event when a supplier sells a payable as an invoice
+pub fn emit_payable_sold(env: &Env, payable: &ApprovedPayable, invoice_id: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("rf_sold"),),
+        (
+            payable.payable_id,
+            invoice_id.clone(),
+            payable.buyer.clone(),
+            payable.supplier.clone(),
+        ),
+    );
+}
+
+// Invoice Pool Events
+
+/// Emit event when an admin packages invoices into a pool
+pub fn emit_pool_created(env: &Env, pool: &InvoicePool) {
+    env.events().publish(
+        (symbol_short!("pool_new"),),
+        (
+            pool.pool_id,
+            pool.invoice_ids.len(),
+            pool.total_size,
+            pool.senior.size,
+        ),
+    );
+}
+
+/// Emit event when an investor subscribes to a pool tranche
+pub fn emit_pool_subscribed(
+    env: &Env,
+    pool_id: u64,
+    investor: &Address,
+    tranche: TrancheKind,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("pool_sub"),),
+        (pool_id, investor.clone(), tranche, amount),
+    );
+}
+
+/// Emit event when an admin cancels a pool before funding
+pub fn emit_pool_cancelled(env: &Env, pool_id: u64, admin: &Address) {
+    env.events()
+        .publish((symbol_short!("pool_cncl"),), (pool_id, admin.clone()));
+}
+
+/// Emit event when an unfilled pool expires
+pub fn emit_pool_expired(env: &Env, pool: &InvoicePool) {
+    env.events().publish(
+        (symbol_short!("pool_exp"),),
+        (pool.pool_id, pool.subscription_deadline),
+    );
+}
+
+/// Emit event when an investor claims pool proceeds
+pub fn emit_pool_claimed(env: &Env, pool_id: u64, investor: &Address, senior: i128, junior: i128) {
+    env.events().publish(
+        (symbol_short!("pool_clm"),),
+        (pool_id, investor.clone(), senior, junior),
+    );
+}
+
+/// Emit event when a payment on a pool-held receipt is credited to the pool
+pub fn emit_pool_proceeds(env: &Env, pool: &InvoicePool, invoice_id: &BytesN<32>, amount: i128) {
+    env.events().publish(
+        (symbol_short!("pool_in"),),
+        (pool.pool_id, invoice_id.clone(), amount, pool.collected),
+    );
+}
+
+/// Emit event when a pooled investment defaults
+pub fn emit_pool_loss(env: &Env, pool: &InvoicePool, invoice_id: &BytesN<32>, loss: i128) {
+    env.events().publish(
+        (symbol_short!("pool_loss"),),
+        (pool.pool_id, invoice_id.clone(), loss),
+    );
+}
+
+/// Emit event when a filled pool funds its invoices
+pub fn emit_pool_funded(env: &Env, pool: &InvoicePool) {
+    env.events().publish(
+        (symbol_short!("pool_fnd"),),
+        (pool.pool_id, pool.total_size),
+    );
+}
+
+// Liquidity Vault Events
+
+/// Emit event when an admin creates a liquidity vault
+pub fn emit_vault_created(env: &Env, vault: &LiquidityVault) {
+    env.events().publish(
+        (symbol_short!("vlt_new"),),
+        (
+            vault.vault_id,
+            vault.currency.clone(),
+            vault.curator.clone(),
+            vault.max_idle,
+        ),
+    );
+}
+
+/// Emit event when a vault's allocation policy is replaced
+pub fn emit_vault_policy_set(env: &Env, vault_id: u64, caller: &Address) {
+    env.events()
+        .publish((symbol_short!("vlt_pol"),), (vault_id, caller.clone()));
+}
+
+/// Emit event when an investor deposits into a vault
+pub fn emit_vault_deposit(
+    env: &Env,
+    vault_id: u64,
+    depositor: &Address,
+    amount: i128,
+    shares: i128,
+) {
+    env.events().publish(
+        (symbol_short!("vlt_dep"),),
+        (vault_id, depositor.clone(), amount, shares),
+    );
+}
+
+/// Emit event when a holder redeems vault shares
+pub fn emit_vault_withdrawal(
+    env: &Env,
+    vault_id: u64,
+    holder: &Address,
+    shares: i128,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("vlt_wdr"),),
+        (vault_id, holder.clone(), shares, amount),
+    );
+}
+
+/// Emit event when a vault bids on an invoice
+pub fn emit_vault_bid(env: &Env, vault_id: u64, bid: &Bid) {
+    env.events().publish(
+        (symbol_short!("vlt_bid"),),
+        (
+            vault_id,
+            bid.invoice_id.clone(),
+            bid.bid_id.clone(),
+            bid.bid_amount,
+        ),
+    );
+}
+
+/// Emit event when a vault whose policy matches an invoice does not bid on it; `error`
+/// is the `QuickLendXError` code
+pub fn emit_vault_bid_skipped(
+    env: &Env,
+    vault_id: u64,
+    invoice_id: &BytesN<32>,
+    error: QuickLendXError,
+) {
+    env.events().publish(
+        (symbol_short!("vlt_skip"),),
+        (vault_id, invoice_id.clone(), error as u32),
+    );
+}
+
+/// Emit event when a preapproved vault bid is left for the business to accept because
+/// it could not be funded; `error` is the `QuickLendXError` code
+pub fn emit_vault_funding_skipped(env: &Env, bid: &Bid, error: QuickLendXError) {
+    env.events().publish(
+        (symbol_short!("vlt_fskip"),),
+        (bid.bid_id.clone(), bid.invoice_id.clone(), error as u32),
+    );
+}
+
+/// Emit event when an accepted vault bid commits the vault's liquidity
+pub fn emit_vault_funded(env: &Env, vault_id: u64, bid: &Bid) {
+    env.events().publish(
+        (symbol_short!("vlt_fund"),),
+        (vault_id, bid.invoice_id.clone(), bid.bid_amount),
+    );
+}
+
+/// Emit event when a payment on a vault-held receipt is credited to the vault
+pub fn emit_vault_proceeds(
+    env: &Env,
+    vault_id: u64,
+    invoice_id: &BytesN<32>,
+    amount: i128,
+    principal: i128,
+) {
+    env.events().publish(
+        (symbol_short!("vlt_in"),),
+        (vault_id, invoice_id.clone(), amount, principal),
+    );
+}
+
+/// Emit event when a defaulted vault investment is written off
+pub fn emit_vault_loss(env: &Env, vault_id: u64, invoice_id: &BytesN<32>, loss: i128) {
+    env.events().publish(
+        (symbol_short!("vlt_loss"),),
+        (vault_id, invoice_id.clone(), loss),
+    );
+}
+
+// Credit Line Events
+
+/// Emit event when a credit line is proposed to a business
+pub fn emit_credit_line_opened(env: &Env, line: &CreditLine) {
+    env.events().publish(
+        (symbol_short!("cl_open"),),
+        (
+            line.line_id,
+            line.lender.clone(),
+            line.business.clone(),
+            line.terms.limit,
+            line.terms.expires_at,
+        ),
+    );
+}
+
+/// Emit event when a business accepts a proposed credit line
+pub fn emit_credit_line_accepted(env: &Env, line: &CreditLine) {
+    env.events().publish(
+        (symbol_short!("cl_acpt"),),
+        (line.line_id, line.business.clone()),
+    );
+}
+
+/// Emit event when the lender changes a credit line's limit or expiry
+pub fn emit_credit_line_updated(env: &Env, line: &CreditLine) {
+    env.events().publish(
+        (symbol_short!("cl_upd"),),
+        (line.line_id, line.terms.limit, line.terms.expires_at),
+    );
+}
+
+/// Emit event when a credit line is closed to new draws
+pub fn emit_credit_line_closed(env: &Env, line: &CreditLine) {
+    env.events()
+        .publish((symbol_short!("cl_close"),), (line.line_id, line.utilized));
+}
+
+/// Emit event when a business draws on a credit line against an invoice
+pub fn emit_credit_line_drawn(env: &Env, line: &CreditLine, invoice_id: &BytesN<32>, amount: i128) {
+    env.events().publish(
+        (symbol_short!("cl_draw"),),
+        (line.line_id, invoice_id.clone(), amount, line.utilized),
+    );
+}
+
+/// Emit event when a payment on a pledged invoice restores credit
+pub fn emit_credit_line_repaid(
+    env: &Env,
+    line: &CreditLine,
+    invoice_id: &BytesN<32>,
+    repaid: i128,
+) {
+    env.events().publish(
+        (symbol_short!("cl_repay"),),
+        (line.line_id, invoice_id.clone(), repaid, line.utilized),
+    );
+}
+
+// Time-Based Pricing Events
+
+/// Emit event when a business prices an invoice by time
+pub fn emit_time_pricing_set(env: &Env, invoice_id: &BytesN<32>, pricing: &TimePricing) {
+    env.events().publish(
+        (symbol_short!("inv_tprc"),),
+        (invoice_id.clone(), pricing.annual_rate_bps, pricing.min_fee),
+    );
+}
+
+/// Emit event when an early settlement leaves a discount to the business
+pub fn emit_early_payment_discount(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    discount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("early_dsc"),),
+        (invoice_id.clone(), payer.clone(), discount),
+    );
+}
+
+// Late Payment Events
+
+/// Emit event when the admin sets the late-payment interest terms
+pub fn emit_late_interest_config_set(env: &Env, config: &LateInterestConfig) {
+    env.events().publish(
+        (symbol_short!("late_cfg"),),
+        (config.daily_rate_bps, config.max_interest_bps),
+    );
+}
+
+/// Emit event when a settlement collects late-payment interest
+pub fn emit_late_interest_paid(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    interest: i128,
+) {
+    env.events().publish(
+        (symbol_short!("late_int"),),
+        (invoice_id.clone(), payer.clone(), interest),
+    );
+}
+
+// Installment Events
+
+/// Emit event when a business attaches an installment schedule to an invoice
+pub fn emit_installments_set(env: &Env, invoice_id: &BytesN<32>, installments: &Vec<Installment>) {
+    env.events().publish(
+        (symbol_short!("inst_set"),),
+        (invoice_id.clone(), installments.len()),
+    );
+}
+
+/// Emit event when payments cover an installment
+pub fn emit_installment_paid(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    index: u32,
+    installment: &Installment,
+) {
+    env.events().publish(
+        (symbol_short!("inst_paid"),),
+        (invoice_id.clone(), index, installment.status.clone()),
+    );
+}
+
+/// Emit event when an installment passes its due date unpaid
+pub fn emit_installment_missed(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    index: u32,
+    installment: &Installment,
+) {
+    env.events().publish(
+        (symbol_short!("inst_miss"),),
+        (invoice_id.clone(), index, installment.amount),
+    );
+}
+
+// Third-Party Payer Events
+
+/// Emit event when a business approves or revokes a third-party payer
+pub fn emit_payer_approval_set(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    approved: bool,
+) {
+    env.events().publish(
+        (symbol_short!("payer_set"),),
+        (invoice_id.clone(), payer.clone(), approved),
+    );
+}
+
+// Progressive Distribution Events
+
+/// Emit event when a business turns progressive distribution on or off for an invoice
+pub fn emit_progressive_distribution_set(env: &Env, invoice_id: &BytesN<32>, enabled: bool) {
+    env.events()
+        .publish((symbol_short!("dist_set"),), (invoice_id.clone(), enabled));
+}
+
+/// Emit event when a partial payment is distributed to the holders of an invoice
+pub fn emit_progressive_distribution(
+    env: &Env,
+    invoice: &Invoice,
+    amount: i128,
+    investor_total: i128,
+    platform_fee: i128,
+) {
+    env.events().publish(
+        (symbol_short!("dist_pay"),),
+        (invoice.id.clone(), amount, investor_total, platform_fee),
+    );
+}
+
+// Escrow Terms Events
+
+/// Emit event when a business attaches escrow release terms to an invoice
+pub fn emit_escrow_terms_set(env: &Env, invoice_id: &BytesN<32>, terms: &EscrowTerms) {
+    env.events().publish(
+        (symbol_short!("esc_terms"),),
+        (
+            invoice_id.clone(),
+            terms.release_condition.clone(),
+            terms.refund_timeout,
+        ),
+    );
+}
+
+// Milestone Events
+
+/// Emit event when an escrow is split into milestones
+pub fn emit_milestones_set(env: &Env, escrow: &Escrow, milestones: &Vec<Milestone>) {
+    env.events().publish(
+        (symbol_short!("ms_set"),),
+        (
+            escrow.escrow_id.clone(),
+            escrow.invoice_id.clone(),
+            milestones.len(),
+        ),
+    );
+}
+
+/// Emit event when a milestone's share of the escrow is released to the business
+pub fn emit_milestone_released(env: &Env, escrow: &Escrow, index: u32, milestone: &Milestone) {
+    env.events().publish(
+        (symbol_short!("ms_rel"),),
+        (
+            escrow.escrow_id.clone(),
+            escrow.invoice_id.clone(),
+            index,
+            milestone.amount,
+        ),
+    );
+}
+
+// Auto-Debit Events
+
+/// Emit event when an obligor opts in or out of auto-debit
+pub fn emit_auto_debit_set(env: &Env, obligor: &Address, enabled: bool) {
+    env.events()
+        .publish((symbol_short!("debit_set"),), (obligor.clone(), enabled));
+}
+
+/// Emit event when an auto-debit cannot be collected from the obligor
+pub fn emit_auto_debit_failed(env: &Env, invoice_id: &BytesN<32>, obligor: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("debit_bad"),),
+        (invoice_id.clone(), obligor.clone(), amount),
+    );
+}
+
+/// Emit event when an auto-debit settles an invoice
+pub fn emit_auto_debit_collected(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    obligor: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("debit_ok"),),
+        (invoice_id.clone(), obligor.clone(), amount),
+    );
+}
+
+// Keeper Events
+
+/// Emit event when the admin sets the keeper reward terms
+pub fn emit_keeper_config_set(env: &Env, config: &KeeperConfig) {
+    env.events().publish(
+        (symbol_short!("keep_cfg"),),
+        (
+            config.currency.clone(),
+            config.bounty,
+            config.fee_share_bps,
+            config.period_cap,
+        ),
+    );
+}
+
+/// Emit event when a keeper is paid a reward
+pub fn emit_keeper_rewarded(env: &Env, keeper: &Address, amount: i128) {
+    env.events()
+        .publish((symbol_short!("keep_paid"),), (keeper.clone(), amount));
+}
+
+// Batch Payment Events
+
+/// Emit event when a payer settles a batch of invoice payments
+pub fn emit_batch_payment(env: &Env, payer: &Address, results: &Vec<BatchPaymentResult>) {
+    let mut applied_count: u32 = 0;
+    let mut applied_total: i128 = 0;
+    for result in results.iter() {
+        if result.applied > 0 {
+            applied_count += 1;
+            applied_total = applied_total.saturating_add(result.applied);
+        }
+    }
+    env.events().publish(
+        (symbol_short!("batch_pay"),),
+        (payer.clone(), results.len(), applied_count, applied_total),
+    );
+}
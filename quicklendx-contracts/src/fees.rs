@@ -24,6 +24,8 @@ pub enum FeeType {
     Platform,
     Processing,
     Verification,
+    /// Prepayment fee kept for investors out of the early-payment discount a business gets
+    /// when it settles a time-priced invoice early (see `pricing`).
     EarlyPayment,
     LatePayment,
 }
@@ -568,7 +570,8 @@ impl FeeManager {
         Ok(())
     }
 
-    /// Route platform fees to treasury if configured
+    /// Route platform fees to treasury if configured, after setting aside the keeper
+    /// reward share
     pub fn route_platform_fee(
         env: &Env,
         currency: &Address,
@@ -579,6 +582,17 @@ impl FeeManager {
             return Err(QuickLendXError::InvalidAmount);
         }
 
+        let keeper_share = crate::keeper::KeeperRewards::fee_share(env, currency, fee_amount);
+        if keeper_share > 0 {
+            let contract_address = env.current_contract_address();
+            crate::payments::transfer_funds(env, currency, from, &contract_address, keeper_share)?;
+            crate::keeper::KeeperRewards::add_to_pool(env, keeper_share);
+            if keeper_share == fee_amount {
+                return Ok(contract_address);
+            }
+        }
+        let fee_amount = fee_amount - keeper_share;
+
         if let Some(treasury_address) = Self::get_treasury_address(env) {
             // Transfer to treasury
             crate::payments::transfer_funds(env, currency, from, &treasury_address, fee_amount)?;
@@ -0,0 +1,223 @@
+//! Installment schedules for invoice repayment.
+//!
+//! Before an invoice is funded, its business can split the amount into installments with
+//! fixed due dates, the last no later than the invoice's due date. Recorded payments cover
+//! the installments in order. Each one is tracked as paid on time, paid late or missed, and
+//! a missed installment makes the invoice overdue ahead of its final due date.
+
+use soroban_sdk::{contracttype, BytesN, Env, Vec};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_installment_missed, emit_installment_paid, emit_installments_set};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+
+/// Most installments an invoice can be split into.
+pub const MAX_INSTALLMENTS: u32 = 24;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum InstallmentKey {
+    InstallmentPlan(BytesN<32>),
+}
+
+/// One installment as requested by the business: a due date and its share of the invoice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstallmentTerms {
+    pub due_date: u64,
+    pub share_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InstallmentStatus {
+    /// Not yet due and not yet covered by payments.
+    Upcoming,
+    /// Covered by payments by its due date.
+    OnTime,
+    /// Covered by payments after its due date.
+    Late,
+    /// Past its due date and not yet covered by payments.
+    Missed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Installment {
+    pub due_date: u64,
+    pub amount: i128,
+    pub paid_at: Option<u64>,
+    pub status: InstallmentStatus,
+}
+
+pub struct InstallmentStorage;
+
+impl InstallmentStorage {
+    /// Stored installments of an invoice; empty when it has no schedule.
+    pub fn get_installments(env: &Env, invoice_id: &BytesN<32>) -> Vec<Installment> {
+        env.storage()
+            .persistent()
+            .get(&InstallmentKey::InstallmentPlan(invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_installments(env: &Env, invoice_id: &BytesN<32>, installments: &Vec<Installment>) {
+        let key = InstallmentKey::InstallmentPlan(invoice_id.clone());
+        if installments.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, installments);
+        }
+    }
+}
+
+pub struct InstallmentPlans;
+
+impl InstallmentPlans {
+    /// Attach an installment schedule to an invoice, replacing any earlier one (business
+    /// only, before funding and before any bid is placed on the terms it replaces). An
+    /// empty schedule removes it. Amounts are the shares of the invoice amount, the last
+    /// installment absorbing rounding.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    /// * `InvalidAmount` if there are more than `MAX_INSTALLMENTS`, a share is zero or the
+    ///   shares do not add up to 100%
+    /// * `InvalidTimestamp` if due dates are not increasing, not in the future, or after
+    ///   the invoice's due date
+    pub fn set_schedule(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        terms: &Vec<InstallmentTerms>,
+    ) -> Result<Vec<Installment>, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if terms.len() > MAX_INSTALLMENTS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let mut installments = Vec::new(env);
+        let mut total_bps: i128 = 0;
+        let mut allocated: i128 = 0;
+        let mut previous_due = env.ledger().timestamp();
+        for (index, term) in terms.iter().enumerate() {
+            if term.share_bps == 0 {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+            if term.due_date <= previous_due || term.due_date > invoice.due_date {
+                return Err(QuickLendXError::InvalidTimestamp);
+            }
+            previous_due = term.due_date;
+            total_bps = total_bps.saturating_add(term.share_bps as i128);
+
+            let amount = if index as u32 == terms.len() - 1 {
+                invoice.amount.saturating_sub(allocated)
+            } else {
+                invoice.amount.saturating_mul(term.share_bps as i128) / BPS_DENOMINATOR
+            };
+            allocated = allocated.saturating_add(amount);
+            installments.push_back(Installment {
+                due_date: term.due_date,
+                amount,
+                paid_at: None,
+                status: InstallmentStatus::Upcoming,
+            });
+        }
+        if !terms.is_empty() && total_bps != BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        InstallmentStorage::set_installments(env, invoice_id, &installments);
+        emit_installments_set(env, invoice_id, &installments);
+        Ok(installments)
+    }
+
+    /// An invoice's installments as of now: unpaid ones past their due date show as
+    /// Missed even before `check_missed` records it.
+    pub fn get_schedule(env: &Env, invoice_id: &BytesN<32>) -> Vec<Installment> {
+        let now = env.ledger().timestamp();
+        let mut installments = InstallmentStorage::get_installments(env, invoice_id);
+        for index in 0..installments.len() {
+            let mut installment = installments.get(index).unwrap();
+            if installment.status == InstallmentStatus::Upcoming && now > installment.due_date {
+                installment.status = InstallmentStatus::Missed;
+                installments.set(index, installment);
+            }
+        }
+        installments
+    }
+
+    /// Mark the installments that `total_paid` now covers, in order, as paid on time or
+    /// late.
+    pub fn record_payment(env: &Env, invoice_id: &BytesN<32>, total_paid: i128) {
+        let mut installments = InstallmentStorage::get_installments(env, invoice_id);
+        if installments.is_empty() {
+            return;
+        }
+        let now = env.ledger().timestamp();
+        let mut covered: i128 = 0;
+        let mut changed = false;
+        for index in 0..installments.len() {
+            let mut installment = installments.get(index).unwrap();
+            covered = covered.saturating_add(installment.amount);
+            if covered > total_paid {
+                break;
+            }
+            if installment.paid_at.is_some() {
+                continue;
+            }
+            installment.paid_at = Some(now);
+            installment.status = if now <= installment.due_date {
+                InstallmentStatus::OnTime
+            } else {
+                InstallmentStatus::Late
+            };
+            emit_installment_paid(env, invoice_id, index, &installment);
+            installments.set(index, installment);
+            changed = true;
+        }
+        if changed {
+            InstallmentStorage::set_installments(env, invoice_id, &installments);
+        }
+    }
+
+    /// Record unpaid installments past their due date as Missed. Returns the due date of
+    /// the earliest missed installment, if any.
+    pub fn check_missed(env: &Env, invoice_id: &BytesN<32>) -> Option<u64> {
+        let now = env.ledger().timestamp();
+        let mut installments = InstallmentStorage::get_installments(env, invoice_id);
+        let mut earliest_missed = None;
+        let mut changed = false;
+        for index in 0..installments.len() {
+            let mut installment = installments.get(index).unwrap();
+            if installment.paid_at.is_some() || now <= installment.due_date {
+                continue;
+            }
+            earliest_missed.get_or_insert(installment.due_date);
+            if installment.status == InstallmentStatus::Upcoming {
+                installment.status = InstallmentStatus::Missed;
+                emit_installment_missed(env, invoice_id, index, &installment);
+                installments.set(index, installment);
+                changed = true;
+            }
+        }
+        if changed {
+            InstallmentStorage::set_installments(env, invoice_id, &installments);
+        }
+        earliest_missed
+    }
+}
@@ -129,6 +129,10 @@ impl InvestmentStorage {
         (symbol_short!("inv_map"), invoice_id.clone())
     }
 
+    fn invoice_list_key(invoice_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+        (symbol_short!("inv_list"), invoice_id.clone())
+    }
+
     /// Generate a unique investment ID using timestamp and counter
     pub fn generate_unique_investment_id(env: &Env) -> BytesN<32> {
         let timestamp = env.ledger().timestamp();
@@ -166,6 +170,18 @@ impl InvestmentStorage {
             &investment.investment_id,
         );
 
+        // Track every investment funding the invoice (syndicated invoices have several)
+        let list_key = Self::invoice_list_key(&investment.invoice_id);
+        let mut ids: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&list_key)
+            .unwrap_or_else(|| Vec::new(env));
+        if !ids.contains(&investment.investment_id) {
+            ids.push_back(investment.investment_id.clone());
+            env.storage().instance().set(&list_key, &ids);
+        }
+
         // Add to investor index
         Self::add_to_investor_index(env, &investment.investor, &investment.investment_id);
     }
@@ -177,6 +193,33 @@ impl InvestmentStorage {
         let investment_id: Option<BytesN<32>> = env.storage().instance().get(&index_key);
         investment_id.and_then(|id| Self::get_investment(env, &id))
     }
+
+    /// Get every investment funding an invoice, in funding order.
+    ///
+    /// Returns a single entry for bilateral deals and one entry per
+    /// participant for syndicated invoices.
+    pub fn get_investments_by_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<Investment> {
+        let mut investments = Vec::new(env);
+        let ids: Option<Vec<BytesN<32>>> = env
+            .storage()
+            .instance()
+            .get(&Self::invoice_list_key(invoice_id));
+        match ids {
+            Some(ids) => {
+                for id in ids.iter() {
+                    if let Some(investment) = Self::get_investment(env, &id) {
+                        investments.push_back(investment);
+                    }
+                }
+            }
+            None => {
+                if let Some(investment) = Self::get_investment_by_invoice(env, invoice_id) {
+                    investments.push_back(investment);
+                }
+            }
+        }
+        investments
+    }
     pub fn update_investment(env: &Env, investment: &Investment) {
         env.storage()
             .instance()
@@ -218,4 +261,17 @@ impl InvestmentStorage {
             env.storage().instance().set(&key, &investments);
         }
     }
+
+    /// Remove investment from investor index (e.g. after the position is sold)
+    pub fn remove_from_investor_index(env: &Env, investor: &Address, investment_id: &BytesN<32>) {
+        let key = Self::investor_index_key(investor);
+        let investments = Self::get_investments_by_investor(env, investor);
+        let mut remaining = Vec::new(env);
+        for inv_id in investments.iter() {
+            if inv_id != *investment_id {
+                remaining.push_back(inv_id);
+            }
+        }
+        env.storage().instance().set(&key, &remaining);
+    }
 }
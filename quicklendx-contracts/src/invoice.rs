@@ -159,6 +159,8 @@ pub struct Invoice {
     pub dispute: Dispute,                    // Dispute details if any
     pub total_paid: i128,                    // Aggregate amount paid towards the invoice
     pub payment_history: Vec<PaymentRecord>, // History of partial payments
+    pub debtor: Option<Address>,             // Debtor expected to acknowledge the invoice
+    pub debtor_confirmed_at: Option<u64>,    // When the debtor acknowledged it on-chain
 }
 
 // Use the main error enum from errors.rs
@@ -257,6 +259,8 @@ impl Invoice {
             },
             total_paid: 0,
             payment_history: vec![env],
+            debtor: None,
+            debtor_confirmed_at: None,
         };
 
         // Log invoice creation
@@ -289,6 +293,11 @@ impl Invoice {
         self.status == InvoiceStatus::Verified && self.funded_amount == 0
     }
 
+    /// Check if the debtor has acknowledged the invoice on-chain
+    pub fn is_debtor_confirmed(&self) -> bool {
+        self.debtor_confirmed_at.is_some()
+    }
+
     pub const DEFAULT_GRACE_PERIOD: u64 = DEFAULT_INVOICE_GRACE_PERIOD;
 
     /// Check if invoice is overdue
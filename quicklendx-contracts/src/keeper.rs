@@ -0,0 +1,177 @@
+//! Rewards for keepers running maintenance entrypoints.
+//!
+//! Overdue checks, default handling, expired bid cleanup and backup cleanup only happen
+//! when someone calls them. The admin can set aside a share of the platform fees charged
+//! in one currency as a reward pool, and every keeper call that actually changes state
+//! is paid a bounty from it. A keeper is paid at most once per `min_interval`, and the
+//! total paid out in each `period` is capped.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::admin::AdminStorage;
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{emit_keeper_config_set, emit_keeper_rewarded};
+use crate::payments::transfer_funds;
+
+const MAX_BPS: u32 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum KeeperKey {
+    KeeperRewardConfig,
+    KeeperRewardPool,
+    LastKeeperReward(Address),
+    KeeperPeriodPayout,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperConfig {
+    /// Currency the fee share is retained in and rewards are paid in.
+    pub currency: Address,
+    /// Reward per call that changes state.
+    pub bounty: i128,
+    /// Share of each platform fee in `currency` added to the pool.
+    pub fee_share_bps: u32,
+    /// Shortest time between two rewards to the same keeper.
+    pub min_interval: u64,
+    /// Length of the period `period_cap` applies to.
+    pub period: u64,
+    /// Most paid out to all keepers within one period.
+    pub period_cap: i128,
+}
+
+/// Rewards paid out so far in the current period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PeriodPayout {
+    period: u64,
+    paid: i128,
+}
+
+pub struct KeeperRewards;
+
+impl KeeperRewards {
+    pub fn get_config(env: &Env) -> Option<KeeperConfig> {
+        env.storage()
+            .persistent()
+            .get(&KeeperKey::KeeperRewardConfig)
+    }
+
+    /// Set how keepers are rewarded (admin only). The pool carries over, so the currency
+    /// cannot change while it holds a balance.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    /// * `InvalidAmount` if the bounty or cap is not positive, the cap is below the
+    ///   bounty, or the fee share is above 100%
+    /// * `InvalidTimestamp` if the period is zero
+    /// * `OperationNotAllowed` if the currency changes while the pool holds a balance
+    pub fn set_config(
+        env: &Env,
+        admin: &Address,
+        config: &KeeperConfig,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        AdminStorage::require_admin(env, admin)?;
+        CurrencyWhitelist::require_allowed_currency(env, &config.currency)?;
+        if config.bounty <= 0 || config.period_cap < config.bounty || config.fee_share_bps > MAX_BPS
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if config.period == 0 {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        if let Some(current) = Self::get_config(env) {
+            if current.currency != config.currency && Self::pool_balance(env) > 0 {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&KeeperKey::KeeperRewardConfig, config);
+        emit_keeper_config_set(env, config);
+        Ok(())
+    }
+
+    /// Fees set aside for keeper rewards and not paid out yet.
+    pub fn pool_balance(env: &Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&KeeperKey::KeeperRewardPool)
+            .unwrap_or(0)
+    }
+
+    /// Part of a platform fee in `currency` to retain in the pool; zero when rewards are
+    /// not configured or paid in another currency.
+    pub fn fee_share(env: &Env, currency: &Address, fee_amount: i128) -> i128 {
+        match Self::get_config(env) {
+            Some(config) if config.currency == *currency => fee_amount
+                .saturating_mul(config.fee_share_bps as i128)
+                .saturating_div(MAX_BPS as i128),
+            _ => 0,
+        }
+    }
+
+    /// Add a retained fee share, already held by the contract, to the pool.
+    pub fn add_to_pool(env: &Env, amount: i128) {
+        let balance = Self::pool_balance(env).saturating_add(amount);
+        env.storage()
+            .persistent()
+            .set(&KeeperKey::KeeperRewardPool, &balance);
+    }
+
+    /// Pay `keeper` the bounty for a call that changed state. Pays what the pool and the
+    /// period's cap still allow, and nothing when rewards are not configured or the keeper
+    /// was rewarded less than `min_interval` ago. Returns the amount paid.
+    pub fn reward(env: &Env, keeper: &Address) -> Result<i128, QuickLendXError> {
+        let config = match Self::get_config(env) {
+            Some(config) => config,
+            None => return Ok(0),
+        };
+        let now = env.ledger().timestamp();
+        let last_key = KeeperKey::LastKeeperReward(keeper.clone());
+        if let Some(last) = env.storage().persistent().get::<_, u64>(&last_key) {
+            if now < last.saturating_add(config.min_interval) {
+                return Ok(0);
+            }
+        }
+
+        let period = now / config.period;
+        let mut payout: PeriodPayout = env
+            .storage()
+            .persistent()
+            .get(&KeeperKey::KeeperPeriodPayout)
+            .filter(|payout: &PeriodPayout| payout.period == period)
+            .unwrap_or(PeriodPayout { period, paid: 0 });
+        let pool = Self::pool_balance(env);
+        let amount = config
+            .bounty
+            .min(pool)
+            .min(config.period_cap.saturating_sub(payout.paid));
+        if amount <= 0 {
+            return Ok(0);
+        }
+
+        transfer_funds(
+            env,
+            &config.currency,
+            &env.current_contract_address(),
+            keeper,
+            amount,
+        )?;
+        env.storage()
+            .persistent()
+            .set(&KeeperKey::KeeperRewardPool, &(pool - amount));
+        payout.paid = payout.paid.saturating_add(amount);
+        env.storage()
+            .persistent()
+            .set(&KeeperKey::KeeperPeriodPayout, &payout);
+        env.storage().persistent().set(&last_key, &now);
+        emit_keeper_rewarded(env, keeper, amount);
+        Ok(amount)
+    }
+}
@@ -0,0 +1,146 @@
+//! Late-payment interest on overdue invoices.
+//!
+//! Once a Funded invoice passes its due date, interest accrues on its unpaid amount at the
+//! admin-set `LateInterestConfig` daily rate, up to the grace deadline after which the
+//! invoice can be defaulted instead, and is capped at a share of the invoice amount. This
+//! is separate from the `FeeType::LatePayment` fee structure, which `FeeManager` charges
+//! as a flat surcharge. Payments go to the invoice amount first and to interest last; the
+//! interest is settled to investors as part of their return, so the platform fee takes its
+//! share.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+use crate::admin::AdminStorage;
+use crate::defaults::resolve_grace_period;
+use crate::errors::QuickLendXError;
+use crate::events::emit_late_interest_config_set;
+use crate::invoice::{Invoice, InvoiceStatus};
+use crate::listing::BPS_DENOMINATOR;
+
+const SECONDS_PER_DAY: i128 = 86_400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum LatePaymentKey {
+    LateInterest(BytesN<32>),
+    LateInterestConfig,
+}
+
+/// How late-payment interest is charged.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateInterestConfig {
+    /// Interest per day overdue, in bps of the unpaid invoice amount; zero charges none.
+    pub daily_rate_bps: u32,
+    /// Most interest charged on an invoice, in bps of its amount.
+    pub max_interest_bps: u32,
+}
+
+/// Interest accrued on an invoice up to a checkpoint, before the cap.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct LateInterest {
+    accrued: i128,
+    accrued_until: u64,
+}
+
+pub struct LatePaymentInterest;
+
+impl LatePaymentInterest {
+    pub fn get_config(env: &Env) -> Option<LateInterestConfig> {
+        env.storage()
+            .persistent()
+            .get(&LatePaymentKey::LateInterestConfig)
+    }
+
+    /// Set the late-payment interest rate and cap (admin only).
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `InvalidAmount` if the daily rate or the cap is above 100%
+    pub fn set_config(
+        env: &Env,
+        admin: &Address,
+        config: &LateInterestConfig,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        AdminStorage::require_admin(env, admin)?;
+        if config.daily_rate_bps as i128 > BPS_DENOMINATOR
+            || config.max_interest_bps as i128 > BPS_DENOMINATOR
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage()
+            .persistent()
+            .set(&LatePaymentKey::LateInterestConfig, config);
+        emit_late_interest_config_set(env, config);
+        Ok(())
+    }
+
+    /// Late-payment interest owed on an invoice now.
+    ///
+    /// Interest stops accruing once the invoice is no longer Funded, and is zero while no
+    /// `LateInterestConfig` with a daily rate is set.
+    pub fn current(env: &Env, invoice: &Invoice) -> i128 {
+        let config = match Self::active_config(env) {
+            Some(config) => config,
+            None => return 0,
+        };
+        let mut state = Self::get(env, &invoice.id);
+        if invoice.status == InvoiceStatus::Funded {
+            Self::accrue(env, invoice, &config, &mut state);
+        }
+        Self::charged(invoice, &config, &state)
+    }
+
+    /// Store the interest accrued so far, called before a payment changes the unpaid
+    /// amount it accrues on. Returns the interest owed.
+    pub fn checkpoint(env: &Env, invoice: &Invoice) -> i128 {
+        let config = match Self::active_config(env) {
+            Some(config) => config,
+            None => return 0,
+        };
+        let mut state = Self::get(env, &invoice.id);
+        Self::accrue(env, invoice, &config, &mut state);
+        env.storage()
+            .persistent()
+            .set(&LatePaymentKey::LateInterest(invoice.id.clone()), &state);
+        Self::charged(invoice, &config, &state)
+    }
+
+    fn get(env: &Env, invoice_id: &BytesN<32>) -> LateInterest {
+        env.storage()
+            .persistent()
+            .get(&LatePaymentKey::LateInterest(invoice_id.clone()))
+            .unwrap_or_default()
+    }
+
+    fn active_config(env: &Env) -> Option<LateInterestConfig> {
+        Self::get_config(env).filter(|config| config.daily_rate_bps > 0)
+    }
+
+    /// Accrue interest on the unpaid amount from the last checkpoint (or the due date) up
+    /// to now, stopping at the grace deadline.
+    fn accrue(env: &Env, invoice: &Invoice, config: &LateInterestConfig, state: &mut LateInterest) {
+        let grace_deadline = invoice.grace_deadline(resolve_grace_period(env, None));
+        let from = state.accrued_until.max(invoice.due_date);
+        let until = env.ledger().timestamp().min(grace_deadline);
+        if until > from {
+            let unpaid = invoice.amount.saturating_sub(invoice.total_paid).max(0);
+            let interest = unpaid
+                .saturating_mul(config.daily_rate_bps as i128)
+                .saturating_mul((until - from) as i128)
+                / (BPS_DENOMINATOR * SECONDS_PER_DAY);
+            state.accrued = state.accrued.saturating_add(interest);
+            state.accrued_until = until;
+        }
+    }
+
+    fn charged(invoice: &Invoice, config: &LateInterestConfig, state: &LateInterest) -> i128 {
+        let cap = invoice
+            .amount
+            .saturating_mul(config.max_interest_bps as i128)
+            / BPS_DENOMINATOR;
+        state.accrued.clamp(0, cap.max(0))
+    }
+}
@@ -5,27 +5,48 @@ use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Ma
 mod admin;
 mod analytics;
 mod audit;
+mod auto_accept;
+mod auto_debit;
 mod backup;
+mod batch_payment;
 mod bid;
+mod credit_line;
 mod currency;
 mod defaults;
 mod dispute;
+mod distribution;
 mod emergency;
 mod errors;
 mod escrow;
+mod escrow_terms;
 mod events;
 mod fees;
 mod init;
+mod installment;
 mod investment;
 mod invoice;
+mod keeper;
+mod late_payment;
+mod listing;
+mod milestone;
+mod negotiation;
 mod notifications;
 mod payments;
+mod pool;
+mod pricing;
 mod profits;
 mod protocol_limits;
+mod receipt;
+mod recourse;
 mod reentrancy;
+mod reverse_factoring;
+mod sealed_bid;
+mod secondary_market;
 mod settlement;
+mod standing_order;
 #[cfg(test)]
 mod storage;
+mod syndication;
 #[cfg(test)]
 mod test_admin;
 #[cfg(test)]
@@ -53,10 +74,15 @@ mod test_string_limits;
 #[cfg(test)]
 mod test_vesting;
 pub mod types;
+mod vault;
 mod verification;
 mod vesting;
 use admin::AdminStorage;
-use bid::{Bid, BidStatus, BidStorage};
+use auto_accept::{AutoAccept, AutoAcceptPolicy, AutoAcceptStorage};
+use auto_debit::{AutoDebit, CollectionFailure};
+use batch_payment::{AllocationOrder, BatchPayment, BatchPaymentResult, BatchPayments};
+use bid::{Bid, BidAmendment, BidStatus, BidStorage};
+use credit_line::{CreditLine, CreditLineStorage, CreditLineTerms, CreditLines};
 use defaults::{
     create_dispute as do_create_dispute, get_dispute_details as do_get_dispute_details,
     get_invoices_by_dispute_status as do_get_invoices_by_dispute_status,
@@ -64,30 +90,57 @@ use defaults::{
     handle_default as do_handle_default, mark_invoice_defaulted as do_mark_invoice_defaulted,
     put_dispute_under_review as do_put_dispute_under_review, resolve_dispute as do_resolve_dispute,
 };
+use distribution::ProgressiveDistribution;
 use errors::QuickLendXError;
 use escrow::{
-    accept_bid_and_fund as do_accept_bid_and_fund, refund_escrow_funds as do_refund_escrow_funds,
+    accept_bid_and_fund as do_accept_bid_and_fund, reclaim_escrow_funds as do_reclaim_escrow_funds,
+    refund_escrow_funds as do_refund_escrow_funds,
 };
+use escrow_terms::{EscrowReleaseTerms, EscrowTerms, ReleaseCondition};
 use events::{
-    emit_audit_query, emit_audit_validation, emit_bid_accepted, emit_bid_placed,
-    emit_bid_withdrawn, emit_escrow_created, emit_escrow_released, emit_insurance_added,
-    emit_insurance_premium_collected, emit_investor_verified, emit_invoice_cancelled,
-    emit_invoice_metadata_cleared, emit_invoice_metadata_updated, emit_invoice_uploaded,
-    emit_invoice_verified,
+    emit_audit_query, emit_audit_validation, emit_bid_accepted, emit_bid_amended,
+    emit_bid_condition_set, emit_bid_placed, emit_bid_withdrawn, emit_escrow_created,
+    emit_escrow_released, emit_insurance_added, emit_insurance_premium_collected,
+    emit_investor_verified, emit_invoice_cancelled, emit_invoice_confirmed,
+    emit_invoice_debtor_set, emit_invoice_metadata_cleared, emit_invoice_metadata_updated,
+    emit_invoice_uploaded, emit_invoice_verified, emit_standing_order_skipped,
+    emit_vault_funding_skipped,
 };
+use installment::{Installment, InstallmentPlans, InstallmentTerms};
 use investment::{InsuranceCoverage, Investment, InvestmentStatus, InvestmentStorage};
 use invoice::{DisputeStatus, Invoice, InvoiceMetadata, InvoiceStatus, InvoiceStorage};
-use payments::{create_escrow, release_escrow, EscrowStorage};
+use keeper::{KeeperConfig, KeeperRewards};
+use late_payment::{LateInterestConfig, LatePaymentInterest};
+use listing::{Listing, ListingStorage, ListingTerms};
+use milestone::{EscrowMilestones, Milestone, MilestoneTerms};
+use negotiation::{Negotiation, NegotiationStorage, Negotiations};
+use payments::{check_transfer_in, create_escrow, release_escrow, EscrowStorage};
+use pool::{
+    InvoicePool, InvoicePools, PoolPosition, PoolStorage, PoolTerms, PoolWaterfall, TrancheKind,
+};
+use pricing::{TimeBasedPricing, TimePricing};
 use profits::{calculate_profit as do_calculate_profit, PlatformFee, PlatformFeeConfig};
+use receipt::{InvestmentReceipt, ReceiptStorage, Receipts};
+use recourse::{FactoringMode, Recourse, RecourseObligation, RecourseStorage};
+use reverse_factoring::{ApprovedPayable, PayableTerms, ReverseFactoring, ReverseFactoringStorage};
+use sealed_bid::{BidCommitment, SealedAuction, SealedBidStorage, SealedBidding};
+use secondary_market::{PositionListing, SecondaryMarket, SecondaryMarketStorage};
 use settlement::{
-    process_partial_payment as do_process_partial_payment, settle_invoice as do_settle_invoice,
+    get_invoice_progress as do_get_invoice_progress, get_payment_records as do_get_payment_records,
+    is_approved_payer as do_is_approved_payer,
+    process_partial_payment as do_process_partial_payment,
+    process_payment_from as do_process_payment_from, set_approved_payer as do_set_approved_payer,
+    settle_invoice as do_settle_invoice, Progress, SettlementPaymentRecord,
 };
+use standing_order::{StandingOrder, StandingOrderStorage, StandingOrderTerms, StandingOrders};
+use vault::{LiquidityVault, LiquidityVaults, VaultPolicy, VaultStorage};
 use verification::{
     calculate_investment_limit, calculate_investor_risk_score, determine_investor_tier,
     get_business_verification_status, get_investor_analytics,
     get_investor_verification as do_get_investor_verification, reject_business,
-    reject_investor as do_reject_investor, submit_investor_kyc as do_submit_investor_kyc,
-    submit_kyc_application, update_investor_analytics, validate_bid, validate_investor_investment,
+    reject_investor as do_reject_investor, require_no_open_bid,
+    submit_investor_kyc as do_submit_investor_kyc, submit_kyc_application,
+    update_investor_analytics, validate_bid_terms, validate_investor_investment,
     validate_invoice_metadata, verify_business, verify_investor as do_verify_investor,
     verify_invoice_data, BusinessVerificationStatus, BusinessVerificationStorage,
     InvestorRiskLevel, InvestorTier, InvestorVerification, InvestorVerificationStorage,
@@ -110,6 +163,17 @@ use crate::currency::CurrencyWhitelist;
 #[contract]
 pub struct QuickLendXContract;
 
+/// A bid that passed every placement check, ready to be stored.
+struct CheckedBid {
+    invoice: Invoice,
+    investor: Address,
+    bid_amount: i128,
+    expected_return: i128,
+    requires_debtor_confirmation: bool,
+    /// Funded as soon as it is placed (buy-now price or auto-accept policy).
+    fund_now: bool,
+}
+
 /// Maximum number of records returned by paginated query endpoints.
 pub(crate) const MAX_QUERY_LIMIT: u32 = 100;
 
@@ -124,6 +188,19 @@ fn cap_query_limit(limit: u32) -> u32 {
     limit.min(MAX_QUERY_LIMIT)
 }
 
+/// Count the investments of invoices a batch settled as successful.
+fn record_settled_batch(env: &Env, results: &Vec<BatchPaymentResult>) {
+    for result in results.iter() {
+        if result.settled {
+            let investments =
+                InvestmentStorage::get_investments_by_invoice(env, &result.invoice_id);
+            for inv in investments.iter() {
+                let _ = update_investor_analytics(env, &inv.investor, inv.amount, true);
+            }
+        }
+    }
+}
+
 #[inline]
 fn require_current_admin(env: &Env) -> Result<Address, QuickLendXError> {
     let admin = AdminStorage::get_admin(env).ok_or(QuickLendXError::NotAdmin)?;
@@ -472,6 +549,7 @@ impl QuickLendXContract {
     /// * `InvalidAmount` if amount <= 0
     /// * `InvoiceDueDateInvalid` if due_date is not in the future
     /// * `InvalidDescription` if description is empty
+    /// * `OperationNotAllowed` if the business has an unpaid recourse obligation
     pub fn store_invoice(
         env: Env,
         business: Address,
@@ -500,6 +578,7 @@ impl QuickLendXContract {
         }
 
         currency::CurrencyWhitelist::require_allowed_currency(&env, &currency)?;
+        Recourse::require_no_outstanding(&env, &business)?;
 
         // Check if business is verified (temporarily disabled for debugging)
         // if !verification::BusinessVerificationStorage::is_business_verified(&env, &business) {
@@ -566,6 +645,7 @@ impl QuickLendXContract {
         // Basic validation
         verify_invoice_data(&env, &business, amount, &currency, due_date, &description)?;
         currency::CurrencyWhitelist::require_allowed_currency(&env, &currency)?;
+        Recourse::require_no_outstanding(&env, &business)?;
 
         // Validate category and tags
         verification::validate_invoice_category(&category)?;
@@ -616,6 +696,28 @@ impl QuickLendXContract {
         reentrancy::with_payment_guard(&env, || do_accept_bid_and_fund(&env, &invoice_id, &bid_id))
     }
 
+    /// Accept several bids that jointly fund the invoice (syndicated funding).
+    ///
+    /// Business must be authorized. Each bid gets its own escrow slice and investment;
+    /// settlement pays every participant pro rata by principal.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<BytesN<32>>)` - The new escrow IDs, in `bid_ids` order
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `InvoiceAlreadyFunded`, `InvoiceNotAvailableForFunding`, `Unauthorized`
+    /// * `OperationNotAllowed` if the bid list is empty, exceeds the syndicate size or has duplicates
+    /// * `InvalidAmount` if the combined bids exceed the invoice amount
+    pub fn accept_syndicated_bids(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_ids: Vec<BytesN<32>>,
+    ) -> Result<Vec<BytesN<32>>, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            syndication::accept_bids_and_fund(&env, &invoice_id, &bid_ids)
+        })
+    }
+
     /// Verify an invoice (admin or automated process)
     pub fn verify_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
         let admin = require_current_admin(&env)?;
@@ -625,7 +727,7 @@ impl QuickLendXContract {
 
         // When invoice is already funded, verify_invoice triggers release_escrow_funds (Issue #300)
         if invoice.status == InvoiceStatus::Funded {
-            return Self::release_escrow_funds(env, invoice_id);
+            return Self::release_held_escrows(&env, &invoice_id, true);
         }
 
         // Only allow verification if pending
@@ -650,6 +752,9 @@ impl QuickLendXContract {
         // Send notification
         let _ = NotificationSystem::notify_invoice_verified(&env, &invoice);
 
+        Self::execute_standing_orders(&env, &invoice)?;
+        Self::execute_vault_bids(&env, &invoice)?;
+
         // If invoice is funded (has escrow), release escrow funds to business
         if invoice.status == InvoiceStatus::Funded {
             Self::release_escrow_funds(env.clone(), invoice_id)?;
@@ -658,6 +763,91 @@ impl QuickLendXContract {
         Ok(())
     }
 
+    /// Start placing bids on a newly verified invoice for every matching standing order,
+    /// evaluating the first batch of open orders; keepers evaluate the rest through
+    /// `keeper_execute_standing_orders`. Nothing is placed while a sealed auction is
+    /// open, since its bids must go through commit/reveal.
+    fn execute_standing_orders(env: &Env, invoice: &Invoice) -> Result<(), QuickLendXError> {
+        if SealedBidding::is_bidding_open(env, &invoice.id) {
+            return Ok(());
+        }
+        StandingOrders::begin_evaluation(env, &invoice.id);
+        Self::execute_standing_order_batch(env, invoice)?;
+        Ok(())
+    }
+
+    /// Place bids for the matching orders among the next batch of open standing orders.
+    /// Returns how many were placed.
+    ///
+    /// Each bid is checked like `place_bid` before anything is written, so investor
+    /// limits, the per-investor active-bid cap and listing terms apply. An order whose bid
+    /// is rejected is skipped with an event rather than failing the caller.
+    fn execute_standing_order_batch(env: &Env, invoice: &Invoice) -> Result<u32, QuickLendXError> {
+        BidStorage::cleanup_expired_bids(env, &invoice.id);
+        let mut placed = 0u32;
+        for order in StandingOrders::next_matching_orders(env, invoice).iter() {
+            let (bid_amount, expected_return) = order.bid_terms(invoice.amount);
+            let checked = match Self::check_bid_placement(
+                env,
+                &order.investor,
+                &invoice.id,
+                bid_amount,
+                expected_return,
+                false,
+            ) {
+                Ok(checked) => checked,
+                Err(error) => {
+                    emit_standing_order_skipped(env, order.order_id, &invoice.id, error);
+                    continue;
+                }
+            };
+            let bid_id = Self::place_checked_bid(env, &checked)?;
+            StandingOrders::record_bid(env, order.order_id, &invoice.id, &bid_id);
+            placed += 1;
+            // Stop once a bid was auto-accepted or bought the invoice outright
+            if checked.fund_now {
+                StandingOrders::end_evaluation(env, &invoice.id);
+                break;
+            }
+        }
+        Ok(placed)
+    }
+
+    /// Let the first liquidity vault whose policy matches bid on a newly verified
+    /// invoice left unfunded by standing orders. The bid is funded straight away when it
+    /// meets the buy-now price or the business's auto-accept policy; otherwise it waits
+    /// for the business like any other bid. A preapproved bid that cannot be funded is
+    /// left waiting too, with an event.
+    fn execute_vault_bids(env: &Env, invoice: &Invoice) -> Result<(), QuickLendXError> {
+        let current = match InvoiceStorage::get_invoice(env, &invoice.id) {
+            Some(current) if current.status == InvoiceStatus::Verified => current,
+            _ => return Ok(()),
+        };
+        let (bid, buy_now) = match LiquidityVaults::bid_on_invoice(env, &current) {
+            Some(placed) => placed,
+            None => return Ok(()),
+        };
+        let preapproved = Self::is_preapproved(
+            env,
+            &current,
+            bid.bid_amount,
+            bid.expected_return,
+            false,
+            buy_now,
+            &InvestorTier::Basic,
+        );
+        if !preapproved {
+            return Ok(());
+        }
+        if let Err(error) = Self::check_funding(env, &current, &bid) {
+            emit_vault_funding_skipped(env, &bid, error);
+            return Ok(());
+        }
+        reentrancy::with_payment_guard(env, || {
+            Self::fund_bid(env.clone(), current.clone(), bid.clone())
+        })
+    }
+
     /// Cancel an invoice (business only, before funding)
     pub fn cancel_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
@@ -763,6 +953,155 @@ impl QuickLendXContract {
         InvoiceStorage::get_invoices_by_tax_id(&env, &tax_id)
     }
 
+    /// Name the debtor expected to acknowledge an invoice (business only).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified, or is already confirmed
+    /// * `OperationNotAllowed` if the business names itself as debtor
+    pub fn set_invoice_debtor(
+        env: Env,
+        invoice_id: BytesN<32>,
+        debtor: Address,
+    ) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) || invoice.is_debtor_confirmed()
+        {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if debtor == invoice.business {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        invoice.debtor = Some(debtor.clone());
+        InvoiceStorage::update_invoice(&env, &invoice);
+        emit_invoice_debtor_set(&env, &invoice, &debtor);
+        Ok(())
+    }
+
+    /// Price an invoice's investor return by time: an annualized rate plus a minimum fee
+    /// (business only, before funding and before bids are placed). Settling early then
+    /// discounts the return investors have not earned yet.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    /// * `InvalidAmount` for an invalid rate or minimum fee
+    pub fn set_invoice_time_pricing(
+        env: Env,
+        invoice_id: BytesN<32>,
+        pricing: TimePricing,
+    ) -> Result<(), QuickLendXError> {
+        TimeBasedPricing::set(&env, &invoice_id, &pricing)
+    }
+
+    /// Get the time-based pricing of an invoice, if it has one.
+    pub fn get_invoice_time_pricing(env: Env, invoice_id: BytesN<32>) -> Option<TimePricing> {
+        TimeBasedPricing::get(&env, &invoice_id)
+    }
+
+    /// Split an invoice into installments due on fixed dates, each a share of the amount in
+    /// bps (business only, before funding and before bids are placed). An empty schedule
+    /// removes it.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    /// * `InvalidAmount` if shares are zero, do not add up to 100% or are too many
+    /// * `InvalidTimestamp` if due dates are not increasing, past, or after the due date
+    pub fn set_installment_schedule(
+        env: Env,
+        invoice_id: BytesN<32>,
+        installments: Vec<InstallmentTerms>,
+    ) -> Result<Vec<Installment>, QuickLendXError> {
+        InstallmentPlans::set_schedule(&env, &invoice_id, &installments)
+    }
+
+    /// Get an invoice's installments with their on-time, late or missed status.
+    pub fn get_installment_schedule(env: Env, invoice_id: BytesN<32>) -> Vec<Installment> {
+        InstallmentPlans::get_schedule(&env, &invoice_id)
+    }
+
+    /// Opt an invoice in or out of progressive distribution, which pays partial payments
+    /// out to investors as they come in (business only, before funding and before bids
+    /// are placed).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    pub fn set_progressive_distribution(
+        env: Env,
+        invoice_id: BytesN<32>,
+        enabled: bool,
+    ) -> Result<(), QuickLendXError> {
+        ProgressiveDistribution::set(&env, &invoice_id, enabled)
+    }
+
+    /// Whether an invoice's partial payments are paid out to investors as they come in.
+    pub fn is_progressive_distribution(env: Env, invoice_id: BytesN<32>) -> bool {
+        ProgressiveDistribution::is_enabled(&env, &invoice_id)
+    }
+
+    /// Gross payments already paid out to an investment, platform fee included.
+    pub fn get_distributed_amount(env: Env, investment_id: BytesN<32>) -> i128 {
+        ProgressiveDistribution::distributed(&env, &investment_id)
+    }
+
+    /// Acknowledge an invoice as its debtor.
+    ///
+    /// The confirmation is recorded on the invoice, makes bids that were conditional on it
+    /// acceptable, and lets standing orders that require it bid on a Verified invoice.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `OperationNotAllowed` if the business has not named a debtor
+    /// * `InvalidStatus` if the invoice is already confirmed, or is not Pending, Verified
+    ///   or Funded
+    pub fn confirm_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let debtor = invoice
+            .debtor
+            .clone()
+            .ok_or(QuickLendXError::OperationNotAllowed)?;
+        debtor.require_auth();
+
+        if invoice.is_debtor_confirmed()
+            || !matches!(
+                invoice.status,
+                InvoiceStatus::Pending | InvoiceStatus::Verified | InvoiceStatus::Funded
+            )
+        {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        invoice.debtor_confirmed_at = Some(env.ledger().timestamp());
+        InvoiceStorage::update_invoice(&env, &invoice);
+        emit_invoice_confirmed(&env, &invoice, &debtor);
+
+        if invoice.status == InvoiceStatus::Verified {
+            Self::execute_standing_orders(&env, &invoice)?;
+        }
+        // Escrow waiting on this confirmation is released straight away
+        if invoice.status == InvoiceStatus::Funded
+            && EscrowReleaseTerms::get(&env, &invoice_id).release_condition
+                == ReleaseCondition::DebtorConfirmed
+            && !EscrowStorage::get_held_escrows_by_invoice(&env, &invoice_id).is_empty()
+        {
+            Self::release_held_escrows(&env, &invoice_id, false)?;
+        }
+        Ok(())
+    }
+
     /// Get all invoices by status
     pub fn get_invoices_by_status(env: Env, status: InvoiceStatus) -> Vec<BytesN<32>> {
         InvoiceStorage::get_invoices_by_status(&env, &status)
@@ -921,6 +1260,9 @@ impl QuickLendXContract {
     }
 
     /// Get all bids for an invoice sorted using the platform ranking rules
+    ///
+    /// Only Placed bids are ranked; on sealed invoices these are the revealed bids. Bids
+    /// conditional on debtor confirmation are left out until the debtor confirms.
     pub fn get_ranked_bids(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
         BidStorage::rank_bids(&env, &invoice_id)
     }
@@ -942,141 +1284,1155 @@ impl QuickLendXContract {
         BidStorage::get_bid_records_for_invoice(&env, &invoice_id)
     }
 
-    /// Remove bids that have passed their expiration window
-    pub fn cleanup_expired_bids(env: Env, invoice_id: BytesN<32>) -> u32 {
-        BidStorage::cleanup_expired_bids(&env, &invoice_id)
+    // ============================================================================
+    // Listing Terms
+    // ============================================================================
+
+    /// Set per-invoice listing terms (business only).
+    ///
+    /// Bids that violate the terms are rejected at `place_bid`; a bid at or above the
+    /// buy-now price is accepted and funded through escrow immediately.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidAmount` / `InvalidTimestamp` for inconsistent terms
+    pub fn set_listing_terms(
+        env: Env,
+        invoice_id: BytesN<32>,
+        terms: ListingTerms,
+    ) -> Result<(), QuickLendXError> {
+        Listing::set_terms(&env, &invoice_id, &terms)
     }
 
-    /// Cancel a placed bid (investor only, Placed → Cancelled).
-    pub fn cancel_bid(env: Env, bid_id: BytesN<32>) -> bool {
-        bid::BidStorage::cancel_bid(&env, &bid_id)
+    /// Remove the listing terms of an invoice (business only).
+    pub fn clear_listing_terms(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        Listing::clear_terms(&env, &invoice_id)
     }
 
-    /// Get all bids placed by an investor across all invoices.
-    pub fn get_all_bids_by_investor(env: Env, investor: Address) -> Vec<Bid> {
-        bid::BidStorage::get_all_bids_by_investor(&env, &investor)
+    /// Get the listing terms of an invoice, if any.
+    pub fn get_listing_terms(env: Env, invoice_id: BytesN<32>) -> Option<ListingTerms> {
+        ListingStorage::get_terms(&env, &invoice_id)
     }
 
-    /// Place a bid on an invoice
+    // ============================================================================
+    // Auto-Accept Policies
+    // ============================================================================
+
+    /// Set a policy that auto-accepts qualifying bids on all of a business's invoices.
     ///
-    /// Validates:
-    /// - Invoice exists and is verified
-    /// - Bid amount is positive
-    /// - Investor is authorized and verified
-    /// - Creates and stores the bid
-    pub fn place_bid(
+    /// The first bid placed that meets the policy is funded through escrow as if the
+    /// business had called `accept_bid`. Invoice-level policies take precedence.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `min_advance_bps` is outside 1..=10,000 or not below
+    ///   `max_return_bps`
+    pub fn set_business_auto_accept(
         env: Env,
-        investor: Address,
-        invoice_id: BytesN<32>,
-        bid_amount: i128,
-        expected_return: i128,
-    ) -> Result<BytesN<32>, QuickLendXError> {
-        // Authorization check: Only the investor can place their own bid
-        investor.require_auth();
+        business: Address,
+        policy: AutoAcceptPolicy,
+    ) -> Result<(), QuickLendXError> {
+        AutoAccept::set_business_policy(&env, &business, &policy)
+    }
 
-        // Validate bid amount is positive
-        if bid_amount <= 0 {
-            return Err(QuickLendXError::InvalidAmount);
-        }
+    /// Revoke the business-wide auto-accept policy (business only).
+    pub fn clear_business_auto_accept(env: Env, business: Address) -> Result<(), QuickLendXError> {
+        AutoAccept::clear_business_policy(&env, &business)
+    }
 
-        // Enforce per-investor rate limit on active (Placed) bids across all invoices.
-        let active_bids =
-            bid::BidStorage::count_active_bids_by_investor(&env, &investor);
-        if active_bids >= MAX_ACTIVE_BIDS_PER_INVESTOR {
-            // Use a generic business-logic error to avoid adding new error variants.
-            return Err(QuickLendXError::OperationNotAllowed);
-        }
+    /// Get the business-wide auto-accept policy, if any.
+    pub fn get_business_auto_accept(env: Env, business: Address) -> Option<AutoAcceptPolicy> {
+        AutoAcceptStorage::get_business_policy(&env, &business)
+    }
 
-        // Validate invoice exists and is verified
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-        if invoice.status != InvoiceStatus::Verified {
-            return Err(QuickLendXError::InvalidStatus);
-        }
-        currency::CurrencyWhitelist::require_allowed_currency(&env, &invoice.currency)?;
+    /// Set an auto-accept policy for a single invoice (business only).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidAmount` for an inconsistent policy
+    pub fn set_invoice_auto_accept(
+        env: Env,
+        invoice_id: BytesN<32>,
+        policy: AutoAcceptPolicy,
+    ) -> Result<(), QuickLendXError> {
+        AutoAccept::set_invoice_policy(&env, &invoice_id, &policy)
+    }
 
-        // Check invoice currency is whitelisted
-        if !CurrencyWhitelist::is_allowed_currency(&env, &invoice.currency) {
-            return Err(QuickLendXError::InvalidCurrency);
-        }
+    /// Revoke the auto-accept policy of an invoice (business only).
+    pub fn clear_invoice_auto_accept(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        AutoAccept::clear_invoice_policy(&env, &invoice_id)
+    }
 
-        let verification = do_get_investor_verification(&env, &investor)
-            .ok_or(QuickLendXError::BusinessNotVerified)?;
-        match verification.status {
-            BusinessVerificationStatus::Verified => {
-                // Enforce tier/risk-aware limits from investor analytics.
-                validate_investor_investment(&env, &investor, bid_amount)?;
-            }
-            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
-            BusinessVerificationStatus::Rejected => {
-                return Err(QuickLendXError::BusinessNotVerified)
-            }
-        }
+    /// Get the auto-accept policy applying to an invoice: its own, or its business's.
+    pub fn get_invoice_auto_accept(env: Env, invoice_id: BytesN<32>) -> Option<AutoAcceptPolicy> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)?;
+        AutoAcceptStorage::get_effective_policy(&env, &invoice)
+    }
 
-        BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        let max_active_bids = BidStorage::get_max_active_bids_per_investor(&env);
-        if max_active_bids > 0 {
-            let active_bids = BidStorage::count_active_placed_bids_for_investor(&env, &investor);
-            if active_bids >= max_active_bids {
-                return Err(QuickLendXError::OperationNotAllowed);
-            }
-        }
-        validate_bid(&env, &invoice, bid_amount, expected_return, &investor)?;
-        // Create bid
-        let bid_id = BidStorage::generate_unique_bid_id(&env);
-        let current_timestamp = env.ledger().timestamp();
-        let bid = Bid {
-            bid_id: bid_id.clone(),
-            invoice_id: invoice_id.clone(),
-            investor: investor.clone(),
+    // ============================================================================
+    // Counter-Offer Negotiation
+    // ============================================================================
+
+    /// Counter-offer different terms on a placed bid.
+    ///
+    /// The invoice's business opens the negotiation; the business and the bidder then
+    /// alternate offers, each valid for `valid_for` seconds (1 hour to 7 days, and never
+    /// beyond the bid's own expiration), up to `MAX_NEGOTIATION_ROUNDS` offers.
+    ///
+    /// # Errors
+    /// * `Unauthorized` if `caller` is not a party or it is not the caller's turn
+    /// * `OperationNotAllowed` if the bid is not live, the invoice is sealed or the round
+    ///   limit is reached
+    /// * `InvalidTimestamp` for an out-of-range `valid_for`
+    /// * Bid validation errors for the proposed terms
+    pub fn counter_offer(
+        env: Env,
+        caller: Address,
+        bid_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        valid_for: u64,
+    ) -> Result<Negotiation, QuickLendXError> {
+        Negotiations::counter_offer(
+            &env,
+            &caller,
+            &bid_id,
             bid_amount,
             expected_return,
-            timestamp: current_timestamp,
-            status: BidStatus::Placed,
-            expiration_timestamp: Bid::default_expiration_with_env(&env, current_timestamp),
-        };
-        BidStorage::store_bid(&env, &bid);
-        // Track bid for this invoice
-        BidStorage::add_bid_to_invoice(&env, &invoice_id, &bid_id);
-
-        // Emit bid placed event
-        emit_bid_placed(&env, &bid);
-        audit::log_bid_placed(&env, invoice_id, investor, bid_amount, bid_id.clone());
-
-        // Send notification for business about new bid
-        let _ = NotificationSystem::notify_bid_received(&env, &invoice, &bid);
-
-        Ok(bid_id)
+            valid_for,
+        )
     }
 
-    /// Accept a bid (business only)
-    pub fn accept_bid(
+    /// Accept the other party's latest counter-offer and fund the invoice at the agreed
+    /// terms through escrow. The terms are checked like a new bid first.
+    ///
+    /// # Errors
+    /// * `Unauthorized` if `caller` made the offer or is not a party
+    /// * `OperationNotAllowed` if the offer is no longer open or has expired
+    /// * Any bid validation error for the agreed terms, including listing terms
+    /// * Any escrow/funding error from the accept path
+    pub fn accept_counter_offer(
         env: Env,
-        invoice_id: BytesN<32>,
+        caller: Address,
         bid_id: BytesN<32>,
     ) -> Result<(), QuickLendXError> {
         reentrancy::with_payment_guard(&env, || {
-            Self::accept_bid_impl(env.clone(), invoice_id.clone(), bid_id.clone())
+            let (invoice, bid) = Negotiations::accept_offer(
+                &env,
+                &caller,
+                &bid_id,
+                |invoice, investor, amount, ret| {
+                    Self::check_bid_terms(&env, invoice, investor, amount, ret).map(|_| ())
+                },
+            )?;
+            Self::fund_bid(env.clone(), invoice, bid)
         })
     }
 
-    fn accept_bid_impl(
+    /// End a negotiation without agreement; the bid keeps its original terms.
+    pub fn reject_counter_offer(
         env: Env,
-        invoice_id: BytesN<32>,
+        caller: Address,
         bid_id: BytesN<32>,
     ) -> Result<(), QuickLendXError> {
-        BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+        Negotiations::reject_offer(&env, &caller, &bid_id)
+    }
+
+    /// Get the negotiation on a bid, if any.
+    pub fn get_negotiation(env: Env, bid_id: BytesN<32>) -> Option<Negotiation> {
+        NegotiationStorage::get_negotiation(&env, &bid_id)
+    }
+
+    // ============================================================================
+    // Secondary Market
+    // ============================================================================
+
+    /// List an Active investment for sale at `asking_price` (holder only).
+    ///
+    /// # Errors
+    /// * `NotInvestor` if `seller` does not hold the investment and its whole receipt
+    /// * `InvalidStatus` if the investment is not Active on a Funded invoice
+    /// * `InvalidAmount` for a non-positive price, `OperationNotAllowed` if already listed
+    pub fn list_investment(
+        env: Env,
+        seller: Address,
+        investment_id: BytesN<32>,
+        asking_price: i128,
+    ) -> Result<PositionListing, QuickLendXError> {
+        SecondaryMarket::list(&env, &seller, &investment_id, asking_price)
+    }
+
+    /// Withdraw an investment listing (seller only).
+    pub fn cancel_investment_listing(
+        env: Env,
+        seller: Address,
+        investment_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        SecondaryMarket::cancel(&env, &seller, &investment_id)
+    }
+
+    /// Buy a listed investment. The asking price moves from buyer to seller and the
+    /// position, including future settlement payouts, moves to the buyer atomically.
+    pub fn buy_investment(
+        env: Env,
+        buyer: Address,
+        investment_id: BytesN<32>,
+    ) -> Result<Investment, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || SecondaryMarket::buy(&env, &buyer, &investment_id))
+    }
+
+    /// Get the listing of an investment, if it is for sale.
+    pub fn get_investment_listing(env: Env, investment_id: BytesN<32>) -> Option<PositionListing> {
+        SecondaryMarketStorage::get_listing(&env, &investment_id)
+    }
+
+    /// Get all investments currently listed for sale.
+    pub fn get_investment_listings(env: Env) -> Vec<PositionListing> {
+        SecondaryMarketStorage::get_listings(&env)
+    }
+
+    // ============================================================================
+    // Investment Receipts
+    // ============================================================================
+
+    /// Get the receipt of an investment.
+    pub fn get_investment_receipt(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Option<InvestmentReceipt> {
+        ReceiptStorage::get_receipt(&env, &investment_id)
+    }
+
+    /// Receipt units of an investment held by `holder`.
+    pub fn receipt_balance(env: Env, investment_id: BytesN<32>, holder: Address) -> i128 {
+        ReceiptStorage::balance(&env, &investment_id, &holder)
+    }
+
+    /// Transfer receipt units, and with them the matching share of future payouts.
+    pub fn receipt_transfer(
+        env: Env,
+        investment_id: BytesN<32>,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        Receipts::transfer(&env, &investment_id, &from, &to, amount)
+    }
+
+    /// Approve `spender` to transfer receipt units until `expiration_ledger`.
+    pub fn receipt_approve(
+        env: Env,
+        investment_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), QuickLendXError> {
+        Receipts::approve(
+            &env,
+            &investment_id,
+            &from,
+            &spender,
+            amount,
+            expiration_ledger,
+        )
+    }
+
+    /// Remaining receipt allowance of `spender` over `from`.
+    pub fn receipt_allowance(
+        env: Env,
+        investment_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+    ) -> i128 {
+        ReceiptStorage::allowance(&env, &investment_id, &from, &spender)
+    }
+
+    /// Transfer receipt units on behalf of `from`.
+    pub fn receipt_transfer_from(
+        env: Env,
+        investment_id: BytesN<32>,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        Receipts::transfer_from(&env, &investment_id, &spender, &from, &to, amount)
+    }
+
+    // ============================================================================
+    // Recourse Factoring
+    // ============================================================================
+
+    /// Offer an invoice on recourse or non-recourse terms (business only, before funding).
+    ///
+    /// # Errors
+    /// * `InvalidStatus` if the invoice is already funded
+    /// * `OperationNotAllowed` if recourse would be removed while bids are open
+    pub fn set_factoring_mode(
+        env: Env,
+        invoice_id: BytesN<32>,
+        mode: FactoringMode,
+    ) -> Result<(), QuickLendXError> {
+        Recourse::set_mode(&env, &invoice_id, mode)
+    }
+
+    /// Get the factoring mode of an invoice (non-recourse unless set).
+    pub fn get_factoring_mode(env: Env, invoice_id: BytesN<32>) -> FactoringMode {
+        RecourseStorage::get_mode(&env, &invoice_id)
+    }
+
+    /// Pay down the recourse obligation left by a defaulted recourse invoice, or by a
+    /// refunded invoice whose milestones had released escrow. Once all of its obligations
+    /// are repaid the business can upload invoices again.
+    pub fn repay_recourse(
+        env: Env,
+        business: Address,
+        invoice_id: BytesN<32>,
+        amount: i128,
+    ) -> Result<RecourseObligation, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            Recourse::repay(&env, &business, &invoice_id, amount)
+        })
+    }
+
+    /// Get the recourse obligation of a defaulted or refunded invoice, if any.
+    pub fn get_recourse_obligation(env: Env, invoice_id: BytesN<32>) -> Option<RecourseObligation> {
+        RecourseStorage::get_obligation(&env, &invoice_id)
+    }
+
+    /// Get the recourse obligations a business still has to repay.
+    pub fn get_outstanding_recourse(env: Env, business: Address) -> Vec<RecourseObligation> {
+        RecourseStorage::get_outstanding_obligations(&env, &business)
+    }
+
+    // ============================================================================
+    // Reverse Factoring
+    // ============================================================================
+
+    /// Register a payable the buyer owes to a supplier, approving it for early sale
+    /// (verified buyer only).
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` if the buyer is not a verified business
+    /// * `InvalidAmount`, `InvoiceDueDateInvalid`, `InvalidDescription`, `InvalidCurrency`
+    ///   for invalid terms
+    pub fn register_payable(
+        env: Env,
+        buyer: Address,
+        terms: PayableTerms,
+    ) -> Result<ApprovedPayable, QuickLendXError> {
+        ReverseFactoring::register(&env, &buyer, terms)
+    }
+
+    /// Withdraw a payable that has not been sold yet (buyer only).
+    pub fn cancel_payable(
+        env: Env,
+        buyer: Address,
+        payable_id: u64,
+    ) -> Result<(), QuickLendXError> {
+        ReverseFactoring::cancel(&env, &buyer, payable_id)
+    }
+
+    /// Sell an approved payable early (supplier only). Returns the new invoice, which is
+    /// verified, bid on and funded like any other but settled by the buyer.
+    pub fn sell_payable(
+        env: Env,
+        supplier: Address,
+        payable_id: u64,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        ReverseFactoring::sell(&env, &supplier, payable_id)
+    }
+
+    /// Get a payable by ID.
+    pub fn get_payable(env: Env, payable_id: u64) -> Option<ApprovedPayable> {
+        ReverseFactoringStorage::get_payable(&env, payable_id)
+    }
+
+    /// Get the payables registered by a buyer.
+    pub fn get_buyer_payables(env: Env, buyer: Address) -> Vec<ApprovedPayable> {
+        ReverseFactoringStorage::get_payables_by_buyer(&env, &buyer)
+    }
+
+    /// Get the payables owed to a supplier.
+    pub fn get_supplier_payables(env: Env, supplier: Address) -> Vec<ApprovedPayable> {
+        ReverseFactoringStorage::get_payables_by_supplier(&env, &supplier)
+    }
+
+    /// Get the payable an invoice was created from, if it is reverse-factored.
+    pub fn get_invoice_payable(env: Env, invoice_id: BytesN<32>) -> Option<ApprovedPayable> {
+        ReverseFactoringStorage::get_invoice_payable(&env, &invoice_id)
+    }
+
+    // ============================================================================
+    // Invoice Pools
+    // ============================================================================
+
+    /// Package Verified invoices into a pool with a senior and a junior tranche (admin,
+    /// with the consent of each invoice's business). Returns the new pool id.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `OperationNotAllowed` if the invoice list is invalid or an invoice is already pooled
+    /// * `InvalidAmount`, `InvalidCurrency`, `InvalidTimestamp` for invalid terms
+    /// * `InvoiceNotFound` / `InvalidStatus` if an invoice is missing or not Verified
+    pub fn create_invoice_pool(
+        env: Env,
+        admin: Address,
+        invoice_ids: Vec<BytesN<32>>,
+        terms: PoolTerms,
+    ) -> Result<u64, QuickLendXError> {
+        InvoicePools::create(&env, &admin, &invoice_ids, &terms).map(|pool| pool.pool_id)
+    }
+
+    /// Subscribe to a tranche of a pool (verified investor). The subscription that fills
+    /// both tranches funds every invoice of the pool.
+    pub fn subscribe_to_pool(
+        env: Env,
+        investor: Address,
+        pool_id: u64,
+        tranche: TrancheKind,
+        amount: i128,
+    ) -> Result<InvoicePool, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            InvoicePools::subscribe(&env, &investor, pool_id, tranche, amount)
+        })
+    }
+
+    /// Withdraw a pool before it is funded (admin only), refunding its subscribers.
+    pub fn cancel_invoice_pool(
+        env: Env,
+        admin: Address,
+        pool_id: u64,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || InvoicePools::cancel(&env, &admin, pool_id))
+    }
+
+    /// Expire a pool left unfilled past its subscription deadline, refunding its
+    /// subscribers. Anyone can call this.
+    pub fn expire_invoice_pool(env: Env, pool_id: u64) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || InvoicePools::expire(&env, pool_id))
+    }
+
+    /// Claim the pool proceeds allotted to an investor's subscriptions. Returns the amount
+    /// paid.
+    pub fn claim_pool_proceeds(
+        env: Env,
+        investor: Address,
+        pool_id: u64,
+    ) -> Result<i128, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || InvoicePools::claim(&env, &investor, pool_id))
+    }
+
+    /// Get a pool by ID.
+    pub fn get_invoice_pool(env: Env, pool_id: u64) -> Option<InvoicePool> {
+        PoolStorage::get_pool(&env, pool_id)
+    }
+
+    /// Get the pool an invoice belongs to, if any.
+    pub fn get_pool_of_invoice(env: Env, invoice_id: BytesN<32>) -> Option<u64> {
+        PoolStorage::get_pool_for_invoice(&env, &invoice_id)
+    }
+
+    /// Get an investor's subscriptions to a pool.
+    pub fn get_pool_position(env: Env, pool_id: u64, investor: Address) -> Option<PoolPosition> {
+        PoolStorage::get_position(&env, pool_id, &investor)
+    }
+
+    /// Get how a pool's proceeds are currently split between its tranches.
+    pub fn get_pool_waterfall(env: Env, pool_id: u64) -> Result<PoolWaterfall, QuickLendXError> {
+        PoolStorage::get_pool(&env, pool_id)
+            .map(|pool| pool.waterfall())
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    // ============================================================================
+    // Liquidity Vaults
+    // ============================================================================
+
+    /// Open a passive liquidity vault that funds invoices matching `policy` out of its
+    /// deposits (admin only). Returns the new vault id.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    /// * `InvalidAmount` / `InvalidTimestamp` for an invalid policy or idle cap
+    /// * `OperationNotAllowed` if the maximum number of vaults exists
+    pub fn create_liquidity_vault(
+        env: Env,
+        admin: Address,
+        currency: Address,
+        curator: Address,
+        policy: VaultPolicy,
+        max_idle: i128,
+    ) -> Result<u64, QuickLendXError> {
+        LiquidityVaults::create(&env, &admin, &currency, &curator, &policy, max_idle)
+            .map(|vault| vault.vault_id)
+    }
+
+    /// Replace a vault's allocation policy and idle cap (curator or admin).
+    pub fn set_vault_policy(
+        env: Env,
+        caller: Address,
+        vault_id: u64,
+        policy: VaultPolicy,
+        max_idle: i128,
+    ) -> Result<(), QuickLendXError> {
+        LiquidityVaults::set_policy(&env, &caller, vault_id, &policy, max_idle)
+    }
+
+    /// Deposit into a vault (verified investor). Returns the shares issued.
+    pub fn deposit_to_vault(
+        env: Env,
+        depositor: Address,
+        vault_id: u64,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            LiquidityVaults::deposit(&env, &depositor, vault_id, amount)
+        })
+    }
+
+    /// Redeem vault shares out of the vault's idle liquidity. Returns the amount paid.
+    pub fn withdraw_from_vault(
+        env: Env,
+        holder: Address,
+        vault_id: u64,
+        shares: i128,
+    ) -> Result<i128, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            LiquidityVaults::withdraw(&env, &holder, vault_id, shares)
+        })
+    }
+
+    /// Get a vault by ID.
+    pub fn get_liquidity_vault(env: Env, vault_id: u64) -> Option<LiquidityVault> {
+        VaultStorage::get_vault(&env, vault_id)
+    }
+
+    /// Get the vault shares held by an address.
+    pub fn get_vault_shares(env: Env, vault_id: u64, holder: Address) -> i128 {
+        VaultStorage::get_shares(&env, vault_id, &holder)
+    }
+
+    /// Get the current value of a holder's vault shares.
+    pub fn get_vault_share_value(
+        env: Env,
+        vault_id: u64,
+        holder: Address,
+    ) -> Result<i128, QuickLendXError> {
+        let vault =
+            VaultStorage::get_vault(&env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Ok(vault.share_value(VaultStorage::get_shares(&env, vault_id, &holder)))
+    }
+
+    // ============================================================================
+    // Credit Lines
+    // ============================================================================
+
+    /// Propose a revolving credit limit to a verified business (verified investor). The
+    /// business has to accept the line before drawing on it. Returns the new line id.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the lender or business is not verified
+    /// * `OperationNotAllowed` if the lender names itself or the business has too many
+    ///   Active lines
+    /// * `InvalidAmount` / `InvalidTimestamp` / `InvalidCurrency` for invalid terms
+    pub fn open_credit_line(
+        env: Env,
+        lender: Address,
+        business: Address,
+        terms: CreditLineTerms,
+    ) -> Result<u64, QuickLendXError> {
+        CreditLines::open(&env, &lender, &business, &terms).map(|line| line.line_id)
+    }
+
+    /// Propose a revolving credit limit to a verified business out of a liquidity vault
+    /// (vault curator or admin). Returns the new line id.
+    pub fn open_vault_credit_line(
+        env: Env,
+        caller: Address,
+        vault_id: u64,
+        business: Address,
+        terms: CreditLineTerms,
+    ) -> Result<u64, QuickLendXError> {
+        CreditLines::open_for_vault(&env, &caller, vault_id, &business, &terms)
+            .map(|line| line.line_id)
+    }
+
+    /// Accept a credit line proposed to a business, opening it for draws (business only).
+    pub fn accept_credit_line(
+        env: Env,
+        business: Address,
+        line_id: u64,
+    ) -> Result<(), QuickLendXError> {
+        CreditLines::accept(&env, &business, line_id)
+    }
+
+    /// Change the limit and expiry of a credit line (its lender).
+    pub fn update_credit_line(
+        env: Env,
+        caller: Address,
+        line_id: u64,
+        limit: i128,
+        expires_at: u64,
+    ) -> Result<CreditLine, QuickLendXError> {
+        CreditLines::update(&env, &caller, line_id, limit, expires_at)
+    }
+
+    /// Close a credit line to new draws, or withdraw a proposed one (its lender).
+    pub fn close_credit_line(
+        env: Env,
+        caller: Address,
+        line_id: u64,
+    ) -> Result<(), QuickLendXError> {
+        CreditLines::close(&env, &caller, line_id)
+    }
+
+    /// Draw on a credit line by pledging a Verified invoice (business only). The draw is
+    /// funded straight away through escrow at the line's advance rate. Returns the bid
+    /// recording the draw.
+    ///
+    /// # Errors
+    /// * `InsufficientFunds` if the draw exceeds the available credit, or a vault line's
+    ///   vault lacks the idle liquidity
+    /// * `InvalidStatus` / `InvalidTimestamp` if the line is not Active or has expired
+    /// * See `CreditLines::draw` and `accept_bid` for the remaining cases
+    pub fn draw_credit_line(
+        env: Env,
+        business: Address,
+        line_id: u64,
+        invoice_id: BytesN<32>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            let (invoice, bid) = CreditLines::draw(&env, &business, line_id, &invoice_id)?;
+            let bid_id = bid.bid_id.clone();
+            Self::fund_bid(env.clone(), invoice, bid)?;
+            Ok(bid_id)
+        })
+    }
+
+    /// Get a credit line by ID, with its limit, utilization and expiry.
+    pub fn get_credit_line(env: Env, line_id: u64) -> Option<CreditLine> {
+        CreditLineStorage::get_line(&env, line_id)
+    }
+
+    /// Get the credit still available on a line.
+    pub fn get_available_credit(env: Env, line_id: u64) -> Result<i128, QuickLendXError> {
+        CreditLineStorage::get_line(&env, line_id)
+            .map(|line| line.available())
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    /// Get the share of a credit line's limit in use, in bps.
+    pub fn get_credit_utilization(env: Env, line_id: u64) -> Result<u32, QuickLendXError> {
+        CreditLineStorage::get_line(&env, line_id)
+            .map(|line| line.utilization_bps())
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    /// Get the credit lines held by a business.
+    pub fn get_business_credit_lines(env: Env, business: Address) -> Vec<CreditLine> {
+        CreditLineStorage::get_lines_by_business(&env, &business)
+    }
+
+    /// Get the credit lines committed by a lender.
+    pub fn get_lender_credit_lines(env: Env, lender: Address) -> Vec<CreditLine> {
+        CreditLineStorage::get_lines_by_lender(&env, &lender)
+    }
+
+    // ============================================================================
+    // Standing Orders
+    // ============================================================================
+
+    /// Register a standing order that bids automatically on matching invoices when
+    /// they are verified (investor only).
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the investor is not verified
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    /// * `InvalidAmount` / `InvalidTimestamp` for inconsistent terms
+    /// * `OperationNotAllowed` if the investor already has the maximum number of orders
+    pub fn create_standing_order(
+        env: Env,
+        investor: Address,
+        terms: StandingOrderTerms,
+    ) -> Result<u64, QuickLendXError> {
+        StandingOrders::create(&env, &investor, &terms)
+    }
+
+    /// Cancel a standing order (investor only). Bids already placed are not withdrawn.
+    pub fn cancel_standing_order(
+        env: Env,
+        investor: Address,
+        order_id: u64,
+    ) -> Result<(), QuickLendXError> {
+        StandingOrders::cancel(&env, &investor, order_id)
+    }
+
+    /// Get a standing order by id.
+    pub fn get_standing_order(env: Env, order_id: u64) -> Option<StandingOrder> {
+        StandingOrderStorage::get_order(&env, order_id)
+    }
+
+    /// Get all standing orders of an investor, including cancelled ones.
+    pub fn get_investor_standing_orders(env: Env, investor: Address) -> Vec<StandingOrder> {
+        StandingOrderStorage::get_orders_by_investor(&env, &investor)
+    }
+
+    /// Current exposure of a standing order: open bids plus unsettled funded bids.
+    pub fn get_standing_order_exposure(env: Env, order_id: u64) -> Result<i128, QuickLendXError> {
+        let order = StandingOrderStorage::get_order(&env, order_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Ok(StandingOrders::exposure(&env, &order))
+    }
+
+    // ============================================================================
+    // Sealed-Bid Auctions
+    // ============================================================================
+
+    /// Put a Verified invoice into sealed-bid mode (business only).
+    ///
+    /// Investors commit bid hashes for `commit_window` seconds, then reveal them during
+    /// the following `reveal_window` seconds. Plain `place_bid` is rejected on the invoice
+    /// and bids cannot be accepted until the reveal window has closed.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Verified
+    /// * `OperationNotAllowed` if already sealed or bids were already placed
+    /// * `InvalidTimestamp` if a window is shorter than 1 hour or longer than 30 days
+    pub fn open_sealed_auction(
+        env: Env,
+        invoice_id: BytesN<32>,
+        commit_window: u64,
+        reveal_window: u64,
+    ) -> Result<SealedAuction, QuickLendXError> {
+        SealedBidding::open_auction(&env, &invoice_id, commit_window, reveal_window)
+    }
+
+    /// Commit a hidden bid on a sealed invoice (investor only).
+    ///
+    /// `commitment` must equal `compute_bid_commitment` for the bid revealed later.
+    /// Re-committing before the commit deadline replaces the previous commitment.
+    pub fn commit_bid(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        commitment: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        SealedBidding::commit(&env, &investor, &invoice_id, &commitment)
+    }
+
+    /// Reveal a committed bid during the reveal window and place it (investor only).
+    ///
+    /// The revealed bid goes through the same validation as `place_bid`.
+    ///
+    /// # Returns
+    /// * `Ok(BytesN<32>)` - The placed bid ID
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` outside the reveal window, `StorageKeyNotFound` without a
+    ///   commitment, `Unauthorized` if the values do not match, or any `place_bid` error
+    pub fn reveal_bid(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        salt: BytesN<32>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        investor.require_auth();
+        SealedBidding::verify_reveal(
+            &env,
+            &investor,
+            &invoice_id,
+            bid_amount,
+            expected_return,
+            &salt,
+        )?;
+        let bid_id = Self::place_bid_impl(
+            env.clone(),
+            investor.clone(),
+            invoice_id.clone(),
+            bid_amount,
+            expected_return,
+            false,
+        )?;
+        SealedBidding::mark_revealed(&env, &investor, &invoice_id, &bid_id)?;
+        Ok(bid_id)
+    }
+
+    /// Expire commitments that were not revealed before the reveal deadline.
+    pub fn expire_bid_commitments(env: Env, invoice_id: BytesN<32>) -> u32 {
+        SealedBidding::expire_unrevealed(&env, &invoice_id)
+    }
+
+    /// Compute the commitment hash for a sealed bid.
+    pub fn compute_bid_commitment(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        SealedBidding::compute_commitment(
+            &env,
+            &investor,
+            &invoice_id,
+            bid_amount,
+            expected_return,
+            &salt,
+        )
+    }
+
+    /// Get the sealed-bid schedule for an invoice, if any.
+    pub fn get_sealed_auction(env: Env, invoice_id: BytesN<32>) -> Option<SealedAuction> {
+        SealedBidStorage::get_auction(&env, &invoice_id)
+    }
+
+    /// Get an investor's commitment on a sealed invoice.
+    pub fn get_bid_commitment(
+        env: Env,
+        invoice_id: BytesN<32>,
+        investor: Address,
+    ) -> Option<BidCommitment> {
+        SealedBidStorage::get_commitment(&env, &invoice_id, &investor)
+    }
+
+    /// Get all commitments on a sealed invoice.
+    pub fn get_bid_commitments(env: Env, invoice_id: BytesN<32>) -> Vec<BidCommitment> {
+        SealedBidStorage::get_commitments(&env, &invoice_id)
+    }
+
+    /// Remove bids that have passed their expiration window
+    pub fn cleanup_expired_bids(env: Env, invoice_id: BytesN<32>) -> u32 {
+        BidStorage::cleanup_expired_bids(&env, &invoice_id)
+    }
+
+    /// Cancel a placed bid (investor only, Placed → Cancelled).
+    pub fn cancel_bid(env: Env, bid_id: BytesN<32>) -> bool {
+        bid::BidStorage::cancel_bid(&env, &bid_id)
+    }
+
+    /// Get all bids placed by an investor across all invoices.
+    pub fn get_all_bids_by_investor(env: Env, investor: Address) -> Vec<Bid> {
+        bid::BidStorage::get_all_bids_by_investor(&env, &investor)
+    }
+
+    /// Place a bid on an invoice
+    ///
+    /// Validates:
+    /// - Invoice exists and is verified
+    /// - Bid amount is positive
+    /// - Investor is authorized and verified
+    /// - Creates and stores the bid
+    pub fn place_bid(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        // Authorization check: Only the investor can place their own bid
+        investor.require_auth();
+
+        // Sealed-bid invoices only accept bids through commit/reveal
+        SealedBidding::require_open_bidding(&env, &invoice_id)?;
+
+        Self::place_bid_impl(
+            env,
+            investor,
+            invoice_id,
+            bid_amount,
+            expected_return,
+            false,
+        )
+    }
+
+    /// Place a bid that only stands once the invoice's debtor has confirmed it.
+    ///
+    /// The condition is set before the bid can be funded, so a buy-now price or
+    /// auto-accept policy does not fund it until the debtor confirms; see
+    /// `set_bid_debtor_condition`.
+    pub fn place_conditional_bid(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        investor.require_auth();
+        SealedBidding::require_open_bidding(&env, &invoice_id)?;
+        Self::place_bid_impl(env, investor, invoice_id, bid_amount, expected_return, true)
+    }
+
+    /// Shared bid placement used by `place_bid`, `place_conditional_bid`, `reveal_bid`
+    /// and standing orders. Caller must have authorized `investor`.
+    fn place_bid_impl(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        requires_debtor_confirmation: bool,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let checked = Self::check_bid_placement(
+            &env,
+            &investor,
+            &invoice_id,
+            bid_amount,
+            expected_return,
+            requires_debtor_confirmation,
+        )?;
+        Self::place_checked_bid(&env, &checked)
+    }
+
+    /// Check, without writing anything, that `investor` can place a bid on the invoice
+    /// now, including that its funds cover a bid that is funded as soon as it is placed.
+    /// Expired bids must have been cleaned up first.
+    fn check_bid_placement(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        requires_debtor_confirmation: bool,
+    ) -> Result<CheckedBid, QuickLendXError> {
+        let env = env.clone();
+        let investor = investor.clone();
+        let invoice_id = invoice_id.clone();
+        // Validate bid amount is positive
+        if bid_amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        // Enforce per-investor rate limit on active (Placed) bids across all invoices.
+        let active_bids =
+            bid::BidStorage::count_active_bids_by_investor(&env, &investor);
+        if active_bids >= MAX_ACTIVE_BIDS_PER_INVESTOR {
+            // Use a generic business-logic error to avoid adding new error variants.
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        // Validate invoice exists
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let (buy_now, tier) =
+            Self::check_bid_terms(&env, &invoice, &investor, bid_amount, expected_return)?;
+
+        let max_active_bids = BidStorage::get_max_active_bids_per_investor(&env);
+        if max_active_bids > 0 {
+            let active_bids = BidStorage::count_active_placed_bids_for_investor(&env, &investor);
+            if active_bids >= max_active_bids {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+        require_no_open_bid(&env, &invoice, &investor)?;
+        let fund_now = Self::is_preapproved(
+            &env,
+            &invoice,
+            bid_amount,
+            expected_return,
+            requires_debtor_confirmation,
+            buy_now,
+            &tier,
+        );
+        if fund_now {
+            check_transfer_in(&env, &invoice.currency, &investor, bid_amount)?;
+        }
+        Ok(CheckedBid {
+            invoice,
+            investor,
+            bid_amount,
+            expected_return,
+            requires_debtor_confirmation,
+            fund_now,
+        })
+    }
+
+    /// Check that the invoice is open for bids and that `investor` may bid these terms
+    /// on it: investor verification and limits, protocol bid limits and the invoice's
+    /// listing terms. Returns whether the terms meet the buy-now price, and the
+    /// investor's tier.
+    fn check_bid_terms(
+        env: &Env,
+        invoice: &Invoice,
+        investor: &Address,
+        bid_amount: i128,
+        expected_return: i128,
+    ) -> Result<(bool, InvestorTier), QuickLendXError> {
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        InvoicePools::require_unpooled(env, &invoice.id)?;
+        currency::CurrencyWhitelist::require_allowed_currency(env, &invoice.currency)?;
+
+        // Check invoice currency is whitelisted
+        if !CurrencyWhitelist::is_allowed_currency(env, &invoice.currency) {
+            return Err(QuickLendXError::InvalidCurrency);
+        }
+
+        let verification = do_get_investor_verification(env, investor)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {
+                // Enforce tier/risk-aware limits from investor analytics.
+                validate_investor_investment(env, investor, bid_amount)?;
+            }
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        validate_bid_terms(env, invoice, bid_amount, expected_return, investor)?;
+        let buy_now = Listing::check_bid(env, invoice, bid_amount, expected_return)?;
+        Ok((buy_now, verification.tier))
+    }
+
+    /// Store a bid that passed `check_bid_placement`, and fund it straight away when it
+    /// is preapproved.
+    fn place_checked_bid(env: &Env, checked: &CheckedBid) -> Result<BytesN<32>, QuickLendXError> {
+        let invoice = &checked.invoice;
+        let bid_id = BidStorage::generate_unique_bid_id(env);
+        let current_timestamp = env.ledger().timestamp();
+        let bid = Bid {
+            bid_id: bid_id.clone(),
+            invoice_id: invoice.id.clone(),
+            investor: checked.investor.clone(),
+            bid_amount: checked.bid_amount,
+            expected_return: checked.expected_return,
+            timestamp: current_timestamp,
+            status: BidStatus::Placed,
+            expiration_timestamp: Bid::default_expiration_with_env(env, current_timestamp),
+        };
+        BidStorage::store_bid(env, &bid);
+        // Track bid for this invoice
+        BidStorage::add_bid_to_invoice(env, &invoice.id, &bid_id);
+        if checked.requires_debtor_confirmation {
+            BidStorage::set_requires_debtor_confirmation(env, &bid_id, true);
+            emit_bid_condition_set(env, &bid, true);
+        }
+
+        // Emit bid placed event
+        emit_bid_placed(env, &bid);
+        audit::log_bid_placed(
+            env,
+            invoice.id.clone(),
+            checked.investor.clone(),
+            checked.bid_amount,
+            bid_id.clone(),
+        );
+
+        // Send notification for business about new bid
+        let _ = NotificationSystem::notify_bid_received(env, invoice, &bid);
+
+        if checked.fund_now {
+            reentrancy::with_payment_guard(env, || {
+                Self::fund_bid(env.clone(), invoice.clone(), bid.clone())
+            })?;
+        }
+
+        Ok(bid_id)
+    }
+
+    /// Whether a bid is funded as soon as it is placed: it meets the buy-now price or the
+    /// business's auto-accept policy. Sealed auctions are settled only after the reveal
+    /// window, and a conditional bid waits for the debtor's confirmation.
+    fn is_preapproved(
+        env: &Env,
+        invoice: &Invoice,
+        bid_amount: i128,
+        expected_return: i128,
+        conditional: bool,
+        buy_now: bool,
+        investor_tier: &InvestorTier,
+    ) -> bool {
+        if SealedBidding::is_bidding_open(env, &invoice.id)
+            || (conditional && !invoice.is_debtor_confirmed())
+        {
+            return false;
+        }
+        buy_now || AutoAccept::qualifies(env, invoice, bid_amount, expected_return, investor_tier)
+    }
+
+    /// Fund a bid straight away when it meets the buy-now price or the business's
+    /// auto-accept policy. Sealed auctions are settled only after the reveal window,
+    /// so neither applies there.
+    fn fund_if_preapproved(
+        env: &Env,
+        invoice: &Invoice,
+        bid: &Bid,
+        buy_now: bool,
+        investor_tier: &InvestorTier,
+    ) -> Result<(), QuickLendXError> {
+        let conditional = BidStorage::requires_debtor_confirmation(env, &bid.bid_id);
+        if Self::is_preapproved(
+            env,
+            invoice,
+            bid.bid_amount,
+            bid.expected_return,
+            conditional,
+            buy_now,
+            investor_tier,
+        ) {
+            reentrancy::with_payment_guard(env, || {
+                Self::fund_bid(env.clone(), invoice.clone(), bid.clone())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Accept a bid (business only)
+    pub fn accept_bid(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            Self::accept_bid_impl(env.clone(), invoice_id.clone(), bid_id.clone())
+        })
+    }
+
+    fn accept_bid_impl(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
         let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
         let invoice_id = bid.invoice_id.clone();
         BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        let mut bid =
+        let bid =
             BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
         invoice.business.require_auth();
+        Self::fund_bid(env, invoice, bid)
+    }
+
+    /// Check, without writing anything, that `fund_bid` can fund the bid now: the
+    /// invoice is open, the bid's vault has the liquidity and the investor the funds.
+    fn check_funding(env: &Env, invoice: &Invoice, bid: &Bid) -> Result<(), QuickLendXError> {
         if invoice.status != InvoiceStatus::Verified || bid.status != BidStatus::Placed {
             return Err(QuickLendXError::InvalidStatus);
         }
+        SealedBidding::require_bidding_closed(env, &invoice.id)?;
+        BidStorage::require_debtor_condition_met(env, invoice, bid)?;
+        InvoicePools::require_unpooled(env, &invoice.id)?;
+        LiquidityVaults::check_allocation(env, invoice, bid)?;
+        check_transfer_in(env, &invoice.currency, &bid.investor, bid.bid_amount)
+    }
+
+    /// Escrow/investment flow shared by `accept_bid` and bids that satisfy a standing
+    /// business instruction (e.g. a buy-now price). Caller handles business authorization.
+    fn fund_bid(env: Env, mut invoice: Invoice, mut bid: Bid) -> Result<(), QuickLendXError> {
+        let invoice_id = bid.invoice_id.clone();
+        Self::check_funding(&env, &invoice, &bid)?;
+        LiquidityVaults::allocate(&env, &invoice, &bid)?;
 
         let escrow_id = create_escrow(
             &env,
@@ -1113,6 +2469,7 @@ impl QuickLendXContract {
             insurance: Vec::new(&env),
         };
         InvestmentStorage::store_investment(&env, &investment);
+        Receipts::mint(&env, &investment, &escrow_id);
 
         let escrow = EscrowStorage::get_escrow(&env, &escrow_id)
             .ok_or(QuickLendXError::StorageKeyNotFound)?;
@@ -1226,21 +2583,130 @@ impl QuickLendXContract {
         Ok(())
     }
 
+    /// Amend a placed bid in place (investor only).
+    ///
+    /// Updates the amount, expected return and optionally the expiration without
+    /// changing the bid id or its original timestamp, so time priority is kept. The new
+    /// terms are re-validated like a new bid (including listing terms) and the previous
+    /// terms are appended to the bid's amendment history.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `OperationNotAllowed` if the bid is not Placed, has expired, belongs to a sealed
+    ///   auction or has reached `MAX_BID_AMENDMENTS`
+    /// * `InvalidStatus` if the invoice is no longer Verified
+    /// * `InvalidTimestamp` if the new expiration is not in the future or exceeds the bid TTL
+    /// * Any error from `validate_bid_terms` / listing terms
+    pub fn amend_bid(
+        env: Env,
+        bid_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        expiration_timestamp: Option<u64>,
+    ) -> Result<(), QuickLendXError> {
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        SealedBidding::require_open_bidding(&env, &bid.invoice_id)?;
+
+        let now = env.ledger().timestamp();
+        if bid.status != BidStatus::Placed || bid.is_expired(now) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let invoice = InvoiceStorage::get_invoice(&env, &bid.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        validate_bid_terms(&env, &invoice, bid_amount, expected_return, &bid.investor)?;
+        let buy_now = Listing::check_bid(&env, &invoice, bid_amount, expected_return)?;
+        if let Some(expiration) = expiration_timestamp {
+            if expiration <= now || expiration > Bid::default_expiration_with_env(&env, now) {
+                return Err(QuickLendXError::InvalidTimestamp);
+            }
+        }
+
+        let amendment = BidAmendment {
+            previous_amount: bid.bid_amount,
+            previous_expected_return: bid.expected_return,
+            previous_expiration: bid.expiration_timestamp,
+            amended_at: now,
+        };
+        BidStorage::record_amendment(&env, &bid_id, &amendment)?;
+        bid.bid_amount = bid_amount;
+        bid.expected_return = expected_return;
+        if let Some(expiration) = expiration_timestamp {
+            bid.expiration_timestamp = expiration;
+        }
+        BidStorage::update_bid(&env, &bid);
+
+        emit_bid_amended(&env, &bid, &amendment);
+        audit::log_bid_amended(
+            &env,
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            bid_amount,
+        );
+
+        let tier = do_get_investor_verification(&env, &bid.investor)
+            .map(|verification| verification.tier)
+            .unwrap_or(InvestorTier::Basic);
+        Self::fund_if_preapproved(&env, &invoice, &bid, buy_now, &tier)
+    }
+
+    /// Get the amendment history of a bid, oldest first.
+    pub fn get_bid_amendments(env: Env, bid_id: BytesN<32>) -> Vec<BidAmendment> {
+        BidStorage::get_amendments(&env, &bid_id)
+    }
+
+    /// Make a placed bid conditional on the debtor confirming the invoice, or lift the
+    /// condition (investor only).
+    ///
+    /// Until the invoice is confirmed, a conditional bid is left out of `get_best_bid` and
+    /// `get_ranked_bids` and cannot be accepted. A bid meeting a buy-now price or
+    /// auto-accept policy is funded as it is placed; use `place_conditional_bid` to make
+    /// such a bid conditional from the start.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `OperationNotAllowed` if the bid is no longer Placed
+    pub fn set_bid_debtor_condition(
+        env: Env,
+        bid_id: BytesN<32>,
+        requires_debtor_confirmation: bool,
+    ) -> Result<(), QuickLendXError> {
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        BidStorage::set_requires_debtor_confirmation(&env, &bid_id, requires_debtor_confirmation);
+        emit_bid_condition_set(&env, &bid, requires_debtor_confirmation);
+        Ok(())
+    }
+
+    /// Whether a bid only stands once the invoice's debtor has confirmed it.
+    pub fn bid_requires_debtor_confirmation(env: Env, bid_id: BytesN<32>) -> bool {
+        BidStorage::requires_debtor_confirmation(&env, &bid_id)
+    }
+
     /// Settle an invoice (business or automated process)
     pub fn settle_invoice(
         env: Env,
         invoice_id: BytesN<32>,
         payment_amount: i128,
     ) -> Result<(), QuickLendXError> {
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+        let investments = InvestmentStorage::get_investments_by_invoice(&env, &invoice_id);
 
         let result = reentrancy::with_payment_guard(&env, || {
             do_settle_invoice(&env, &invoice_id, payment_amount)
         });
 
         if result.is_ok() {
-            if let Some(inv) = investment {
-                let is_successful = payment_amount >= inv.amount;
+            let funded_total: i128 = investments.iter().map(|inv| inv.amount).sum();
+            let is_successful = payment_amount >= funded_total;
+            for inv in investments.iter() {
                 let _ = update_investor_analytics(&env, &inv.investor, inv.amount, is_successful);
             }
         }
@@ -1261,6 +2727,11 @@ impl QuickLendXContract {
             .ok_or(QuickLendXError::StorageKeyNotFound)
     }
 
+    /// Get every investment funding an invoice (several for syndicated invoices).
+    pub fn get_invoice_investments(env: Env, invoice_id: BytesN<32>) -> Vec<Investment> {
+        InvestmentStorage::get_investments_by_invoice(&env, &invoice_id)
+    }
+
     /// Get an investment by ID.
     ///
     /// # Returns
@@ -1305,19 +2776,173 @@ impl QuickLendXContract {
         do_process_partial_payment(&env, &invoice_id, payment_amount, transaction_id)
     }
 
+    /// Process a partial payment from a third party: the invoice's debtor or a payer the
+    /// business approved (the obligor may use this too). The recorded amount is collected
+    /// from the payer when the invoice settles.
+    ///
+    /// # Errors
+    /// * `NotBusinessOwner` if `payer` may not pay the invoice
+    /// * `OperationNotAllowed` if the payer already used `transaction_id` on the invoice
+    pub fn process_payment_from(
+        env: Env,
+        payer: Address,
+        invoice_id: BytesN<32>,
+        payment_amount: i128,
+        transaction_id: String,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            do_process_payment_from(&env, &invoice_id, &payer, payment_amount, transaction_id)
+        })
+    }
+
+    /// Pay several invoices at once from `payer` (at most 50). A payment covering what is
+    /// left due settles its invoice. Returns a result per payment: rejected payments carry
+    /// their error code and leave their invoice untouched.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if there are no payments or too many
+    pub fn process_batch_payments(
+        env: Env,
+        payer: Address,
+        payments: Vec<BatchPayment>,
+    ) -> Result<Vec<BatchPaymentResult>, QuickLendXError> {
+        let results = reentrancy::with_payment_guard(&env, || {
+            BatchPayments::process(&env, &payer, &payments)
+        })?;
+        record_settled_batch(&env, &results);
+        Ok(results)
+    }
+
+    /// Allocate one lump sum from `payer` across invoices (at most 50) in `order`, paying
+    /// each what is left due until the sum runs out. `nonce` protects each invoice against
+    /// the same allocation being replayed. Returns a result per invoice, in allocation order.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `total` is not positive, or there are no invoices or too many
+    pub fn allocate_payment(
+        env: Env,
+        payer: Address,
+        invoice_ids: Vec<BytesN<32>>,
+        total: i128,
+        order: AllocationOrder,
+        nonce: String,
+    ) -> Result<Vec<BatchPaymentResult>, QuickLendXError> {
+        let results = reentrancy::with_payment_guard(&env, || {
+            BatchPayments::allocate(&env, &payer, &invoice_ids, total, order, nonce)
+        })?;
+        record_settled_batch(&env, &results);
+        Ok(results)
+    }
+
+    /// Approve or revoke a third-party payer for an invoice (business only).
+    pub fn set_approved_payer(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payer: Address,
+        approved: bool,
+    ) -> Result<(), QuickLendXError> {
+        do_set_approved_payer(&env, &invoice_id, &payer, approved)
+    }
+
+    /// Whether the business approved `payer` to pay an invoice.
+    pub fn is_approved_payer(env: Env, invoice_id: BytesN<32>, payer: Address) -> bool {
+        do_is_approved_payer(&env, &invoice_id, &payer)
+    }
+
+    /// Get an invoice's payment records, with the party each was paid by, for
+    /// `[start, start + limit)` (at most 100).
+    pub fn get_payment_records(
+        env: Env,
+        invoice_id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<SettlementPaymentRecord>, QuickLendXError> {
+        do_get_payment_records(&env, &invoice_id, start, limit)
+    }
+
+    /// Get an invoice's payment progress, including its installment schedule.
+    pub fn get_invoice_progress(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<Progress, QuickLendXError> {
+        do_get_invoice_progress(&env, &invoice_id)
+    }
+
+    /// Opt in or out of auto-debit: once in, what the obligor owes on its funded invoices
+    /// can be pulled from the token allowance it grants the contract, from their due date.
+    pub fn set_auto_debit(env: Env, obligor: Address, enabled: bool) {
+        AutoDebit::set_enabled(&env, &obligor, enabled)
+    }
+
+    /// Whether an obligor has opted into auto-debit.
+    pub fn is_auto_debit_enabled(env: Env, obligor: Address) -> bool {
+        AutoDebit::is_enabled(&env, &obligor)
+    }
+
+    /// Pull what is owed on a due invoice from its obligor's allowance and settle it
+    /// (anyone can call). Returns false when the pull was not covered: the failure is
+    /// recorded and the parties are notified that the payment is overdue.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Funded
+    /// * `OperationNotAllowed` if the obligor has not opted into auto-debit, or the invoice
+    ///   is not due yet
+    pub fn collect_due(env: Env, invoice_id: BytesN<32>) -> Result<bool, QuickLendXError> {
+        let investments = InvestmentStorage::get_investments_by_invoice(&env, &invoice_id);
+        let collected =
+            reentrancy::with_payment_guard(&env, || AutoDebit::collect_due(&env, &invoice_id))?;
+        if collected {
+            for inv in investments.iter() {
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, true);
+            }
+        }
+        Ok(collected)
+    }
+
+    /// Get the failed auto-debit pulls recorded on an invoice, oldest first.
+    pub fn get_collection_failures(env: Env, invoice_id: BytesN<32>) -> Vec<CollectionFailure> {
+        AutoDebit::get_failures(&env, &invoice_id)
+    }
+
+    /// Set the daily late-payment interest rate and its cap, both in bps (admin only).
+    pub fn set_late_interest_config(
+        env: Env,
+        admin: Address,
+        config: LateInterestConfig,
+    ) -> Result<(), QuickLendXError> {
+        LatePaymentInterest::set_config(&env, &admin, &config)
+    }
+
+    /// Get the late-payment interest configuration, if one was set.
+    pub fn get_late_interest_config(env: Env) -> Option<LateInterestConfig> {
+        LatePaymentInterest::get_config(&env)
+    }
+
+    /// Late-payment interest owed on an invoice, accrued after its due date at the
+    /// configured daily rate up to the grace deadline, and capped.
+    pub fn get_late_payment_interest(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<i128, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(LatePaymentInterest::current(&env, &invoice))
+    }
+
     /// Handle invoice default (admin only)
     /// This is the internal handler - use mark_invoice_defaulted for public API
     pub fn handle_default(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
         let _ = require_current_admin(&env)?;
 
-        // Get the investment to track investor analytics
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+        // Get the investments to track investor analytics
+        let investments = InvestmentStorage::get_investments_by_invoice(&env, &invoice_id);
 
         let result = do_handle_default(&env, &invoice_id);
 
         // Update investor analytics for failed investment
         if result.is_ok() {
-            if let Some(inv) = investment {
+            for inv in investments.iter() {
                 let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
             }
         }
@@ -1350,14 +2975,14 @@ impl QuickLendXContract {
     ) -> Result<(), QuickLendXError> {
         let _ = require_current_admin(&env)?;
 
-        // Get the investment to track investor analytics
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+        // Get the investments to track investor analytics
+        let investments = InvestmentStorage::get_investments_by_invoice(&env, &invoice_id);
 
         let result = do_mark_invoice_defaulted(&env, &invoice_id, grace_period);
 
         // Update investor analytics for failed investment
         if result.is_ok() {
-            if let Some(inv) = investment {
+            for inv in investments.iter() {
                 let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
             }
         }
@@ -1473,7 +3098,7 @@ impl QuickLendXContract {
         investor: Address,
         investment_limit: i128,
     ) -> Result<(), QuickLendXError> {
-        let admin = require_current_admin(&env)?;
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
         let verification = do_verify_investor(&env, &admin, &investor, investment_limit)?;
         emit_investor_verified(&env, &verification);
         Ok(())
@@ -1485,7 +3110,7 @@ impl QuickLendXContract {
         investor: Address,
         reason: String,
     ) -> Result<(), QuickLendXError> {
-        let admin = require_current_admin(&env)?;
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
         do_reject_investor(&env, &admin, &investor, reason)
     }
 
@@ -1500,7 +3125,7 @@ impl QuickLendXContract {
         investor: Address,
         new_limit: i128,
     ) -> Result<(), QuickLendXError> {
-        let admin = require_current_admin(&env)?;
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
         verification::set_investment_limit(&env, &admin, &investor, new_limit)
     }
 
@@ -1705,6 +3330,11 @@ impl QuickLendXContract {
             .ok_or(QuickLendXError::StorageKeyNotFound)
     }
 
+    /// Get every escrow slice for an invoice (several for syndicated invoices)
+    pub fn get_invoice_escrows(env: Env, invoice_id: BytesN<32>) -> Vec<payments::Escrow> {
+        EscrowStorage::get_escrows_by_invoice(&env, &invoice_id)
+    }
+
     /// Get escrow status for an invoice
     pub fn get_escrow_status(
         env: Env,
@@ -1716,25 +3346,135 @@ impl QuickLendXContract {
     }
 
     /// Release escrow funds to business upon invoice verification
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice's escrow terms hold the funds until a
+    ///   condition this request does not meet
     pub fn release_escrow_funds(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        reentrancy::with_payment_guard(&env, || {
-            let escrow = EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
-                .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Self::release_held_escrows(&env, &invoice_id, false)
+    }
+
+    fn release_held_escrows(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        admin_verified: bool,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(env, || {
+            if let Some(invoice) = InvoiceStorage::get_invoice(env, invoice_id) {
+                if !EscrowReleaseTerms::is_releasable(env, &invoice, admin_verified) {
+                    return Err(QuickLendXError::OperationNotAllowed);
+                }
+            }
+            let held = EscrowStorage::get_held_escrows_by_invoice(env, invoice_id);
+            // Escrow split into milestones is only released milestone by milestone
+            for escrow in held.iter() {
+                if EscrowMilestones::has_milestones(env, &escrow.escrow_id) {
+                    return Err(QuickLendXError::OperationNotAllowed);
+                }
+            }
 
-            release_escrow(&env, &invoice_id)?;
+            release_escrow(env, invoice_id)?;
 
-            emit_escrow_released(
-                &env,
-                &escrow.escrow_id,
-                &invoice_id,
-                &escrow.business,
-                escrow.amount,
-            );
+            for escrow in held.iter() {
+                emit_escrow_released(
+                    env,
+                    &escrow.escrow_id,
+                    invoice_id,
+                    &escrow.business,
+                    escrow.amount,
+                );
+            }
 
             Ok(())
         })
     }
 
+    /// Attach release conditions and a refund timeout to an invoice's escrow (business
+    /// only, before funding and before bids are placed).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids, or the release waits
+    ///   on a debtor confirmation and the invoice has no debtor
+    pub fn set_escrow_terms(
+        env: Env,
+        invoice_id: BytesN<32>,
+        terms: EscrowTerms,
+    ) -> Result<(), QuickLendXError> {
+        EscrowReleaseTerms::set(&env, &invoice_id, &terms)
+    }
+
+    /// Get an invoice's escrow terms (released on request, no timeout, unless set).
+    pub fn get_escrow_terms(env: Env, invoice_id: BytesN<32>) -> EscrowTerms {
+        EscrowReleaseTerms::get(&env, &invoice_id)
+    }
+
+    /// Split a held escrow of a Manufacturing or Consulting invoice into milestones, each
+    /// released on its approver's say (business and the escrow's investor together).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the escrow does not exist
+    /// * `InvalidStatus` if the escrow is not Held or the invoice is not Funded
+    /// * `OperationNotAllowed` if the invoice is not a project invoice, a milestone was
+    ///   already released, or a debtor approver is named on an invoice without a debtor
+    /// * `InvalidAmount` if the amounts are not positive or do not add up to the escrow
+    pub fn set_escrow_milestones(
+        env: Env,
+        escrow_id: BytesN<32>,
+        milestones: Vec<MilestoneTerms>,
+    ) -> Result<Vec<Milestone>, QuickLendXError> {
+        EscrowMilestones::set(&env, &escrow_id, &milestones)
+    }
+
+    /// Approve a milestone of an escrow and release its amount to the business.
+    ///
+    /// Protected by payment reentrancy guard.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the escrow or the milestone does not exist
+    /// * `InvalidStatus` if the escrow is not Held or the milestone was already released
+    /// * `Unauthorized` if `approver` is not the milestone's approver
+    pub fn approve_milestone(
+        env: Env,
+        approver: Address,
+        escrow_id: BytesN<32>,
+        index: u32,
+    ) -> Result<Milestone, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            EscrowMilestones::release(&env, &approver, &escrow_id, index)
+        })
+    }
+
+    /// Get the milestones of an escrow (empty when it is released in one go).
+    pub fn get_escrow_milestones(env: Env, escrow_id: BytesN<32>) -> Vec<Milestone> {
+        EscrowMilestones::get_milestones(&env, &escrow_id)
+    }
+
+    /// Get the balance an escrow still holds, net of released milestones.
+    pub fn get_escrow_held_balance(
+        env: Env,
+        escrow_id: BytesN<32>,
+    ) -> Result<i128, QuickLendXError> {
+        let escrow = EscrowStorage::get_escrow(&env, &escrow_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Ok(EscrowMilestones::held_balance(&env, &escrow))
+    }
+
+    /// Reclaim the escrowed funds of a funded invoice as one of its investors once the
+    /// escrow's refund timeout has passed without a release.
+    ///
+    /// Protected by payment reentrancy guard.
+    pub fn reclaim_escrow_funds(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            do_reclaim_escrow_funds(&env, &invoice_id, &investor)
+        })
+    }
+
     /// Refund escrow funds to investor if verification fails or as an explicit manual refund.
     ///
     /// Can be triggered by Admin or Business owner. Invoice must be Funded.
@@ -1795,21 +3535,36 @@ impl QuickLendXContract {
     }
 
     /// Check for overdue invoices with a custom grace period (in seconds)
+    ///
+    /// An invoice with an installment schedule is overdue once an installment is missed,
+    /// and defaults when the configured grace period after the earliest missed installment
+    /// runs out; the custom grace period does not apply to installment defaults.
     pub fn check_overdue_invoices_grace(
         env: Env,
         grace_period: u64,
     ) -> Result<u32, QuickLendXError> {
         let current_timestamp = env.ledger().timestamp();
+        let installment_grace = defaults::resolve_grace_period(&env, None);
         let funded_invoices = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded);
         let mut overdue_count = 0u32;
 
         for invoice_id in funded_invoices.iter() {
             if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
-                if invoice.is_overdue(current_timestamp) {
+                let missed_due_date = InstallmentPlans::check_missed(&env, &invoice_id);
+                if missed_due_date.is_some() || invoice.is_overdue(current_timestamp) {
                     let _ = NotificationSystem::notify_payment_overdue(&env, &invoice);
                     overdue_count += 1;
                 }
-                let _ = invoice.check_and_handle_expiration(&env, grace_period)?;
+                match missed_due_date {
+                    Some(due_date)
+                        if current_timestamp > due_date.saturating_add(installment_grace) =>
+                    {
+                        do_handle_default(&env, &invoice_id)?;
+                    }
+                    _ => {
+                        let _ = invoice.check_and_handle_expiration(&env, grace_period)?;
+                    }
+                }
             }
         }
 
@@ -2977,6 +4732,98 @@ impl QuickLendXContract {
     pub fn get_backup_details(env: Env, backup_id: BytesN<32>) -> Option<Backup> {
         BackupStorage::get_backup(&env, &backup_id)
     }
+
+    /// Set how keepers running maintenance calls are rewarded from platform fees (admin
+    /// only).
+    pub fn set_keeper_config(
+        env: Env,
+        admin: Address,
+        config: KeeperConfig,
+    ) -> Result<(), QuickLendXError> {
+        KeeperRewards::set_config(&env, &admin, &config)
+    }
+
+    pub fn get_keeper_config(env: Env) -> Option<KeeperConfig> {
+        KeeperRewards::get_config(&env)
+    }
+
+    /// Fees set aside for keeper rewards and not paid out yet.
+    pub fn get_keeper_reward_pool(env: Env) -> i128 {
+        KeeperRewards::pool_balance(&env)
+    }
+
+    /// Run the overdue check as a keeper. Returns how many invoices defaulted, and
+    /// rewards the keeper when any did.
+    pub fn keeper_check_overdue_invoices(
+        env: Env,
+        keeper: Address,
+    ) -> Result<u32, QuickLendXError> {
+        keeper.require_auth();
+        let funded_before =
+            InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded).len();
+        Self::check_overdue_invoices(env.clone())?;
+        let funded_after =
+            InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded).len();
+        let defaulted = funded_before.saturating_sub(funded_after);
+        if defaulted > 0 {
+            KeeperRewards::reward(&env, &keeper)?;
+        }
+        Ok(defaulted)
+    }
+
+    /// Remove an invoice's expired bids as a keeper, rewarding the keeper when any were.
+    pub fn keeper_cleanup_expired_bids(
+        env: Env,
+        keeper: Address,
+        invoice_id: BytesN<32>,
+    ) -> Result<u32, QuickLendXError> {
+        keeper.require_auth();
+        let expired = BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        if expired > 0 {
+            KeeperRewards::reward(&env, &keeper)?;
+        }
+        Ok(expired)
+    }
+
+    /// Place standing-order bids on a verified invoice for the next batch of open orders
+    /// as a keeper, rewarding the keeper when any were placed. Returns how many were.
+    ///
+    /// # Errors
+    /// * `InvalidStatus` - The invoice is no longer open for bids
+    /// * `OperationNotAllowed` - Every standing order has already been evaluated
+    pub fn keeper_execute_standing_orders(
+        env: Env,
+        keeper: Address,
+        invoice_id: BytesN<32>,
+    ) -> Result<u32, QuickLendXError> {
+        keeper.require_auth();
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Verified {
+            StandingOrders::end_evaluation(&env, &invoice_id);
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !StandingOrders::evaluation_pending(&env, &invoice_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let placed = Self::execute_standing_order_batch(&env, &invoice)?;
+        if placed > 0 {
+            KeeperRewards::reward(&env, &keeper)?;
+        }
+        Ok(placed)
+    }
+
+    /// Remove backups past the admin's retention policy as a keeper, rewarding the keeper
+    /// when any were.
+    pub fn keeper_cleanup_backups(env: Env, keeper: Address) -> Result<u32, QuickLendXError> {
+        keeper.require_auth();
+        let removed_count = BackupStorage::cleanup_old_backups(&env)?;
+        if removed_count > 0 {
+            events::emit_backups_cleaned(&env, removed_count);
+            KeeperRewards::reward(&env, &keeper)?;
+        }
+        Ok(removed_count)
+    }
 }
 
 #[cfg(test)]
@@ -3041,3 +4888,53 @@ mod test_revenue_split;
 mod test_risk_tier;
 #[cfg(test)]
 mod test_types;
+#[cfg(test)]
+mod test_syndication;
+#[cfg(test)]
+mod test_sealed_bid;
+#[cfg(test)]
+mod test_listing;
+#[cfg(test)]
+mod test_auto_accept;
+#[cfg(test)]
+mod test_standing_order;
+#[cfg(test)]
+mod test_bid_amendment;
+#[cfg(test)]
+mod test_negotiation;
+#[cfg(test)]
+mod test_secondary_market;
+#[cfg(test)]
+mod test_receipt;
+#[cfg(test)]
+mod test_recourse;
+#[cfg(test)]
+mod test_reverse_factoring;
+#[cfg(test)]
+mod test_debtor_confirmation;
+#[cfg(test)]
+mod test_pool;
+#[cfg(test)]
+mod test_vault;
+#[cfg(test)]
+mod test_credit_line;
+#[cfg(test)]
+mod test_time_pricing;
+#[cfg(test)]
+mod test_late_payment;
+#[cfg(test)]
+mod test_installment;
+#[cfg(test)]
+mod test_third_party_payer;
+#[cfg(test)]
+mod test_progressive_distribution;
+#[cfg(test)]
+mod test_escrow_terms;
+#[cfg(test)]
+mod test_milestone;
+#[cfg(test)]
+mod test_auto_debit;
+#[cfg(test)]
+mod test_keeper;
+#[cfg(test)]
+mod test_batch_payment;
@@ -0,0 +1,205 @@
+//! Business-defined listing terms for invoices.
+//!
+//! A business may attach terms to an invoice that every bid must satisfy on top of
+//! the protocol-wide checks in `verification::validate_bid_terms`: a minimum advance,
+//! a cap on the expected return or on the discount to face value, a bidding deadline
+//! and an optional buy-now price. A bid at or above the buy-now price funds the
+//! invoice immediately through the escrow path.
+
+use soroban_sdk::{contracttype, BytesN, Env};
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_listing_terms_cleared, emit_listing_terms_set};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+
+/// Basis-point denominator used for discounts.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ListingKey {
+    Terms(BytesN<32>),
+}
+
+/// Terms a business sets for bids on one invoice. `None` leaves a term unrestricted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ListingTerms {
+    /// Smallest acceptable `bid_amount`.
+    pub min_advance: Option<i128>,
+    /// Largest acceptable `expected_return`.
+    pub max_expected_return: Option<i128>,
+    /// Largest acceptable discount to face value, `(amount - bid_amount) / amount`, in bps.
+    pub max_discount_bps: Option<u32>,
+    /// Bids are rejected after this timestamp.
+    pub bidding_deadline: Option<u64>,
+    /// A bid of at least this amount is accepted and funded immediately.
+    pub buy_now_price: Option<i128>,
+}
+
+pub struct ListingStorage;
+
+impl ListingStorage {
+    pub fn get_terms(env: &Env, invoice_id: &BytesN<32>) -> Option<ListingTerms> {
+        env.storage()
+            .persistent()
+            .get(&ListingKey::Terms(invoice_id.clone()))
+    }
+
+    fn set_terms(env: &Env, invoice_id: &BytesN<32>, terms: &ListingTerms) {
+        env.storage()
+            .persistent()
+            .set(&ListingKey::Terms(invoice_id.clone()), terms);
+    }
+
+    fn remove_terms(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .remove(&ListingKey::Terms(invoice_id.clone()));
+    }
+}
+
+pub struct Listing;
+
+impl Listing {
+    /// Set or replace the listing terms of an invoice (business only).
+    ///
+    /// Terms can only be changed while the invoice is Pending or Verified. They apply to
+    /// bids placed afterwards; bids already placed are not re-validated.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is no longer open for bids
+    /// * `InvalidAmount` if an amount term is non-positive, exceeds the invoice amount, the
+    ///   buy-now price is below the minimum advance, or the discount exceeds 100%
+    /// * `InvalidTimestamp` if the bidding deadline is not in the future
+    pub fn set_terms(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        terms: &ListingTerms,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        Self::require_open_listing(&invoice)?;
+        Self::validate_terms(env, &invoice, terms)?;
+
+        ListingStorage::set_terms(env, invoice_id, terms);
+        emit_listing_terms_set(env, invoice_id, terms);
+        Ok(())
+    }
+
+    /// Remove the listing terms of an invoice (business only).
+    pub fn clear_terms(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        Self::require_open_listing(&invoice)?;
+
+        ListingStorage::remove_terms(env, invoice_id);
+        emit_listing_terms_cleared(env, invoice_id);
+        Ok(())
+    }
+
+    /// Check a bid against the invoice's listing terms.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the bid meets the buy-now price and should be funded immediately
+    /// * `Ok(false)` if the bid satisfies the terms (or there are none)
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the bidding deadline has passed
+    /// * `InvalidAmount` if the bid is below the minimum advance or above the return/discount caps
+    pub fn check_bid(
+        env: &Env,
+        invoice: &Invoice,
+        bid_amount: i128,
+        expected_return: i128,
+    ) -> Result<bool, QuickLendXError> {
+        let terms = match ListingStorage::get_terms(env, &invoice.id) {
+            Some(terms) => terms,
+            None => return Ok(false),
+        };
+
+        if let Some(deadline) = terms.bidding_deadline {
+            if env.ledger().timestamp() > deadline {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+        if let Some(min_advance) = terms.min_advance {
+            if bid_amount < min_advance {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let Some(max_return) = terms.max_expected_return {
+            if expected_return > max_return {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let Some(max_discount_bps) = terms.max_discount_bps {
+            if Self::discount_bps(invoice.amount, bid_amount)? > max_discount_bps as i128 {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+
+        Ok(terms
+            .buy_now_price
+            .map(|price| bid_amount >= price)
+            .unwrap_or(false))
+    }
+
+    /// Discount of `bid_amount` to the invoice face value, in basis points.
+    fn discount_bps(face_value: i128, bid_amount: i128) -> Result<i128, QuickLendXError> {
+        if face_value <= 0 {
+            return Err(QuickLendXError::InvoiceAmountInvalid);
+        }
+        let discount = face_value.saturating_sub(bid_amount).max(0);
+        discount
+            .checked_mul(BPS_DENOMINATOR)
+            .map(|v| v / face_value)
+            .ok_or(QuickLendXError::InvalidAmount)
+    }
+
+    fn require_open_listing(invoice: &Invoice) -> Result<(), QuickLendXError> {
+        match invoice.status {
+            InvoiceStatus::Pending | InvoiceStatus::Verified => Ok(()),
+            _ => Err(QuickLendXError::InvalidStatus),
+        }
+    }
+
+    fn validate_terms(
+        env: &Env,
+        invoice: &Invoice,
+        terms: &ListingTerms,
+    ) -> Result<(), QuickLendXError> {
+        for amount in [terms.min_advance, terms.buy_now_price]
+            .into_iter()
+            .flatten()
+        {
+            if amount <= 0 || amount > invoice.amount {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let Some(max_return) = terms.max_expected_return {
+            if max_return <= 0 {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let Some(max_discount_bps) = terms.max_discount_bps {
+            if max_discount_bps as i128 > BPS_DENOMINATOR {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let (Some(min_advance), Some(buy_now)) = (terms.min_advance, terms.buy_now_price) {
+            if buy_now < min_advance {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if let Some(deadline) = terms.bidding_deadline {
+            if deadline <= env.ledger().timestamp() {
+                return Err(QuickLendXError::InvalidTimestamp);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+//! Milestone-based releases of escrowed funding.
+//!
+//! Project invoices (Manufacturing and Consulting) can have their escrow split into
+//! milestones once funded: the business and the escrow's investor agree on the amounts
+//! and on who approves each one, the investor, the admin or the invoice's debtor. Each
+//! approval releases that milestone's amount to the business on its own, and whatever is
+//! not released yet stays held and is refunded with the escrow. What was released stays
+//! owed by the business after a refund.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Vec};
+
+use crate::admin::AdminStorage;
+use crate::audit::{log_operation, AuditOperation};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_escrow_released, emit_milestone_released, emit_milestones_set};
+use crate::invoice::{InvoiceCategory, InvoiceStatus, InvoiceStorage};
+use crate::payments::{transfer_funds, Escrow, EscrowStatus, EscrowStorage};
+
+/// Most milestones an escrow can be split into.
+pub const MAX_MILESTONES: u32 = 24;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum MilestoneKey {
+    EscrowMilestones(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MilestoneApprover {
+    Investor,
+    Admin,
+    Debtor,
+}
+
+/// One milestone as agreed on: the amount it releases and who approves it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneTerms {
+    pub amount: i128,
+    pub approver: MilestoneApprover,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub amount: i128,
+    pub approver: MilestoneApprover,
+    pub released_at: Option<u64>,
+}
+
+pub struct EscrowMilestones;
+
+impl EscrowMilestones {
+    /// Milestones of an escrow; empty when it is released in one go.
+    pub fn get_milestones(env: &Env, escrow_id: &BytesN<32>) -> Vec<Milestone> {
+        env.storage()
+            .persistent()
+            .get(&MilestoneKey::EscrowMilestones(escrow_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn has_milestones(env: &Env, escrow_id: &BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .has(&MilestoneKey::EscrowMilestones(escrow_id.clone()))
+    }
+
+    /// Part of an escrow not released through its milestones, whatever its status.
+    pub fn unreleased_amount(env: &Env, escrow: &Escrow) -> i128 {
+        let mut released: i128 = 0;
+        for milestone in Self::get_milestones(env, &escrow.escrow_id).iter() {
+            if milestone.released_at.is_some() {
+                released = released.saturating_add(milestone.amount);
+            }
+        }
+        escrow.amount.saturating_sub(released)
+    }
+
+    /// Balance an escrow still holds: nothing once it is released or refunded.
+    pub fn held_balance(env: &Env, escrow: &Escrow) -> i128 {
+        if escrow.status == EscrowStatus::Held {
+            Self::unreleased_amount(env, escrow)
+        } else {
+            0
+        }
+    }
+
+    /// Split a held escrow into milestones, replacing any earlier split while none of it
+    /// has been released. Needs both the business and the escrow's investor.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the escrow does not exist
+    /// * `InvoiceNotFound` if its invoice does not exist
+    /// * `InvalidStatus` if the escrow is not Held or the invoice is not Funded
+    /// * `OperationNotAllowed` if the invoice is not a Manufacturing or Consulting
+    ///   project, a milestone was already released, or a milestone is approved by the
+    ///   debtor of an invoice without one
+    /// * `InvalidAmount` if there are no milestones or more than `MAX_MILESTONES`, an
+    ///   amount is not positive, or the amounts do not add up to the escrowed amount
+    pub fn set(
+        env: &Env,
+        escrow_id: &BytesN<32>,
+        terms: &Vec<MilestoneTerms>,
+    ) -> Result<Vec<Milestone>, QuickLendXError> {
+        let escrow =
+            EscrowStorage::get_escrow(env, escrow_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let invoice = InvoiceStorage::get_invoice(env, &escrow.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        escrow.investor.require_auth();
+
+        if escrow.status != EscrowStatus::Held || invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !matches!(
+            invoice.category,
+            InvoiceCategory::Manufacturing | InvoiceCategory::Consulting
+        ) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if Self::unreleased_amount(env, &escrow) != escrow.amount {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if terms.is_empty() || terms.len() > MAX_MILESTONES {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let mut milestones = Vec::new(env);
+        let mut total: i128 = 0;
+        for term in terms.iter() {
+            if term.amount <= 0 {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+            if term.approver == MilestoneApprover::Debtor && invoice.debtor.is_none() {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            total = total.saturating_add(term.amount);
+            milestones.push_back(Milestone {
+                amount: term.amount,
+                approver: term.approver,
+                released_at: None,
+            });
+        }
+        if total != escrow.amount {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        env.storage().persistent().set(
+            &MilestoneKey::EscrowMilestones(escrow_id.clone()),
+            &milestones,
+        );
+        emit_milestones_set(env, &escrow, &milestones);
+        Ok(milestones)
+    }
+
+    /// Approve a milestone and release its amount to the business. The escrow is
+    /// Released once every milestone is.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the escrow or the milestone does not exist
+    /// * `InvoiceNotFound` if its invoice does not exist
+    /// * `InvalidStatus` if the escrow is not Held, the invoice is not Funded, or the
+    ///   milestone was already released
+    /// * `Unauthorized` if `approver` is not the milestone's approver
+    pub fn release(
+        env: &Env,
+        approver: &Address,
+        escrow_id: &BytesN<32>,
+        index: u32,
+    ) -> Result<Milestone, QuickLendXError> {
+        approver.require_auth();
+        let mut escrow =
+            EscrowStorage::get_escrow(env, escrow_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let invoice = InvoiceStorage::get_invoice(env, &escrow.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if escrow.status != EscrowStatus::Held || invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let mut milestones = Self::get_milestones(env, escrow_id);
+        let mut milestone = milestones
+            .get(index)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if milestone.released_at.is_some() {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let authorized = match milestone.approver {
+            MilestoneApprover::Investor => *approver == escrow.investor,
+            MilestoneApprover::Admin => AdminStorage::is_admin(env, approver),
+            MilestoneApprover::Debtor => invoice.debtor.as_ref() == Some(approver),
+        };
+        if !authorized {
+            return Err(QuickLendXError::Unauthorized);
+        }
+
+        transfer_funds(
+            env,
+            &escrow.currency,
+            &env.current_contract_address(),
+            &escrow.business,
+            milestone.amount,
+        )?;
+        milestone.released_at = Some(env.ledger().timestamp());
+        milestones.set(index, milestone.clone());
+        env.storage().persistent().set(
+            &MilestoneKey::EscrowMilestones(escrow_id.clone()),
+            &milestones,
+        );
+
+        emit_milestone_released(env, &escrow, index, &milestone);
+        log_operation(
+            env,
+            escrow.invoice_id.clone(),
+            AuditOperation::EscrowReleased,
+            approver.clone(),
+            None,
+            Some(String::from_str(env, "Milestone released")),
+            Some(milestone.amount),
+            None,
+        );
+
+        if Self::unreleased_amount(env, &escrow) == 0 {
+            escrow.status = EscrowStatus::Released;
+            EscrowStorage::update_escrow(env, &escrow);
+            emit_escrow_released(
+                env,
+                &escrow.escrow_id,
+                &escrow.invoice_id,
+                &escrow.business,
+                escrow.amount,
+            );
+        }
+        Ok(milestone)
+    }
+}
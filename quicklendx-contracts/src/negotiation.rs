@@ -0,0 +1,284 @@
+//! Counter-offer negotiation on placed bids.
+//!
+//! Instead of only accepting or ignoring a bid, a business can counter-offer a different
+//! advance or return on it. The investor can then accept, reject or counter again, and
+//! the parties alternate until one side accepts, either side rejects, an offer expires or
+//! `MAX_NEGOTIATION_ROUNDS` is reached. Accepting the other side's offer re-prices the bid
+//! and funds the invoice through the regular escrow path (see `QuickLendXContract::fund_bid`).
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+use crate::bid::{Bid, BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_counter_offer_accepted, emit_counter_offer_made, emit_counter_offer_rejected,
+};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::listing::Listing;
+use crate::sealed_bid::SealedBidding;
+use crate::verification::validate_bid_terms;
+
+/// Maximum number of offers (business and investor combined) in one negotiation.
+pub const MAX_NEGOTIATION_ROUNDS: u32 = 6;
+/// Shortest validity of a counter-offer (1 hour).
+pub const MIN_OFFER_VALIDITY_SECS: u64 = 60 * 60;
+/// Longest validity of a counter-offer (7 days).
+pub const MAX_OFFER_VALIDITY_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum NegotiationKey {
+    Negotiation(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NegotiationParty {
+    Business,
+    Investor,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NegotiationStatus {
+    Open,
+    Accepted,
+    Rejected,
+}
+
+/// Latest offer exchanged on a bid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Negotiation {
+    pub bid_id: BytesN<32>,
+    pub invoice_id: BytesN<32>,
+    pub business: Address,
+    pub investor: Address,
+    /// Number of offers made so far, starting at 1 with the business's first counter.
+    pub round: u32,
+    pub proposed_by: NegotiationParty,
+    pub bid_amount: i128,
+    pub expected_return: i128,
+    pub expires_at: u64,
+    pub status: NegotiationStatus,
+    pub updated_at: u64,
+}
+
+impl Negotiation {
+    pub fn is_expired(&self, current_timestamp: u64) -> bool {
+        current_timestamp > self.expires_at
+    }
+}
+
+pub struct NegotiationStorage;
+
+impl NegotiationStorage {
+    pub fn get_negotiation(env: &Env, bid_id: &BytesN<32>) -> Option<Negotiation> {
+        env.storage()
+            .persistent()
+            .get(&NegotiationKey::Negotiation(bid_id.clone()))
+    }
+
+    fn store_negotiation(env: &Env, negotiation: &Negotiation) {
+        env.storage().persistent().set(
+            &NegotiationKey::Negotiation(negotiation.bid_id.clone()),
+            negotiation,
+        );
+    }
+}
+
+pub struct Negotiations;
+
+impl Negotiations {
+    /// Make a counter-offer on a placed bid.
+    ///
+    /// The business opens a negotiation on any live bid of its invoice; afterwards the
+    /// parties alternate. A business may open a fresh negotiation once the previous one
+    /// was rejected or its last offer expired.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `Unauthorized` if `caller` is neither the business nor the bidder, or it is not
+    ///   the caller's turn
+    /// * `OperationNotAllowed` if the bid is not a live Placed bid, the invoice is sealed,
+    ///   the round limit is reached or the negotiation is already settled
+    /// * `InvalidStatus` if the invoice is no longer Verified
+    /// * `InvalidTimestamp` if `valid_for` is outside the allowed window
+    /// * Any error from `validate_bid_terms` / listing terms for the proposed terms
+    pub fn counter_offer(
+        env: &Env,
+        caller: &Address,
+        bid_id: &BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        valid_for: u64,
+    ) -> Result<Negotiation, QuickLendXError> {
+        caller.require_auth();
+        let (invoice, bid) = Self::load_live_bid(env, bid_id)?;
+        let party = Self::party_of(caller, &invoice, &bid)?;
+        let now = env.ledger().timestamp();
+
+        let round = match NegotiationStorage::get_negotiation(env, bid_id) {
+            Some(current)
+                if current.status == NegotiationStatus::Open && !current.is_expired(now) =>
+            {
+                if current.proposed_by == party {
+                    return Err(QuickLendXError::Unauthorized);
+                }
+                if current.round >= MAX_NEGOTIATION_ROUNDS {
+                    return Err(QuickLendXError::OperationNotAllowed);
+                }
+                current.round + 1
+            }
+            Some(current) if current.status == NegotiationStatus::Accepted => {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            // A new negotiation can only be opened by the business
+            _ => {
+                if party != NegotiationParty::Business {
+                    return Err(QuickLendXError::Unauthorized);
+                }
+                1
+            }
+        };
+
+        if !(MIN_OFFER_VALIDITY_SECS..=MAX_OFFER_VALIDITY_SECS).contains(&valid_for) {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        validate_bid_terms(env, &invoice, bid_amount, expected_return, &bid.investor)?;
+        Listing::check_bid(env, &invoice, bid_amount, expected_return)?;
+
+        let negotiation = Negotiation {
+            bid_id: bid_id.clone(),
+            invoice_id: invoice.id.clone(),
+            business: invoice.business.clone(),
+            investor: bid.investor.clone(),
+            round,
+            proposed_by: party,
+            bid_amount,
+            expected_return,
+            // An offer cannot outlive the bid it is made on
+            expires_at: now.saturating_add(valid_for).min(bid.expiration_timestamp),
+            status: NegotiationStatus::Open,
+            updated_at: now,
+        };
+        NegotiationStorage::store_negotiation(env, &negotiation);
+        emit_counter_offer_made(env, &negotiation);
+        Ok(negotiation)
+    }
+
+    /// Accept the other party's latest offer and re-price the bid to the agreed terms.
+    ///
+    /// `check_terms` validates the agreed amount and return for the bid's investor before
+    /// anything is written. Returns the invoice and the re-priced bid, ready to be funded
+    /// by the caller.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if there is no negotiation on the bid
+    /// * `Unauthorized` if `caller` made the offer or is not a party
+    /// * `OperationNotAllowed` if the offer is no longer open or has expired
+    /// * Any error from `check_terms`
+    pub fn accept_offer(
+        env: &Env,
+        caller: &Address,
+        bid_id: &BytesN<32>,
+        check_terms: impl FnOnce(&Invoice, &Address, i128, i128) -> Result<(), QuickLendXError>,
+    ) -> Result<(Invoice, Bid), QuickLendXError> {
+        caller.require_auth();
+        let (invoice, mut bid) = Self::load_live_bid(env, bid_id)?;
+        let party = Self::party_of(caller, &invoice, &bid)?;
+        let mut negotiation = Self::open_negotiation(env, bid_id)?;
+        if negotiation.proposed_by == party {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        // Limits may have changed since the offer was made
+        check_terms(
+            &invoice,
+            &bid.investor,
+            negotiation.bid_amount,
+            negotiation.expected_return,
+        )?;
+
+        negotiation.status = NegotiationStatus::Accepted;
+        negotiation.updated_at = env.ledger().timestamp();
+        NegotiationStorage::store_negotiation(env, &negotiation);
+
+        bid.bid_amount = negotiation.bid_amount;
+        bid.expected_return = negotiation.expected_return;
+        BidStorage::update_bid(env, &bid);
+
+        emit_counter_offer_accepted(env, &negotiation, party);
+        Ok((invoice, bid))
+    }
+
+    /// Reject the negotiation. The bid keeps its original terms.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if there is no negotiation on the bid
+    /// * `Unauthorized` if `caller` is not a party
+    /// * `OperationNotAllowed` if the negotiation is no longer open
+    pub fn reject_offer(
+        env: &Env,
+        caller: &Address,
+        bid_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        caller.require_auth();
+        let mut negotiation = NegotiationStorage::get_negotiation(env, bid_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let party = if *caller == negotiation.business {
+            NegotiationParty::Business
+        } else if *caller == negotiation.investor {
+            NegotiationParty::Investor
+        } else {
+            return Err(QuickLendXError::Unauthorized);
+        };
+        if negotiation.status != NegotiationStatus::Open {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        negotiation.status = NegotiationStatus::Rejected;
+        negotiation.updated_at = env.ledger().timestamp();
+        NegotiationStorage::store_negotiation(env, &negotiation);
+        emit_counter_offer_rejected(env, &negotiation, party);
+        Ok(())
+    }
+
+    fn open_negotiation(env: &Env, bid_id: &BytesN<32>) -> Result<Negotiation, QuickLendXError> {
+        let negotiation = NegotiationStorage::get_negotiation(env, bid_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if negotiation.status != NegotiationStatus::Open
+            || negotiation.is_expired(env.ledger().timestamp())
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(negotiation)
+    }
+
+    fn load_live_bid(env: &Env, bid_id: &BytesN<32>) -> Result<(Invoice, Bid), QuickLendXError> {
+        let bid = BidStorage::get_bid(env, bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if bid.status != BidStatus::Placed || bid.is_expired(env.ledger().timestamp()) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        SealedBidding::require_open_bidding(env, &bid.invoice_id)?;
+        let invoice = InvoiceStorage::get_invoice(env, &bid.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        Ok((invoice, bid))
+    }
+
+    fn party_of(
+        caller: &Address,
+        invoice: &Invoice,
+        bid: &Bid,
+    ) -> Result<NegotiationParty, QuickLendXError> {
+        if *caller == invoice.business {
+            Ok(NegotiationParty::Business)
+        } else if *caller == bid.investor {
+            Ok(NegotiationParty::Investor)
+        } else {
+            Err(QuickLendXError::Unauthorized)
+        }
+    }
+}
@@ -4,8 +4,11 @@
 
 use crate::errors::QuickLendXError;
 use crate::events::emit_escrow_created;
+use crate::investment::InvestmentStorage;
+use crate::milestone::EscrowMilestones;
+use crate::receipt::{ReceiptStorage, Receipts};
 use soroban_sdk::token;
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -38,6 +41,17 @@ impl EscrowStorage {
             &(symbol_short!("escrow"), &escrow.invoice_id),
             &escrow.escrow_id,
         );
+        // Keep every escrow slice for the invoice (syndicated invoices have several)
+        let list_key = (symbol_short!("esc_list"), escrow.invoice_id.clone());
+        let mut ids: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&list_key)
+            .unwrap_or_else(|| Vec::new(env));
+        if !ids.contains(&escrow.escrow_id) {
+            ids.push_back(escrow.escrow_id.clone());
+            env.storage().instance().set(&list_key, &ids);
+        }
     }
 
     pub fn get_escrow(env: &Env, escrow_id: &BytesN<32>) -> Option<Escrow> {
@@ -56,6 +70,41 @@ impl EscrowStorage {
         }
     }
 
+    /// Get every escrow slice for an invoice, in creation order.
+    pub fn get_escrows_by_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<Escrow> {
+        let mut escrows = Vec::new(env);
+        let ids: Option<Vec<BytesN<32>>> = env
+            .storage()
+            .instance()
+            .get(&(symbol_short!("esc_list"), invoice_id.clone()));
+        match ids {
+            Some(ids) => {
+                for id in ids.iter() {
+                    if let Some(escrow) = Self::get_escrow(env, &id) {
+                        escrows.push_back(escrow);
+                    }
+                }
+            }
+            None => {
+                if let Some(escrow) = Self::get_escrow_by_invoice(env, invoice_id) {
+                    escrows.push_back(escrow);
+                }
+            }
+        }
+        escrows
+    }
+
+    /// Get the escrow slices for an invoice that are still `Held`.
+    pub fn get_held_escrows_by_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<Escrow> {
+        let mut held = Vec::new(env);
+        for escrow in Self::get_escrows_by_invoice(env, invoice_id).iter() {
+            if escrow.status == EscrowStatus::Held {
+                held.push_back(escrow);
+            }
+        }
+        held
+    }
+
     pub fn update_escrow(env: &Env, escrow: &Escrow) {
         env.storage().instance().set(&escrow.escrow_id, escrow);
     }
@@ -129,59 +178,109 @@ pub fn create_escrow(
 
 /// Release escrow funds to business (contract → business). Escrow must be Held.
 ///
+/// Every `Held` slice of a syndicated invoice is released together.
+///
 /// # Errors
 /// * `StorageKeyNotFound` if no escrow for invoice, `InvalidStatus` if not Held
 pub fn release_escrow(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
-    let mut escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
-        .ok_or(QuickLendXError::StorageKeyNotFound)?;
-
-    if escrow.status != EscrowStatus::Held {
-        return Err(QuickLendXError::InvalidStatus);
-    }
+    let held = held_escrows_or_err(env, invoice_id)?;
 
     // Transfer funds from escrow (contract) to business
     let contract_address = env.current_contract_address();
-    transfer_funds(
-        env,
-        &escrow.currency,
-        &contract_address,
-        &escrow.business,
-        escrow.amount,
-    )?;
-
-    // Update escrow status
-    escrow.status = EscrowStatus::Released;
-    EscrowStorage::update_escrow(env, &escrow);
+    for mut escrow in held.iter() {
+        transfer_funds(
+            env,
+            &escrow.currency,
+            &contract_address,
+            &escrow.business,
+            escrow.amount,
+        )?;
+
+        // Update escrow status
+        escrow.status = EscrowStatus::Released;
+        EscrowStorage::update_escrow(env, &escrow);
+    }
 
     Ok(())
 }
 
 /// Refund escrow funds to investor (contract → investor). Escrow must be Held.
 ///
+/// Every `Held` slice of a syndicated invoice is refunded to its own investor, or to the
+/// current holders of the position's receipt. Milestones already released stay with the
+/// business; only the balance still held is refunded.
+///
 /// # Errors
 /// * `StorageKeyNotFound` if no escrow for invoice, `InvalidStatus` if not Held
 pub fn refund_escrow(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
-    let mut escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
-        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    let held = held_escrows_or_err(env, invoice_id)?;
+
+    // Refund funds from escrow (contract) back to investor
+    let contract_address = env.current_contract_address();
+    for mut escrow in held.iter() {
+        let amount = EscrowMilestones::held_balance(env, &escrow);
+        // Positions with a receipt are refunded to its current holders
+        let investment = ReceiptStorage::get_investment_for_escrow(env, &escrow.escrow_id)
+            .and_then(|investment_id| InvestmentStorage::get_investment(env, &investment_id));
+        match investment {
+            Some(investment) => Receipts::pay_holders(
+                env,
+                &escrow.currency,
+                &contract_address,
+                &investment,
+                amount,
+            )?,
+            None => transfer_funds(
+                env,
+                &escrow.currency,
+                &contract_address,
+                &escrow.investor,
+                amount,
+            )?,
+        }
+
+        // Update escrow status
+        escrow.status = EscrowStatus::Refunded;
+        EscrowStorage::update_escrow(env, &escrow);
+    }
+
+    Ok(())
+}
 
-    if escrow.status != EscrowStatus::Held {
+fn held_escrows_or_err(env: &Env, invoice_id: &BytesN<32>) -> Result<Vec<Escrow>, QuickLendXError> {
+    if EscrowStorage::get_escrows_by_invoice(env, invoice_id).is_empty() {
+        return Err(QuickLendXError::StorageKeyNotFound);
+    }
+    let held = EscrowStorage::get_held_escrows_by_invoice(env, invoice_id);
+    if held.is_empty() {
         return Err(QuickLendXError::InvalidStatus);
     }
+    Ok(held)
+}
 
-    // Refund funds from escrow (contract) back to investor
+/// Check, without moving anything, that `transfer_funds` could pull `amount` from `from`
+/// into the contract.
+///
+/// # Errors
+/// * `InsufficientFunds` if the balance of `from` does not cover `amount`
+/// * `OperationNotAllowed` if its allowance to the contract does not cover `amount`
+pub fn check_transfer_in(
+    env: &Env,
+    currency: &Address,
+    from: &Address,
+    amount: i128,
+) -> Result<(), QuickLendXError> {
     let contract_address = env.current_contract_address();
-    transfer_funds(
-        env,
-        &escrow.currency,
-        &contract_address,
-        &escrow.investor,
-        escrow.amount,
-    )?;
-
-    // Update escrow status
-    escrow.status = EscrowStatus::Refunded;
-    EscrowStorage::update_escrow(env, &escrow);
-
+    if amount <= 0 || *from == contract_address {
+        return Ok(());
+    }
+    let token_client = token::Client::new(env, currency);
+    if token_client.balance(from) < amount {
+        return Err(QuickLendXError::InsufficientFunds);
+    }
+    if token_client.allowance(from, &contract_address) < amount {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
     Ok(())
 }
 
@@ -0,0 +1,656 @@
+//! Invoice pools with senior and junior tranches.
+//!
+//! An admin packages Verified invoices, with the consent of their businesses, into a pool
+//! that buys every invoice at `advance_bps` of its face value. Investors subscribe to the
+//! senior tranche or to the junior (first-loss) tranche. Once both tranches are fully
+//! subscribed, the pool funds every invoice through the regular escrow, `Investment` and
+//! receipt flow, with the contract itself holding the receipts on the pool's behalf. A
+//! pool not filled by its subscription deadline can be expired by anyone, which refunds
+//! every subscription and releases the invoices.
+//!
+//! Whatever is paid to those receipts (settlements, escrow refunds, recourse repayments)
+//! is credited to the pool and flows through a waterfall: the senior tranche is owed its
+//! principal plus its target return first, and the junior tranche receives the rest.
+//! Defaults reduce what the pool collects, so they hit the junior tranche before the
+//! senior one. Investors pull their share of the proceeds with `claim_pool_proceeds`.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::admin::AdminStorage;
+use crate::audit;
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_invoice_funded, emit_pool_cancelled, emit_pool_claimed, emit_pool_created,
+    emit_pool_expired, emit_pool_funded, emit_pool_loss, emit_pool_proceeds, emit_pool_subscribed,
+};
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+use crate::notifications::NotificationSystem;
+use crate::payments::{create_escrow, transfer_funds};
+use crate::receipt::Receipts;
+use crate::verification::{BusinessVerificationStatus, InvestorVerificationStorage};
+
+/// Maximum number of invoices in one pool.
+pub const MAX_POOL_INVOICES: u32 = 10;
+/// Maximum number of investors subscribed to one pool.
+pub const MAX_POOL_INVESTORS: u32 = 50;
+/// Longest subscription period a pool can be given.
+pub const MAX_POOL_SUBSCRIPTION_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum PoolKey {
+    Pool(u64),
+    /// Pooled invoice -> pool id
+    PoolOfInvoice(BytesN<32>),
+    PoolPosition(u64, Address),
+    PoolInvestors(u64),
+    PoolCounter,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrancheKind {
+    /// Paid first, at a lower target return.
+    Senior,
+    /// Paid after the senior tranche and absorbs losses first.
+    Junior,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PoolStatus {
+    /// Open for subscriptions; the invoices are reserved for the pool.
+    Subscribing,
+    /// Both tranches were filled and every invoice was funded.
+    Funded,
+    /// Withdrawn before funding; subscriptions were refunded.
+    Cancelled,
+    /// Not filled by its subscription deadline; subscriptions were refunded.
+    Expired,
+}
+
+/// Pool terms chosen by the admin.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolTerms {
+    pub currency: Address,
+    /// Price the pool pays for each invoice, in bps of its face value.
+    pub advance_bps: u32,
+    /// Share of the pool funded by the senior tranche, in bps.
+    pub senior_bps: u32,
+    /// Target return of the senior tranche, in bps of its principal.
+    pub senior_return_bps: u32,
+    /// Target return of the junior tranche, in bps of its principal.
+    pub junior_return_bps: u32,
+    /// How long the pool stays open for subscriptions, in seconds.
+    pub subscription_period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tranche {
+    pub size: i128,
+    pub subscribed: i128,
+    pub target_return_bps: u32,
+}
+
+impl Tranche {
+    /// Principal plus target return.
+    pub fn entitlement(&self) -> i128 {
+        self.size.saturating_add(
+            self.size.saturating_mul(self.target_return_bps as i128) / BPS_DENOMINATOR,
+        )
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoicePool {
+    pub pool_id: u64,
+    pub currency: Address,
+    pub invoice_ids: Vec<BytesN<32>>,
+    pub advance_bps: u32,
+    /// Sum of the prices paid for the invoices.
+    pub total_size: i128,
+    pub senior: Tranche,
+    pub junior: Tranche,
+    pub status: PoolStatus,
+    /// Proceeds credited to the pool so far.
+    pub collected: i128,
+    /// Uncovered principal of the pool's defaulted investments.
+    pub defaulted_principal: i128,
+    pub created_at: u64,
+    /// Subscriptions close at this timestamp; the pool can be expired after it.
+    pub subscription_deadline: u64,
+    pub funded_at: Option<u64>,
+}
+
+impl InvoicePool {
+    /// How the proceeds collected so far are split between the tranches.
+    pub fn waterfall(&self) -> PoolWaterfall {
+        let senior_due = self.senior.entitlement();
+        let senior_available = self.collected.min(senior_due);
+        PoolWaterfall {
+            collected: self.collected,
+            senior_due,
+            senior_available,
+            junior_available: self.collected.saturating_sub(senior_available),
+            defaulted_principal: self.defaulted_principal,
+        }
+    }
+
+    fn tranche_mut(&mut self, kind: TrancheKind) -> &mut Tranche {
+        match kind {
+            TrancheKind::Senior => &mut self.senior,
+            TrancheKind::Junior => &mut self.junior,
+        }
+    }
+}
+
+/// Split of the pool's proceeds between its tranches.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolWaterfall {
+    pub collected: i128,
+    /// Senior principal plus target return.
+    pub senior_due: i128,
+    pub senior_available: i128,
+    pub junior_available: i128,
+    pub defaulted_principal: i128,
+}
+
+/// An investor's subscriptions to a pool and what they have claimed so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolPosition {
+    pub senior: i128,
+    pub junior: i128,
+    pub senior_claimed: i128,
+    pub junior_claimed: i128,
+}
+
+impl PoolPosition {
+    /// Proceeds the position can claim now: its pro rata share of what each tranche has
+    /// been allotted, less what it already claimed.
+    pub fn claimable(&self, pool: &InvoicePool) -> (i128, i128) {
+        let waterfall = pool.waterfall();
+        (
+            Self::share(waterfall.senior_available, self.senior, pool.senior.size)
+                .saturating_sub(self.senior_claimed),
+            Self::share(waterfall.junior_available, self.junior, pool.junior.size)
+                .saturating_sub(self.junior_claimed),
+        )
+    }
+
+    fn share(available: i128, units: i128, tranche_size: i128) -> i128 {
+        available
+            .checked_mul(units)
+            .and_then(|v| v.checked_div(tranche_size))
+            .unwrap_or(0)
+    }
+}
+
+pub struct PoolStorage;
+
+impl PoolStorage {
+    pub fn get_pool(env: &Env, pool_id: u64) -> Option<InvoicePool> {
+        env.storage().persistent().get(&PoolKey::Pool(pool_id))
+    }
+
+    /// Pool an invoice is reserved for or funded by.
+    pub fn get_pool_for_invoice(env: &Env, invoice_id: &BytesN<32>) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&PoolKey::PoolOfInvoice(invoice_id.clone()))
+    }
+
+    pub fn get_position(env: &Env, pool_id: u64, investor: &Address) -> Option<PoolPosition> {
+        env.storage()
+            .persistent()
+            .get(&PoolKey::PoolPosition(pool_id, investor.clone()))
+    }
+
+    pub fn get_investors(env: &Env, pool_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&PoolKey::PoolInvestors(pool_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_pool(env: &Env, pool: &InvoicePool) {
+        env.storage()
+            .persistent()
+            .set(&PoolKey::Pool(pool.pool_id), pool);
+    }
+
+    fn store_position(env: &Env, pool_id: u64, investor: &Address, position: &PoolPosition) {
+        env.storage()
+            .persistent()
+            .set(&PoolKey::PoolPosition(pool_id, investor.clone()), position);
+    }
+
+    fn next_pool_id(env: &Env) -> u64 {
+        let next: u64 = env
+            .storage()
+            .persistent()
+            .get(&PoolKey::PoolCounter)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage().persistent().set(&PoolKey::PoolCounter, &next);
+        next
+    }
+}
+
+pub struct InvoicePools;
+
+impl InvoicePools {
+    /// Package Verified invoices into a pool (admin, with each business's consent).
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `OperationNotAllowed` if the invoice list is empty, too large, has duplicates or
+    ///   names an invoice already in a pool
+    /// * `InvalidAmount` if the terms are inconsistent
+    /// * `InvalidTimestamp` if the subscription period is zero or above
+    ///   `MAX_POOL_SUBSCRIPTION_SECS`
+    /// * `InvalidCurrency` if the currency is not whitelisted or an invoice uses another
+    /// * `InvoiceNotFound` / `InvalidStatus` if an invoice is missing or not Verified
+    pub fn create(
+        env: &Env,
+        admin: &Address,
+        invoice_ids: &Vec<BytesN<32>>,
+        terms: &PoolTerms,
+    ) -> Result<InvoicePool, QuickLendXError> {
+        admin.require_auth();
+        AdminStorage::require_admin(env, admin)?;
+        if invoice_ids.is_empty() || invoice_ids.len() > MAX_POOL_INVOICES {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Self::validate_terms(terms)?;
+        CurrencyWhitelist::require_allowed_currency(env, &terms.currency)?;
+
+        let mut total_size = 0i128;
+        for (index, invoice_id) in invoice_ids.iter().enumerate() {
+            if invoice_ids.first_index_of(&invoice_id) != Some(index as u32)
+                || PoolStorage::get_pool_for_invoice(env, &invoice_id).is_some()
+            {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            let invoice = InvoiceStorage::get_invoice(env, &invoice_id)
+                .ok_or(QuickLendXError::InvoiceNotFound)?;
+            if invoice.status != InvoiceStatus::Verified {
+                return Err(QuickLendXError::InvalidStatus);
+            }
+            if invoice.currency != terms.currency {
+                return Err(QuickLendXError::InvalidCurrency);
+            }
+            invoice.business.require_auth();
+            total_size = total_size
+                .checked_add(Self::price(invoice.amount, terms.advance_bps)?)
+                .ok_or(QuickLendXError::InvalidAmount)?;
+        }
+
+        let senior_size = total_size
+            .checked_mul(terms.senior_bps as i128)
+            .map(|v| v / BPS_DENOMINATOR)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+        let junior_size = total_size - senior_size;
+        if senior_size <= 0 || junior_size <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let pool = InvoicePool {
+            pool_id: PoolStorage::next_pool_id(env),
+            currency: terms.currency.clone(),
+            invoice_ids: invoice_ids.clone(),
+            advance_bps: terms.advance_bps,
+            total_size,
+            senior: Tranche {
+                size: senior_size,
+                subscribed: 0,
+                target_return_bps: terms.senior_return_bps,
+            },
+            junior: Tranche {
+                size: junior_size,
+                subscribed: 0,
+                target_return_bps: terms.junior_return_bps,
+            },
+            status: PoolStatus::Subscribing,
+            collected: 0,
+            defaulted_principal: 0,
+            created_at: env.ledger().timestamp(),
+            subscription_deadline: env
+                .ledger()
+                .timestamp()
+                .saturating_add(terms.subscription_period),
+            funded_at: None,
+        };
+        PoolStorage::store_pool(env, &pool);
+        for invoice_id in invoice_ids.iter() {
+            env.storage()
+                .persistent()
+                .set(&PoolKey::PoolOfInvoice(invoice_id), &pool.pool_id);
+        }
+
+        emit_pool_created(env, &pool);
+        Ok(pool)
+    }
+
+    /// Subscribe to a tranche of a pool (verified investor). The amount is transferred to
+    /// the contract; the subscription that fills both tranches funds the pool's invoices.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the investor is not verified
+    /// * `StorageKeyNotFound` if the pool does not exist
+    /// * `InvalidStatus` if the pool is not open for subscriptions, or a pooled invoice
+    ///   is no longer Verified when the pool funds
+    /// * `InvalidTimestamp` if the subscription deadline has passed
+    /// * `InvalidAmount` if `amount` is not positive or exceeds what the tranche lacks
+    /// * `OperationNotAllowed` if the pool has reached `MAX_POOL_INVESTORS`
+    /// * Any error from the token transfers
+    pub fn subscribe(
+        env: &Env,
+        investor: &Address,
+        pool_id: u64,
+        tranche: TrancheKind,
+        amount: i128,
+    ) -> Result<InvoicePool, QuickLendXError> {
+        investor.require_auth();
+        let verification = InvestorVerificationStorage::get(env, investor)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        let mut pool =
+            PoolStorage::get_pool(env, pool_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if pool.status != PoolStatus::Subscribing {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if env.ledger().timestamp() > pool.subscription_deadline {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        let target = pool.tranche_mut(tranche);
+        if amount <= 0 || amount > target.size.saturating_sub(target.subscribed) {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        target.subscribed += amount;
+
+        let mut position = match PoolStorage::get_position(env, pool_id, investor) {
+            Some(position) => position,
+            None => {
+                let mut investors = PoolStorage::get_investors(env, pool_id);
+                if investors.len() >= MAX_POOL_INVESTORS {
+                    return Err(QuickLendXError::OperationNotAllowed);
+                }
+                investors.push_back(investor.clone());
+                env.storage()
+                    .persistent()
+                    .set(&PoolKey::PoolInvestors(pool_id), &investors);
+                PoolPosition {
+                    senior: 0,
+                    junior: 0,
+                    senior_claimed: 0,
+                    junior_claimed: 0,
+                }
+            }
+        };
+        match tranche {
+            TrancheKind::Senior => position.senior += amount,
+            TrancheKind::Junior => position.junior += amount,
+        }
+
+        transfer_funds(
+            env,
+            &pool.currency,
+            investor,
+            &env.current_contract_address(),
+            amount,
+        )?;
+        PoolStorage::store_position(env, pool_id, investor, &position);
+        emit_pool_subscribed(env, pool_id, investor, tranche, amount);
+
+        if pool.senior.subscribed == pool.senior.size && pool.junior.subscribed == pool.junior.size
+        {
+            Self::fund(env, &mut pool)?;
+        }
+        PoolStorage::store_pool(env, &pool);
+        Ok(pool)
+    }
+
+    /// Withdraw a pool before it is funded (admin only), refunding every subscription
+    /// and releasing its invoices.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `StorageKeyNotFound` if the pool does not exist
+    /// * `InvalidStatus` if the pool is not open for subscriptions
+    pub fn cancel(env: &Env, admin: &Address, pool_id: u64) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        AdminStorage::require_admin(env, admin)?;
+        let mut pool =
+            PoolStorage::get_pool(env, pool_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if pool.status != PoolStatus::Subscribing {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        Self::unwind(env, &mut pool, PoolStatus::Cancelled)?;
+        emit_pool_cancelled(env, pool_id, admin);
+        Ok(())
+    }
+
+    /// Expire a pool that was not filled by its subscription deadline, refunding every
+    /// subscription and releasing its invoices. Anyone can call this.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the pool does not exist
+    /// * `InvalidStatus` if the pool is not open for subscriptions
+    /// * `InvalidTimestamp` if the subscription deadline has not passed yet
+    pub fn expire(env: &Env, pool_id: u64) -> Result<(), QuickLendXError> {
+        let mut pool =
+            PoolStorage::get_pool(env, pool_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if pool.status != PoolStatus::Subscribing {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if env.ledger().timestamp() <= pool.subscription_deadline {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+
+        Self::unwind(env, &mut pool, PoolStatus::Expired)?;
+        emit_pool_expired(env, &pool);
+        Ok(())
+    }
+
+    /// Pay an investor the proceeds the waterfall has allotted to their subscriptions.
+    /// Returns the amount paid, which is zero when nothing new is claimable.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the pool does not exist
+    /// * `InvalidStatus` if the pool is not funded
+    /// * `NotInvestor` if `investor` has no subscription in the pool
+    pub fn claim(env: &Env, investor: &Address, pool_id: u64) -> Result<i128, QuickLendXError> {
+        investor.require_auth();
+        let pool =
+            PoolStorage::get_pool(env, pool_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if pool.status != PoolStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let mut position = PoolStorage::get_position(env, pool_id, investor)
+            .ok_or(QuickLendXError::NotInvestor)?;
+
+        let (senior, junior) = position.claimable(&pool);
+        let amount = senior + junior;
+        if amount > 0 {
+            position.senior_claimed += senior;
+            position.junior_claimed += junior;
+            PoolStorage::store_position(env, pool_id, investor, &position);
+            transfer_funds(
+                env,
+                &pool.currency,
+                &env.current_contract_address(),
+                investor,
+                amount,
+            )?;
+            emit_pool_claimed(env, pool_id, investor, senior, junior);
+        }
+        Ok(amount)
+    }
+
+    /// Credit a payment received on a pool-held receipt to the pool.
+    pub fn record_proceeds(env: &Env, invoice_id: &BytesN<32>, amount: i128) {
+        if let Some(mut pool) = Self::funded_pool_for_invoice(env, invoice_id) {
+            pool.collected = pool.collected.saturating_add(amount);
+            PoolStorage::store_pool(env, &pool);
+            emit_pool_proceeds(env, &pool, invoice_id, amount);
+        }
+    }
+
+    /// Record the uncovered principal of a defaulted pool investment.
+    pub fn record_loss(env: &Env, investment: &Investment, loss: i128) {
+        if investment.investor != env.current_contract_address() || loss <= 0 {
+            return;
+        }
+        if let Some(mut pool) = Self::funded_pool_for_invoice(env, &investment.invoice_id) {
+            pool.defaulted_principal = pool.defaulted_principal.saturating_add(loss);
+            PoolStorage::store_pool(env, &pool);
+            emit_pool_loss(env, &pool, &investment.invoice_id, loss);
+        }
+    }
+
+    /// Invoices reserved for a pool cannot be bid on or funded through bids.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice belongs to a pool
+    pub fn require_unpooled(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        match PoolStorage::get_pool_for_invoice(env, invoice_id) {
+            Some(_) => Err(QuickLendXError::OperationNotAllowed),
+            None => Ok(()),
+        }
+    }
+
+    /// Refund every subscription of an unfunded pool and release its invoices.
+    fn unwind(
+        env: &Env,
+        pool: &mut InvoicePool,
+        status: PoolStatus,
+    ) -> Result<(), QuickLendXError> {
+        let contract_address = env.current_contract_address();
+        for investor in PoolStorage::get_investors(env, pool.pool_id).iter() {
+            if let Some(position) = PoolStorage::get_position(env, pool.pool_id, &investor) {
+                let refund = position.senior + position.junior;
+                if refund > 0 {
+                    transfer_funds(env, &pool.currency, &contract_address, &investor, refund)?;
+                }
+            }
+        }
+        for invoice_id in pool.invoice_ids.iter() {
+            env.storage()
+                .persistent()
+                .remove(&PoolKey::PoolOfInvoice(invoice_id));
+        }
+        pool.status = status;
+        PoolStorage::store_pool(env, pool);
+        Ok(())
+    }
+
+    /// Fund every invoice of a fully subscribed pool out of the subscriptions held by
+    /// the contract.
+    fn fund(env: &Env, pool: &mut InvoicePool) -> Result<(), QuickLendXError> {
+        let contract_address = env.current_contract_address();
+        let now = env.ledger().timestamp();
+        for invoice_id in pool.invoice_ids.iter() {
+            let mut invoice = InvoiceStorage::get_invoice(env, &invoice_id)
+                .ok_or(QuickLendXError::InvoiceNotFound)?;
+            if invoice.status != InvoiceStatus::Verified {
+                return Err(QuickLendXError::InvalidStatus);
+            }
+            let price = Self::price(invoice.amount, pool.advance_bps)?;
+            // The subscriptions are already held by the contract, so no transfer happens
+            let escrow_id = create_escrow(
+                env,
+                &invoice_id,
+                &contract_address,
+                &invoice.business,
+                price,
+                &invoice.currency,
+            )?;
+
+            InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Verified, &invoice_id);
+            invoice.mark_as_funded(env, contract_address.clone(), price, now);
+            InvoiceStorage::update_invoice(env, &invoice);
+            InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Funded, &invoice_id);
+
+            let investment = Investment {
+                investment_id: InvestmentStorage::generate_unique_investment_id(env),
+                invoice_id: invoice_id.clone(),
+                investor: contract_address.clone(),
+                amount: price,
+                funded_at: now,
+                status: InvestmentStatus::Active,
+                insurance: Vec::new(env),
+            };
+            InvestmentStorage::store_investment(env, &investment);
+            Receipts::mint(env, &investment, &escrow_id);
+
+            audit::log_escrow_created(
+                env,
+                invoice_id.clone(),
+                contract_address.clone(),
+                price,
+                escrow_id,
+            );
+            emit_invoice_funded(env, &invoice_id, &contract_address, price);
+            let _ = NotificationSystem::notify_invoice_status_changed(
+                env,
+                &invoice,
+                &InvoiceStatus::Verified,
+                &InvoiceStatus::Funded,
+            );
+        }
+
+        pool.status = PoolStatus::Funded;
+        pool.funded_at = Some(now);
+        emit_pool_funded(env, pool);
+        Ok(())
+    }
+
+    fn funded_pool_for_invoice(env: &Env, invoice_id: &BytesN<32>) -> Option<InvoicePool> {
+        let pool_id = PoolStorage::get_pool_for_invoice(env, invoice_id)?;
+        PoolStorage::get_pool(env, pool_id).filter(|pool| pool.status == PoolStatus::Funded)
+    }
+
+    fn price(invoice_amount: i128, advance_bps: u32) -> Result<i128, QuickLendXError> {
+        let price = invoice_amount
+            .checked_mul(advance_bps as i128)
+            .map(|v| v / BPS_DENOMINATOR)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+        if price <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        Ok(price)
+    }
+
+    fn validate_terms(terms: &PoolTerms) -> Result<(), QuickLendXError> {
+        if terms.advance_bps == 0 || terms.advance_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.senior_bps == 0 || terms.senior_bps as i128 >= BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        // The first-loss tranche has to be paid more than the senior one
+        if terms.junior_return_bps <= terms.senior_return_bps {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.subscription_period == 0 || terms.subscription_period > MAX_POOL_SUBSCRIPTION_SECS
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
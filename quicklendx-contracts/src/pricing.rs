@@ -0,0 +1,149 @@
+//! Time-based pricing of invoice returns.
+//!
+//! By default an investor is owed its pro-rata share of everything the business pays,
+//! however early it settles. A business can instead price its invoice by time before it
+//! is funded: an annualized rate on each investor's principal, accrued per started day
+//! since funding, with a minimum fee. When such an invoice is settled before its due
+//! date, the part of the payment the investors have not earned yet is an early-payment
+//! discount for the business, less any prepayment fee set by the `FeeType::EarlyPayment`
+//! fee structure.
+
+use soroban_sdk::{contracttype, BytesN, Env};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::emit_time_pricing_set;
+use crate::fees::{FeeManager, FeeType};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+
+/// Highest annualized rate a business can offer, in bps.
+pub const MAX_ANNUAL_RATE_BPS: u32 = 10_000;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const DAYS_PER_YEAR: i128 = 365;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum PricingKey {
+    InvoiceTimePricing(BytesN<32>),
+}
+
+/// Time-based pricing of an invoice's investor return.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimePricing {
+    /// Annualized return on principal, in bps.
+    pub annual_rate_bps: u32,
+    /// Minimum return over the whole invoice, shared by investors pro rata.
+    pub min_fee: i128,
+}
+
+impl TimePricing {
+    /// Principal plus the return earned on it after `elapsed` seconds: the accrued
+    /// interest for every started day, or the investment's share of the minimum fee if
+    /// that is higher.
+    pub fn entitlement(&self, principal: i128, funded_total: i128, elapsed: u64) -> i128 {
+        let days = elapsed.div_ceil(SECONDS_PER_DAY) as i128;
+        let accrued = principal
+            .saturating_mul(self.annual_rate_bps as i128)
+            .saturating_mul(days)
+            / (BPS_DENOMINATOR * DAYS_PER_YEAR);
+        let min_fee = if funded_total > 0 {
+            self.min_fee.saturating_mul(principal) / funded_total
+        } else {
+            0
+        };
+        principal.saturating_add(accrued.max(min_fee))
+    }
+}
+
+pub struct TimeBasedPricing;
+
+impl TimeBasedPricing {
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Option<TimePricing> {
+        env.storage()
+            .persistent()
+            .get(&PricingKey::InvoiceTimePricing(invoice_id.clone()))
+    }
+
+    /// Price an invoice by time (business only, before funding and before any bid is
+    /// placed on the terms it replaces).
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the invoice already has open bids
+    /// * `InvalidAmount` if the rate is zero or above `MAX_ANNUAL_RATE_BPS`, or the minimum
+    ///   fee is negative or not below the invoice amount
+    pub fn set(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        pricing: &TimePricing,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if pricing.annual_rate_bps == 0 || pricing.annual_rate_bps > MAX_ANNUAL_RATE_BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if pricing.min_fee < 0 || pricing.min_fee >= invoice.amount {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&PricingKey::InvoiceTimePricing(invoice_id.clone()), pricing);
+        emit_time_pricing_set(env, invoice_id, pricing);
+        Ok(())
+    }
+
+    /// Split one investment's settlement share into what the investor is owed and the
+    /// early-payment discount left to the business.
+    ///
+    /// Only invoices with time pricing that are settled before their due date get a
+    /// discount: the share the investor has not earned yet, less the prepayment fee an
+    /// active `FeeType::EarlyPayment` fee structure keeps for the investor (`base_fee_bps`
+    /// of the unearned amount, within `min_fee`..`max_fee`).
+    pub fn early_settlement_split(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        due_date: u64,
+        principal: i128,
+        funded_total: i128,
+        funded_at: u64,
+        share: i128,
+    ) -> (i128, i128) {
+        let now = env.ledger().timestamp();
+        let pricing = match Self::get(env, invoice_id) {
+            Some(pricing) if now < due_date => pricing,
+            _ => return (share, 0),
+        };
+        let earned = pricing
+            .entitlement(principal, funded_total, now.saturating_sub(funded_at))
+            .min(share);
+        let unearned = share.saturating_sub(earned);
+        if unearned <= 0 {
+            return (share, 0);
+        }
+        let prepayment_fee = match FeeManager::get_fee_structure(env, &FeeType::EarlyPayment) {
+            Ok(structure) if structure.is_active => {
+                let fee = unearned.saturating_mul(structure.base_fee_bps as i128) / BPS_DENOMINATOR;
+                fee.clamp(structure.min_fee, structure.max_fee)
+                    .min(unearned)
+            }
+            _ => 0,
+        };
+        let discount = unearned - prepayment_fee;
+        (share - discount, discount)
+    }
+}
@@ -0,0 +1,382 @@
+//! Transferable receipts for investment positions.
+//!
+//! Every funded `Investment` mints a receipt whose supply equals the invested principal,
+//! credited to the investor. Units can be transferred directly or through an expiring
+//! allowance. Receipts are balances kept by this contract and addressed by investment
+//! id; they do not implement the token interface, so other contracts cannot use a
+//! token client to hold or move them.
+//!
+//! Holding receipt units entitles the holder to the matching share of what the
+//! position is paid: settlement, progressive distributions and escrow refunds pay
+//! current holders pro rata to their balances instead of the stored
+//! `Investment.investor`. A default freezes the receipt; recourse repayments made
+//! afterwards go to the holders at the time of the default.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_receipt_approved, emit_receipt_minted, emit_receipt_paid, emit_receipt_transferred,
+};
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::payments::transfer_funds;
+use crate::pool::InvoicePools;
+use crate::vault::LiquidityVaults;
+
+/// Maximum number of distinct holders of one receipt, bounding settlement fan-out.
+pub const MAX_RECEIPT_HOLDERS: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ReceiptKey {
+    Receipt(BytesN<32>),
+    Balance(BytesN<32>, Address),
+    Allowance(BytesN<32>, Address, Address),
+    /// Escrow that funded an investment -> investment id
+    Escrow(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvestmentReceipt {
+    pub investment_id: BytesN<32>,
+    pub invoice_id: BytesN<32>,
+    pub total_supply: i128,
+    /// Addresses with a non-zero balance, in the order they first received units.
+    pub holders: Vec<Address>,
+    /// Set once the position has been settled, defaulted or refunded.
+    pub redeemed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceiptAllowance {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+pub struct ReceiptStorage;
+
+impl ReceiptStorage {
+    pub fn get_receipt(env: &Env, investment_id: &BytesN<32>) -> Option<InvestmentReceipt> {
+        env.storage()
+            .persistent()
+            .get(&ReceiptKey::Receipt(investment_id.clone()))
+    }
+
+    /// Investment funded by an escrow, if it was minted a receipt.
+    pub fn get_investment_for_escrow(env: &Env, escrow_id: &BytesN<32>) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&ReceiptKey::Escrow(escrow_id.clone()))
+    }
+
+    pub fn balance(env: &Env, investment_id: &BytesN<32>, holder: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&ReceiptKey::Balance(investment_id.clone(), holder.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Allowance of `spender` over `from`'s receipt units; zero once expired.
+    pub fn allowance(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+    ) -> i128 {
+        let allowance: Option<ReceiptAllowance> = env.storage().persistent().get(
+            &ReceiptKey::Allowance(investment_id.clone(), from.clone(), spender.clone()),
+        );
+        match allowance {
+            Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => {
+                allowance.amount
+            }
+            _ => 0,
+        }
+    }
+
+    fn store_receipt(env: &Env, receipt: &InvestmentReceipt) {
+        env.storage()
+            .persistent()
+            .set(&ReceiptKey::Receipt(receipt.investment_id.clone()), receipt);
+    }
+
+    fn set_balance(env: &Env, investment_id: &BytesN<32>, holder: &Address, amount: i128) {
+        let key = ReceiptKey::Balance(investment_id.clone(), holder.clone());
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &amount);
+        }
+    }
+
+    fn set_allowance(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+        allowance: &ReceiptAllowance,
+    ) {
+        env.storage().persistent().set(
+            &ReceiptKey::Allowance(investment_id.clone(), from.clone(), spender.clone()),
+            allowance,
+        );
+    }
+}
+
+pub struct Receipts;
+
+impl Receipts {
+    /// Mint the receipt of a newly funded investment to its investor and link it to the
+    /// escrow holding its principal, so that a refund reaches the current holders.
+    pub fn mint(env: &Env, investment: &Investment, escrow_id: &BytesN<32>) {
+        let mut holders = Vec::new(env);
+        holders.push_back(investment.investor.clone());
+        let receipt = InvestmentReceipt {
+            investment_id: investment.investment_id.clone(),
+            invoice_id: investment.invoice_id.clone(),
+            total_supply: investment.amount,
+            holders,
+            redeemed: false,
+        };
+        ReceiptStorage::store_receipt(env, &receipt);
+        env.storage().persistent().set(
+            &ReceiptKey::Escrow(escrow_id.clone()),
+            &investment.investment_id,
+        );
+        ReceiptStorage::set_balance(
+            env,
+            &investment.investment_id,
+            &investment.investor,
+            investment.amount,
+        );
+        emit_receipt_minted(env, investment);
+    }
+
+    /// Transfer receipt units.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no receipt
+    /// * `InvalidStatus` if the position has been redeemed or is no longer Active
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `InsufficientFunds` if `from` holds fewer units
+    /// * `OperationNotAllowed` if the transfer would exceed `MAX_RECEIPT_HOLDERS`
+    pub fn transfer(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        from.require_auth();
+        Self::move_units(env, investment_id, from, to, amount)
+    }
+
+    /// Allow `spender` to transfer up to `amount` of `from`'s units until
+    /// `expiration_ledger`.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is negative
+    /// * `InvalidTimestamp` if a non-zero allowance expires before the current ledger
+    pub fn approve(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), QuickLendXError> {
+        from.require_auth();
+        ReceiptStorage::get_receipt(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if amount < 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        ReceiptStorage::set_allowance(
+            env,
+            investment_id,
+            from,
+            spender,
+            &ReceiptAllowance {
+                amount,
+                expiration_ledger,
+            },
+        );
+        emit_receipt_approved(env, investment_id, from, spender, amount, expiration_ledger);
+        Ok(())
+    }
+
+    /// Transfer units on behalf of `from` using an allowance.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the allowance is insufficient
+    /// * Any error from `transfer`
+    pub fn transfer_from(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        spender: &Address,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        spender.require_auth();
+        let allowance = ReceiptStorage::allowance(env, investment_id, from, spender);
+        if amount > allowance {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Self::move_units(env, investment_id, from, to, amount)?;
+        let key = ReceiptKey::Allowance(investment_id.clone(), from.clone(), spender.clone());
+        let mut stored: ReceiptAllowance = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        stored.amount = allowance - amount;
+        env.storage().persistent().set(&key, &stored);
+        Ok(())
+    }
+
+    /// Move every unit `from` holds to `to`; used when a whole position changes hands.
+    pub fn move_all(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        to: &Address,
+    ) -> Result<(), QuickLendXError> {
+        let balance = ReceiptStorage::balance(env, investment_id, from);
+        if balance == 0 {
+            return Ok(());
+        }
+        Self::move_units(env, investment_id, from, to, balance)
+    }
+
+    /// Whether `holder` owns the whole receipt of an investment. Investments funded
+    /// before receipts existed are owned by their stored investor.
+    pub fn holds_all(env: &Env, investment: &Investment, holder: &Address) -> bool {
+        match ReceiptStorage::get_receipt(env, &investment.investment_id) {
+            Some(receipt) => {
+                ReceiptStorage::balance(env, &investment.investment_id, holder)
+                    == receipt.total_supply
+            }
+            None => investment.investor == *holder,
+        }
+    }
+
+    /// Split `amount` between the current holders of an investment's receipt, pro rata
+    /// to their balances. The last holder absorbs the rounding remainder. Investments
+    /// funded before receipts existed go entirely to the stored investor.
+    pub fn holder_shares(
+        env: &Env,
+        investment: &Investment,
+        amount: i128,
+    ) -> Result<Vec<(Address, i128)>, QuickLendXError> {
+        let mut shares = Vec::new(env);
+        let receipt = match ReceiptStorage::get_receipt(env, &investment.investment_id) {
+            Some(receipt) if receipt.total_supply > 0 => receipt,
+            _ => {
+                shares.push_back((investment.investor.clone(), amount));
+                return Ok(shares);
+            }
+        };
+
+        let last_index = receipt.holders.len().saturating_sub(1);
+        let mut distributed = 0i128;
+        for (index, holder) in receipt.holders.iter().enumerate() {
+            let share = if index as u32 == last_index {
+                amount
+                    .checked_sub(distributed)
+                    .ok_or(QuickLendXError::InvalidAmount)?
+            } else {
+                let balance = ReceiptStorage::balance(env, &investment.investment_id, &holder);
+                amount
+                    .checked_mul(balance)
+                    .and_then(|v| v.checked_div(receipt.total_supply))
+                    .ok_or(QuickLendXError::InvalidAmount)?
+            };
+            distributed = distributed
+                .checked_add(share)
+                .ok_or(QuickLendXError::InvalidAmount)?;
+            shares.push_back((holder, share));
+        }
+        Ok(shares)
+    }
+
+    /// Pay `amount` from `payer` to the receipt holders of an investment.
+    pub fn pay_holders(
+        env: &Env,
+        currency: &Address,
+        payer: &Address,
+        investment: &Investment,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        for (holder, share) in Self::holder_shares(env, investment, amount)?.iter() {
+            if share > 0 {
+                transfer_funds(env, currency, payer, &holder, share)?;
+                // Receipts held by the contract belong to an invoice pool or a vault
+                if holder == env.current_contract_address() {
+                    InvoicePools::record_proceeds(env, &investment.invoice_id, share);
+                    LiquidityVaults::record_proceeds(env, &investment.invoice_id, share);
+                }
+                emit_receipt_paid(env, &investment.investment_id, &holder, share);
+            }
+        }
+        Ok(())
+    }
+
+    /// Freeze the receipt once the position has been settled, defaulted or refunded.
+    pub fn redeem(env: &Env, investment_id: &BytesN<32>) {
+        if let Some(mut receipt) = ReceiptStorage::get_receipt(env, investment_id) {
+            receipt.redeemed = true;
+            ReceiptStorage::store_receipt(env, &receipt);
+        }
+    }
+
+    fn move_units(
+        env: &Env,
+        investment_id: &BytesN<32>,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let mut receipt = ReceiptStorage::get_receipt(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let investment = InvestmentStorage::get_investment(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if receipt.redeemed || investment.status != InvestmentStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let from_balance = ReceiptStorage::balance(env, investment_id, from);
+        if from_balance < amount {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        if from == to {
+            return Ok(());
+        }
+
+        let to_balance = ReceiptStorage::balance(env, investment_id, to);
+        if to_balance == 0 {
+            if receipt.holders.len() >= MAX_RECEIPT_HOLDERS && from_balance != amount {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            receipt.holders.push_back(to.clone());
+        }
+        ReceiptStorage::set_balance(env, investment_id, from, from_balance - amount);
+        ReceiptStorage::set_balance(env, investment_id, to, to_balance + amount);
+        if from_balance == amount {
+            if let Some(index) = receipt.holders.first_index_of(from) {
+                receipt.holders.remove(index);
+            }
+        }
+        ReceiptStorage::store_receipt(env, &receipt);
+
+        emit_receipt_transferred(env, investment_id, from, to, amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+//! Recourse and non-recourse factoring.
+//!
+//! Invoices are non-recourse by default: on default the investors bear the loss, less
+//! any insurance coverage. A business can instead offer an invoice on recourse terms
+//! before it is funded. When a recourse invoice defaults, `handle_default` opens a
+//! recourse obligation for the uncovered principal of every defaulted investment; the
+//! business cannot upload new invoices until it has repaid all of its obligations
+//! through `repay_recourse`. Repayments go to the current receipt holders of each
+//! defaulted position.
+//!
+//! A funded invoice refunded after some of its escrow went to the business through
+//! milestones leaves the business owing those released amounts, whatever its factoring
+//! mode. The positions stay Active until the obligation is repaid, then complete.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_factoring_mode_set, emit_recourse_opened, emit_recourse_repaid};
+use crate::investment::{InvestmentStatus, InvestmentStorage};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::receipt::Receipts;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum RecourseKey {
+    Mode(BytesN<32>),
+    Obligation(BytesN<32>),
+    /// Invoices with an outstanding obligation, per business
+    Outstanding(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FactoringMode {
+    /// Investors bear default losses.
+    NonRecourse,
+    /// The business must make investors whole on default.
+    Recourse,
+}
+
+/// The business's debt towards one defaulted investment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecourseClaim {
+    pub investment_id: BytesN<32>,
+    /// Principal not covered by insurance.
+    pub amount: i128,
+    pub repaid: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecourseObligation {
+    pub invoice_id: BytesN<32>,
+    pub business: Address,
+    pub currency: Address,
+    pub claims: Vec<RecourseClaim>,
+    pub total_amount: i128,
+    pub total_repaid: i128,
+    pub created_at: u64,
+    pub settled_at: Option<u64>,
+}
+
+impl RecourseObligation {
+    pub fn outstanding(&self) -> i128 {
+        self.total_amount.saturating_sub(self.total_repaid)
+    }
+}
+
+pub struct RecourseStorage;
+
+impl RecourseStorage {
+    /// Factoring mode of an invoice; `NonRecourse` unless the business chose otherwise.
+    pub fn get_mode(env: &Env, invoice_id: &BytesN<32>) -> FactoringMode {
+        env.storage()
+            .persistent()
+            .get(&RecourseKey::Mode(invoice_id.clone()))
+            .unwrap_or(FactoringMode::NonRecourse)
+    }
+
+    pub fn get_obligation(env: &Env, invoice_id: &BytesN<32>) -> Option<RecourseObligation> {
+        env.storage()
+            .persistent()
+            .get(&RecourseKey::Obligation(invoice_id.clone()))
+    }
+
+    /// Obligations of a business that are not fully repaid.
+    pub fn get_outstanding_obligations(env: &Env, business: &Address) -> Vec<RecourseObligation> {
+        let mut obligations = Vec::new(env);
+        for invoice_id in Self::get_outstanding_ids(env, business).iter() {
+            if let Some(obligation) = Self::get_obligation(env, &invoice_id) {
+                obligations.push_back(obligation);
+            }
+        }
+        obligations
+    }
+
+    fn get_outstanding_ids(env: &Env, business: &Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&RecourseKey::Outstanding(business.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_outstanding_ids(env: &Env, business: &Address, ids: &Vec<BytesN<32>>) {
+        env.storage()
+            .persistent()
+            .set(&RecourseKey::Outstanding(business.clone()), ids);
+    }
+
+    fn store_obligation(env: &Env, obligation: &RecourseObligation) {
+        env.storage().persistent().set(
+            &RecourseKey::Obligation(obligation.invoice_id.clone()),
+            obligation,
+        );
+    }
+}
+
+pub struct Recourse;
+
+impl Recourse {
+    /// Choose the factoring mode of an invoice (business only).
+    ///
+    /// The mode can change until the invoice is funded, except that an invoice with open
+    /// bids cannot drop recourse: those bids were priced on the business's guarantee.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is no longer Pending or Verified
+    /// * `OperationNotAllowed` if recourse would be removed under open bids
+    pub fn set_mode(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        mode: FactoringMode,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if mode == FactoringMode::NonRecourse
+            && RecourseStorage::get_mode(env, invoice_id) == FactoringMode::Recourse
+            && !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty()
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&RecourseKey::Mode(invoice_id.clone()), &mode);
+        emit_factoring_mode_set(env, &invoice, mode);
+        Ok(())
+    }
+
+    /// Open the recourse obligation of a defaulted recourse invoice. `claims` holds the
+    /// uncovered principal of each defaulted investment. No-op for non-recourse invoices.
+    pub fn open_obligation(env: &Env, invoice: &Invoice, claims: Vec<RecourseClaim>) {
+        if RecourseStorage::get_mode(env, &invoice.id) != FactoringMode::Recourse {
+            return;
+        }
+        Self::store_new_obligation(env, invoice, claims);
+    }
+
+    /// Open the obligation of a refunded invoice for the escrow its milestones already
+    /// released to the business, whatever its factoring mode. `claims` holds the released
+    /// amount of each position.
+    pub fn open_refund_obligation(env: &Env, invoice: &Invoice, claims: Vec<RecourseClaim>) {
+        Self::store_new_obligation(env, invoice, claims);
+    }
+
+    fn store_new_obligation(env: &Env, invoice: &Invoice, claims: Vec<RecourseClaim>) {
+        let total_amount = claims
+            .iter()
+            .fold(0i128, |total, claim| total.saturating_add(claim.amount));
+        if total_amount <= 0 {
+            return;
+        }
+
+        let obligation = RecourseObligation {
+            invoice_id: invoice.id.clone(),
+            business: invoice.business.clone(),
+            currency: invoice.currency.clone(),
+            claims,
+            total_amount,
+            total_repaid: 0,
+            created_at: env.ledger().timestamp(),
+            settled_at: None,
+        };
+        RecourseStorage::store_obligation(env, &obligation);
+        let mut outstanding = RecourseStorage::get_outstanding_ids(env, &invoice.business);
+        outstanding.push_back(invoice.id.clone());
+        RecourseStorage::set_outstanding_ids(env, &invoice.business, &outstanding);
+
+        emit_recourse_opened(env, &obligation);
+    }
+
+    /// Pay down the recourse obligation of an invoice (business only).
+    ///
+    /// The payment is spread over the investments in proportion to what is still owed on
+    /// each, and paid to their current receipt holders. Positions left Active by a refund
+    /// complete once the obligation is repaid.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no recourse obligation
+    /// * `Unauthorized` if `business` does not owe it
+    /// * `InvalidStatus` if it is already repaid
+    /// * `InvalidAmount` if `amount` is not positive or exceeds the outstanding balance
+    /// * Any error from the token transfers
+    pub fn repay(
+        env: &Env,
+        business: &Address,
+        invoice_id: &BytesN<32>,
+        amount: i128,
+    ) -> Result<RecourseObligation, QuickLendXError> {
+        business.require_auth();
+        let mut obligation = RecourseStorage::get_obligation(env, invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if obligation.business != *business {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        let outstanding = obligation.outstanding();
+        if outstanding == 0 {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if amount <= 0 || amount > outstanding {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        // Pro rata over what is still owed per claim; the last open claim absorbs the
+        // rounding remainder.
+        let open_claims = obligation
+            .claims
+            .iter()
+            .filter(|claim| claim.amount > claim.repaid)
+            .count() as u32;
+        let mut remaining_claims = open_claims;
+        let mut distributed = 0i128;
+        let mut claims = Vec::new(env);
+        for mut claim in obligation.claims.iter() {
+            let owed = claim.amount - claim.repaid;
+            if owed > 0 {
+                remaining_claims -= 1;
+                let share = if remaining_claims == 0 {
+                    amount - distributed
+                } else {
+                    amount
+                        .checked_mul(owed)
+                        .and_then(|v| v.checked_div(outstanding))
+                        .ok_or(QuickLendXError::InvalidAmount)?
+                };
+                if share > 0 {
+                    let investment = InvestmentStorage::get_investment(env, &claim.investment_id)
+                        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+                    Receipts::pay_holders(env, &obligation.currency, business, &investment, share)?;
+                    claim.repaid += share;
+                    distributed += share;
+                }
+            }
+            claims.push_back(claim);
+        }
+        obligation.claims = claims;
+        obligation.total_repaid += amount;
+
+        if obligation.outstanding() == 0 {
+            obligation.settled_at = Some(env.ledger().timestamp());
+            for claim in obligation.claims.iter() {
+                if let Some(mut investment) =
+                    InvestmentStorage::get_investment(env, &claim.investment_id)
+                {
+                    if investment.status == InvestmentStatus::Active {
+                        investment.status = InvestmentStatus::Completed;
+                        InvestmentStorage::update_investment(env, &investment);
+                        Receipts::redeem(env, &investment.investment_id);
+                    }
+                }
+            }
+            let mut remaining = Vec::new(env);
+            for id in RecourseStorage::get_outstanding_ids(env, business).iter() {
+                if id != *invoice_id {
+                    remaining.push_back(id);
+                }
+            }
+            RecourseStorage::set_outstanding_ids(env, business, &remaining);
+        }
+        RecourseStorage::store_obligation(env, &obligation);
+
+        emit_recourse_repaid(env, &obligation, amount);
+        Ok(obligation)
+    }
+
+    /// Businesses with unpaid recourse obligations cannot upload new invoices.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if `business` has an outstanding obligation
+    pub fn require_no_outstanding(env: &Env, business: &Address) -> Result<(), QuickLendXError> {
+        if RecourseStorage::get_outstanding_ids(env, business).is_empty() {
+            Ok(())
+        } else {
+            Err(QuickLendXError::OperationNotAllowed)
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Reverse factoring (buyer-led supply-chain finance).
+//!
+//! A verified buyer registers approved payables it owes to its suppliers. A supplier can
+//! then sell one early by turning it into an invoice that goes through the regular
+//! verification, bidding and funding flow. Investors price the buyer's credit: the
+//! buyer, not the supplier, is the obligor of the resulting invoice, so payments are
+//! recorded against the buyer and settlement pulls the funds from the buyer.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Vec};
+
+use crate::audit;
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_invoice_uploaded, emit_payable_cancelled, emit_payable_registered, emit_payable_sold,
+};
+use crate::invoice::{Invoice, InvoiceCategory, InvoiceStorage};
+use crate::notifications::NotificationSystem;
+use crate::protocol_limits::ProtocolLimitsContract;
+use crate::recourse::Recourse;
+use crate::verification::{get_business_verification_status, BusinessVerificationStatus};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ReverseFactoringKey {
+    Payable(u64),
+    PayablesByBuyer(Address),
+    PayablesBySupplier(Address),
+    /// Invoice created from a payable -> payable id
+    InvoicePayable(BytesN<32>),
+    PayableCounter,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayableStatus {
+    /// Approved by the buyer, available for the supplier to sell.
+    Approved,
+    /// Turned into an invoice by the supplier.
+    Sold,
+    /// Withdrawn by the buyer before it was sold.
+    Cancelled,
+}
+
+/// Terms of a payable a buyer registers for its supplier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayableTerms {
+    pub supplier: Address,
+    pub amount: i128,
+    pub currency: Address,
+    pub due_date: u64,
+    pub description: String,
+    pub category: InvoiceCategory,
+}
+
+/// A payable the buyer has approved for payment at `due_date`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovedPayable {
+    pub payable_id: u64,
+    pub buyer: Address,
+    pub supplier: Address,
+    pub amount: i128,
+    pub currency: Address,
+    pub due_date: u64,
+    pub description: String,
+    pub category: InvoiceCategory,
+    pub status: PayableStatus,
+    /// Invoice created when the supplier sold the payable.
+    pub invoice_id: Option<BytesN<32>>,
+    pub created_at: u64,
+}
+
+pub struct ReverseFactoringStorage;
+
+impl ReverseFactoringStorage {
+    pub fn get_payable(env: &Env, payable_id: u64) -> Option<ApprovedPayable> {
+        env.storage()
+            .persistent()
+            .get(&ReverseFactoringKey::Payable(payable_id))
+    }
+
+    pub fn get_payables_by_buyer(env: &Env, buyer: &Address) -> Vec<ApprovedPayable> {
+        Self::load_all(env, &ReverseFactoringKey::PayablesByBuyer(buyer.clone()))
+    }
+
+    pub fn get_payables_by_supplier(env: &Env, supplier: &Address) -> Vec<ApprovedPayable> {
+        Self::load_all(
+            env,
+            &ReverseFactoringKey::PayablesBySupplier(supplier.clone()),
+        )
+    }
+
+    /// Payable an invoice was created from, if it is reverse-factored.
+    pub fn get_invoice_payable(env: &Env, invoice_id: &BytesN<32>) -> Option<ApprovedPayable> {
+        let payable_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&ReverseFactoringKey::InvoicePayable(invoice_id.clone()))?;
+        Self::get_payable(env, payable_id)
+    }
+
+    /// Party that owes payment on an invoice: the buyer of a reverse-factored invoice,
+    /// otherwise the issuing business.
+    pub fn obligor(env: &Env, invoice: &Invoice) -> Address {
+        Self::get_invoice_payable(env, &invoice.id)
+            .map(|payable| payable.buyer)
+            .unwrap_or_else(|| invoice.business.clone())
+    }
+
+    fn load_all(env: &Env, index_key: &ReverseFactoringKey) -> Vec<ApprovedPayable> {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(index_key)
+            .unwrap_or_else(|| Vec::new(env));
+        let mut payables = Vec::new(env);
+        for payable_id in ids.iter() {
+            if let Some(payable) = Self::get_payable(env, payable_id) {
+                payables.push_back(payable);
+            }
+        }
+        payables
+    }
+
+    fn append_index(env: &Env, index_key: &ReverseFactoringKey, payable_id: u64) {
+        let mut ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(index_key)
+            .unwrap_or_else(|| Vec::new(env));
+        ids.push_back(payable_id);
+        env.storage().persistent().set(index_key, &ids);
+    }
+
+    fn store_payable(env: &Env, payable: &ApprovedPayable) {
+        env.storage()
+            .persistent()
+            .set(&ReverseFactoringKey::Payable(payable.payable_id), payable);
+    }
+
+    fn next_payable_id(env: &Env) -> u64 {
+        let next: u64 = env
+            .storage()
+            .persistent()
+            .get(&ReverseFactoringKey::PayableCounter)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage()
+            .persistent()
+            .set(&ReverseFactoringKey::PayableCounter, &next);
+        next
+    }
+}
+
+pub struct ReverseFactoring;
+
+impl ReverseFactoring {
+    /// Register a payable owed to a supplier (verified buyer only).
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` if the buyer is not a verified business
+    /// * `OperationNotAllowed` if the buyer names itself as supplier
+    /// * `InvalidAmount`, `InvoiceDueDateInvalid`, `InvalidDescription` for invalid terms
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    pub fn register(
+        env: &Env,
+        buyer: &Address,
+        terms: PayableTerms,
+    ) -> Result<ApprovedPayable, QuickLendXError> {
+        buyer.require_auth();
+        Self::require_verified_business(env, buyer)?;
+        let PayableTerms {
+            supplier,
+            amount,
+            currency,
+            due_date,
+            description,
+            category,
+        } = terms;
+        if *buyer == supplier {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if due_date <= env.ledger().timestamp() {
+            return Err(QuickLendXError::InvoiceDueDateInvalid);
+        }
+        ProtocolLimitsContract::validate_invoice(env.clone(), amount, due_date)?;
+        if description.is_empty() {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        CurrencyWhitelist::require_allowed_currency(env, &currency)?;
+
+        let payable = ApprovedPayable {
+            payable_id: ReverseFactoringStorage::next_payable_id(env),
+            buyer: buyer.clone(),
+            supplier: supplier.clone(),
+            amount,
+            currency,
+            due_date,
+            description,
+            category,
+            status: PayableStatus::Approved,
+            invoice_id: None,
+            created_at: env.ledger().timestamp(),
+        };
+        ReverseFactoringStorage::store_payable(env, &payable);
+        ReverseFactoringStorage::append_index(
+            env,
+            &ReverseFactoringKey::PayablesByBuyer(buyer.clone()),
+            payable.payable_id,
+        );
+        ReverseFactoringStorage::append_index(
+            env,
+            &ReverseFactoringKey::PayablesBySupplier(supplier.clone()),
+            payable.payable_id,
+        );
+
+        emit_payable_registered(env, &payable);
+        Ok(payable)
+    }
+
+    /// Withdraw a payable the supplier has not sold yet (buyer only).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the payable does not exist
+    /// * `Unauthorized` if `buyer` did not register it
+    /// * `InvalidStatus` if it was already sold or cancelled
+    pub fn cancel(env: &Env, buyer: &Address, payable_id: u64) -> Result<(), QuickLendXError> {
+        buyer.require_auth();
+        let mut payable = ReverseFactoringStorage::get_payable(env, payable_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if payable.buyer != *buyer {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if payable.status != PayableStatus::Approved {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        payable.status = PayableStatus::Cancelled;
+        ReverseFactoringStorage::store_payable(env, &payable);
+        emit_payable_cancelled(env, &payable);
+        Ok(())
+    }
+
+    /// Sell an approved payable early (supplier only): creates a Pending invoice of the
+    /// supplier on the payable's terms, to be verified and funded like any other.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the payable does not exist
+    /// * `Unauthorized` if `supplier` is not the payable's supplier
+    /// * `InvalidStatus` if the payable is not Approved
+    /// * `InvoiceDueDateInvalid` if the payable is already due
+    /// * `BusinessNotVerified` if the supplier is not a verified business
+    /// * `OperationNotAllowed` if the supplier has an unpaid recourse obligation
+    pub fn sell(
+        env: &Env,
+        supplier: &Address,
+        payable_id: u64,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        supplier.require_auth();
+        let mut payable = ReverseFactoringStorage::get_payable(env, payable_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if payable.supplier != *supplier {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if payable.status != PayableStatus::Approved {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if payable.due_date <= env.ledger().timestamp() {
+            return Err(QuickLendXError::InvoiceDueDateInvalid);
+        }
+        Self::require_verified_business(env, supplier)?;
+        Recourse::require_no_outstanding(env, supplier)?;
+
+        let invoice = Invoice::new(
+            env,
+            supplier.clone(),
+            payable.amount,
+            payable.currency.clone(),
+            payable.due_date,
+            payable.description.clone(),
+            payable.category.clone(),
+            Vec::new(env),
+        )?;
+        InvoiceStorage::store_invoice(env, &invoice);
+        env.storage().persistent().set(
+            &ReverseFactoringKey::InvoicePayable(invoice.id.clone()),
+            &payable_id,
+        );
+        payable.status = PayableStatus::Sold;
+        payable.invoice_id = Some(invoice.id.clone());
+        ReverseFactoringStorage::store_payable(env, &payable);
+
+        emit_invoice_uploaded(env, &invoice);
+        audit::log_invoice_uploaded(env, invoice.id.clone(), supplier.clone(), invoice.amount);
+        let _ = NotificationSystem::notify_invoice_created(env, &invoice);
+        emit_payable_sold(env, &payable, &invoice.id);
+        Ok(invoice.id)
+    }
+
+    fn require_verified_business(env: &Env, business: &Address) -> Result<(), QuickLendXError> {
+        match get_business_verification_status(env, business) {
+            Some(verification) if verification.status == BusinessVerificationStatus::Verified => {
+                Ok(())
+            }
+            _ => Err(QuickLendXError::BusinessNotVerified),
+        }
+    }
+}
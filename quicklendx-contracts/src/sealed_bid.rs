@@ -0,0 +1,390 @@
+//! Sealed-bid (commit–reveal) auctions for invoices.
+//!
+//! A business may put a Verified invoice into sealed mode. During the commit window
+//! investors submit only a hash of their bid; during the reveal window they disclose
+//! the bid, which is then placed through the regular bid path. Plain `place_bid` is
+//! rejected until the reveal window closes, bids cannot be accepted before then, and
+//! commitments left unrevealed afterwards expire. Once an auction has closed, the
+//! invoice takes plain bids again or can be put into a new auction.
+
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_bid_committed, emit_bid_revealed, emit_commitment_expired, emit_sealed_auction_opened,
+};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::verification::{get_investor_verification, BusinessVerificationStatus};
+
+/// Shortest allowed commit or reveal window (1 hour).
+pub const MIN_SEALED_WINDOW_SECS: u64 = 60 * 60;
+/// Longest allowed commit or reveal window (30 days).
+pub const MAX_SEALED_WINDOW_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum SealedBidKey {
+    Auction(BytesN<32>),
+    Commitment(BytesN<32>, Address),
+    Committers(BytesN<32>),
+}
+
+/// Sealed-bid schedule for an invoice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedAuction {
+    pub invoice_id: BytesN<32>,
+    pub opened_at: u64,
+    pub commit_deadline: u64,
+    pub reveal_deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CommitmentStatus {
+    Committed,
+    Revealed,
+    Expired,
+}
+
+/// A hidden bid committed by an investor.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidCommitment {
+    pub invoice_id: BytesN<32>,
+    pub investor: Address,
+    pub commitment: BytesN<32>,
+    pub committed_at: u64,
+    pub status: CommitmentStatus,
+    /// Bid created on reveal.
+    pub bid_id: Option<BytesN<32>>,
+}
+
+pub struct SealedBidStorage;
+
+impl SealedBidStorage {
+    pub fn get_auction(env: &Env, invoice_id: &BytesN<32>) -> Option<SealedAuction> {
+        env.storage()
+            .persistent()
+            .get(&SealedBidKey::Auction(invoice_id.clone()))
+    }
+
+    fn store_auction(env: &Env, auction: &SealedAuction) {
+        env.storage()
+            .persistent()
+            .set(&SealedBidKey::Auction(auction.invoice_id.clone()), auction);
+    }
+
+    pub fn get_commitment(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        investor: &Address,
+    ) -> Option<BidCommitment> {
+        env.storage().persistent().get(&SealedBidKey::Commitment(
+            invoice_id.clone(),
+            investor.clone(),
+        ))
+    }
+
+    fn store_commitment(env: &Env, commitment: &BidCommitment) {
+        env.storage().persistent().set(
+            &SealedBidKey::Commitment(commitment.invoice_id.clone(), commitment.investor.clone()),
+            commitment,
+        );
+    }
+
+    fn get_committers(env: &Env, invoice_id: &BytesN<32>) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&SealedBidKey::Committers(invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn add_committer(env: &Env, invoice_id: &BytesN<32>, investor: &Address) {
+        let mut committers = Self::get_committers(env, invoice_id);
+        if !committers.contains(investor) {
+            committers.push_back(investor.clone());
+            env.storage()
+                .persistent()
+                .set(&SealedBidKey::Committers(invoice_id.clone()), &committers);
+        }
+    }
+
+    /// Drop the commitments of an earlier auction before a new one opens.
+    fn clear_commitments(env: &Env, invoice_id: &BytesN<32>) {
+        for investor in Self::get_committers(env, invoice_id).iter() {
+            env.storage().persistent().remove(&SealedBidKey::Commitment(
+                invoice_id.clone(),
+                investor.clone(),
+            ));
+        }
+        env.storage()
+            .persistent()
+            .remove(&SealedBidKey::Committers(invoice_id.clone()));
+    }
+
+    /// All commitments recorded for an invoice, in commit order.
+    pub fn get_commitments(env: &Env, invoice_id: &BytesN<32>) -> Vec<BidCommitment> {
+        let mut commitments = Vec::new(env);
+        for investor in Self::get_committers(env, invoice_id).iter() {
+            if let Some(commitment) = Self::get_commitment(env, invoice_id, &investor) {
+                commitments.push_back(commitment);
+            }
+        }
+        commitments
+    }
+}
+
+pub struct SealedBidding;
+
+impl SealedBidding {
+    /// Hash an investor must commit to for a given bid.
+    ///
+    /// `sha256(xdr(investor, invoice_id, bid_amount, expected_return, salt))`. Binding the
+    /// investor and invoice prevents a commitment from being copied by someone else.
+    pub fn compute_commitment(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        salt: &BytesN<32>,
+    ) -> BytesN<32> {
+        let preimage = (
+            investor.clone(),
+            invoice_id.clone(),
+            bid_amount,
+            expected_return,
+            salt.clone(),
+        )
+            .to_xdr(env);
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Put an invoice into sealed-bid mode (business only).
+    ///
+    /// # Arguments
+    /// * `commit_window` - Seconds from now during which commitments are accepted
+    /// * `reveal_window` - Seconds after the commit deadline during which bids are revealed
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Verified
+    /// * `OperationNotAllowed` if an auction is still running or open bids exist
+    /// * `InvalidTimestamp` if a window is outside the allowed bounds
+    pub fn open_auction(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        commit_window: u64,
+        reveal_window: u64,
+    ) -> Result<SealedAuction, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if Self::is_bidding_open(env, invoice_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        // Bids placed in the clear would defeat the purpose of sealing.
+        if !BidStorage::get_bids_by_status(env, invoice_id, BidStatus::Placed).is_empty() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        for window in [commit_window, reveal_window] {
+            if !(MIN_SEALED_WINDOW_SECS..=MAX_SEALED_WINDOW_SECS).contains(&window) {
+                return Err(QuickLendXError::InvalidTimestamp);
+            }
+        }
+
+        let now = env.ledger().timestamp();
+        let commit_deadline = now
+            .checked_add(commit_window)
+            .ok_or(QuickLendXError::InvalidTimestamp)?;
+        let reveal_deadline = commit_deadline
+            .checked_add(reveal_window)
+            .ok_or(QuickLendXError::InvalidTimestamp)?;
+
+        // Commitments of a closed auction were revealed into bids or expired
+        SealedBidStorage::clear_commitments(env, invoice_id);
+        let auction = SealedAuction {
+            invoice_id: invoice_id.clone(),
+            opened_at: now,
+            commit_deadline,
+            reveal_deadline,
+        };
+        SealedBidStorage::store_auction(env, &auction);
+
+        emit_sealed_auction_opened(env, invoice_id, commit_deadline, reveal_deadline);
+        Ok(auction)
+    }
+
+    /// Commit (or re-commit) a hidden bid during the commit window.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice is not in sealed mode or the commit window closed
+    /// * `InvalidStatus` if the invoice is not Verified or the commitment was already revealed
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the investor is not verified
+    pub fn commit(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+        commitment: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        investor.require_auth();
+
+        let auction = SealedBidStorage::get_auction(env, invoice_id)
+            .ok_or(QuickLendXError::OperationNotAllowed)?;
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        if now > auction.commit_deadline {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let verification =
+            get_investor_verification(env, investor).ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+
+        if let Some(existing) = SealedBidStorage::get_commitment(env, invoice_id, investor) {
+            if existing.status != CommitmentStatus::Committed {
+                return Err(QuickLendXError::InvalidStatus);
+            }
+        }
+
+        let record = BidCommitment {
+            invoice_id: invoice_id.clone(),
+            investor: investor.clone(),
+            commitment: commitment.clone(),
+            committed_at: now,
+            status: CommitmentStatus::Committed,
+            bid_id: None,
+        };
+        SealedBidStorage::store_commitment(env, &record);
+        SealedBidStorage::add_committer(env, invoice_id, investor);
+
+        emit_bid_committed(env, invoice_id, investor, commitment, now);
+        Ok(())
+    }
+
+    /// Check a reveal against the stored commitment.
+    ///
+    /// Caller is responsible for investor authorization and for placing the bid.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if not in sealed mode or outside the reveal window
+    /// * `StorageKeyNotFound` if the investor has no commitment
+    /// * `InvalidStatus` if the commitment is no longer open
+    /// * `Unauthorized` if the revealed values do not match the commitment
+    pub fn verify_reveal(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        salt: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let auction = SealedBidStorage::get_auction(env, invoice_id)
+            .ok_or(QuickLendXError::OperationNotAllowed)?;
+        let now = env.ledger().timestamp();
+        if now <= auction.commit_deadline || now > auction.reveal_deadline {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let commitment = SealedBidStorage::get_commitment(env, invoice_id, investor)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if commitment.status != CommitmentStatus::Committed {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let expected =
+            Self::compute_commitment(env, investor, invoice_id, bid_amount, expected_return, salt);
+        if expected != commitment.commitment {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Record that a commitment was revealed into `bid_id`.
+    pub fn mark_revealed(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+        bid_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let mut commitment = SealedBidStorage::get_commitment(env, invoice_id, investor)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        commitment.status = CommitmentStatus::Revealed;
+        commitment.bid_id = Some(bid_id.clone());
+        SealedBidStorage::store_commitment(env, &commitment);
+
+        emit_bid_revealed(env, invoice_id, investor, bid_id);
+        Ok(())
+    }
+
+    /// Expire commitments that were not revealed before the reveal deadline.
+    ///
+    /// Returns the number of commitments expired by this call.
+    pub fn expire_unrevealed(env: &Env, invoice_id: &BytesN<32>) -> u32 {
+        let auction = match SealedBidStorage::get_auction(env, invoice_id) {
+            Some(auction) => auction,
+            None => return 0,
+        };
+        if env.ledger().timestamp() <= auction.reveal_deadline {
+            return 0;
+        }
+
+        let mut expired = 0u32;
+        for mut commitment in SealedBidStorage::get_commitments(env, invoice_id).iter() {
+            if commitment.status == CommitmentStatus::Committed {
+                commitment.status = CommitmentStatus::Expired;
+                SealedBidStorage::store_commitment(env, &commitment);
+                emit_commitment_expired(env, invoice_id, &commitment.investor);
+                expired = expired.saturating_add(1);
+            }
+        }
+        expired
+    }
+
+    /// True while the invoice is in sealed mode and its commit window is open or
+    /// its reveal window has not closed yet.
+    pub fn is_bidding_open(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        match SealedBidStorage::get_auction(env, invoice_id) {
+            Some(auction) => env.ledger().timestamp() <= auction.reveal_deadline,
+            None => false,
+        }
+    }
+
+    /// Reject plain bids while a sealed auction on the invoice is running.
+    pub fn require_open_bidding(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        if Self::is_bidding_open(env, invoice_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Reject bid acceptance until the reveal window has closed, then expire leftovers.
+    pub fn require_bidding_closed(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        if Self::is_bidding_open(env, invoice_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Self::expire_unrevealed(env, invoice_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! Secondary market for funded investment positions.
+//!
+//! An investor holding an Active investment can list it at an asking price. Another
+//! verified investor can buy it before the invoice settles; the price moves from buyer
+//! to seller and ownership of the position moves from seller to buyer in the same
+//! transaction. Ownership covers `Investment.investor` (the settlement payout target),
+//! the investor index, `Invoice.investor`, any escrow still held for the position and
+//! the position's receipt units. Only a seller holding the whole receipt can list.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_position_listed, emit_position_sold, emit_position_unlisted};
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::payments::{transfer_funds, EscrowStatus, EscrowStorage};
+use crate::receipt::Receipts;
+use crate::verification::{
+    validate_investor_investment, BusinessVerificationStatus, InvestorVerificationStorage,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum SecondaryMarketKey {
+    Listing(BytesN<32>),
+    Listings,
+}
+
+/// An investment position offered for sale.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionListing {
+    pub investment_id: BytesN<32>,
+    pub invoice_id: BytesN<32>,
+    pub seller: Address,
+    pub asking_price: i128,
+    pub listed_at: u64,
+}
+
+pub struct SecondaryMarketStorage;
+
+impl SecondaryMarketStorage {
+    pub fn get_listing(env: &Env, investment_id: &BytesN<32>) -> Option<PositionListing> {
+        env.storage()
+            .persistent()
+            .get(&SecondaryMarketKey::Listing(investment_id.clone()))
+    }
+
+    /// All open listings, oldest first.
+    pub fn get_listings(env: &Env) -> Vec<PositionListing> {
+        let mut listings = Vec::new(env);
+        for investment_id in Self::get_listed_ids(env).iter() {
+            if let Some(listing) = Self::get_listing(env, &investment_id) {
+                listings.push_back(listing);
+            }
+        }
+        listings
+    }
+
+    fn get_listed_ids(env: &Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&SecondaryMarketKey::Listings)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_listing(env: &Env, listing: &PositionListing) {
+        env.storage().persistent().set(
+            &SecondaryMarketKey::Listing(listing.investment_id.clone()),
+            listing,
+        );
+        let mut ids = Self::get_listed_ids(env);
+        ids.push_back(listing.investment_id.clone());
+        env.storage()
+            .persistent()
+            .set(&SecondaryMarketKey::Listings, &ids);
+    }
+
+    fn remove_listing(env: &Env, investment_id: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .remove(&SecondaryMarketKey::Listing(investment_id.clone()));
+        let mut remaining = Vec::new(env);
+        for id in Self::get_listed_ids(env).iter() {
+            if id != *investment_id {
+                remaining.push_back(id);
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&SecondaryMarketKey::Listings, &remaining);
+    }
+}
+
+pub struct SecondaryMarket;
+
+impl SecondaryMarket {
+    /// List an Active investment for sale (owning investor only).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment does not exist
+    /// * `NotInvestor` if `seller` does not hold the investment and its whole receipt
+    /// * `InvalidStatus` if the investment is not Active or the invoice is not Funded
+    /// * `InvalidAmount` if `asking_price` is not positive
+    /// * `OperationNotAllowed` if the position is already listed
+    pub fn list(
+        env: &Env,
+        seller: &Address,
+        investment_id: &BytesN<32>,
+        asking_price: i128,
+    ) -> Result<PositionListing, QuickLendXError> {
+        seller.require_auth();
+        let investment = Self::load_tradable(env, investment_id)?;
+        if investment.investor != *seller || !Receipts::holds_all(env, &investment, seller) {
+            return Err(QuickLendXError::NotInvestor);
+        }
+        if asking_price <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if SecondaryMarketStorage::get_listing(env, investment_id).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let listing = PositionListing {
+            investment_id: investment_id.clone(),
+            invoice_id: investment.invoice_id.clone(),
+            seller: seller.clone(),
+            asking_price,
+            listed_at: env.ledger().timestamp(),
+        };
+        SecondaryMarketStorage::store_listing(env, &listing);
+        emit_position_listed(env, &listing);
+        Ok(listing)
+    }
+
+    /// Withdraw a listing (seller only).
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the position is not listed
+    /// * `NotInvestor` if `seller` did not list it
+    pub fn cancel(
+        env: &Env,
+        seller: &Address,
+        investment_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        seller.require_auth();
+        let listing = SecondaryMarketStorage::get_listing(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if listing.seller != *seller {
+            return Err(QuickLendXError::NotInvestor);
+        }
+        SecondaryMarketStorage::remove_listing(env, investment_id);
+        emit_position_unlisted(env, &listing);
+        Ok(())
+    }
+
+    /// Buy a listed position at its asking price.
+    ///
+    /// The buyer must be a verified investor whose limits allow a position of the
+    /// investment's size, and must have approved the contract to move the asking price.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the position is not listed
+    /// * `InvalidStatus` if the position is no longer tradable
+    /// * `OperationNotAllowed` if the buyer is the seller
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the buyer is not a verified investor
+    /// * Any error from `validate_investor_investment` or the token transfer
+    pub fn buy(
+        env: &Env,
+        buyer: &Address,
+        investment_id: &BytesN<32>,
+    ) -> Result<Investment, QuickLendXError> {
+        buyer.require_auth();
+        let listing = SecondaryMarketStorage::get_listing(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let mut investment = Self::load_tradable(env, investment_id)?;
+        let seller = listing.seller.clone();
+        // Part of the receipt may have been transferred since listing
+        if investment.investor != seller || !Receipts::holds_all(env, &investment, &seller) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if *buyer == seller {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let verification = InvestorVerificationStorage::get(env, buyer)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        validate_investor_investment(env, buyer, investment.amount)?;
+
+        let mut invoice = InvoiceStorage::get_invoice(env, &investment.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        transfer_funds(env, &invoice.currency, buyer, &seller, listing.asking_price)?;
+
+        investment.investor = buyer.clone();
+        InvestmentStorage::update_investment(env, &investment);
+        InvestmentStorage::remove_from_investor_index(env, &seller, investment_id);
+        InvestmentStorage::add_to_investor_index(env, buyer, investment_id);
+        Receipts::move_all(env, investment_id, &seller, buyer)?;
+
+        if invoice.investor.as_ref() == Some(&seller) {
+            invoice.investor = Some(buyer.clone());
+            InvoiceStorage::update_invoice(env, &invoice);
+        }
+        // A refund of escrow still held for this position goes to the new holder
+        for mut escrow in EscrowStorage::get_escrows_by_invoice(env, &investment.invoice_id).iter()
+        {
+            if escrow.status == EscrowStatus::Held && escrow.investor == seller {
+                escrow.investor = buyer.clone();
+                EscrowStorage::update_escrow(env, &escrow);
+            }
+        }
+
+        SecondaryMarketStorage::remove_listing(env, investment_id);
+        emit_position_sold(env, &listing, buyer);
+        Ok(investment)
+    }
+
+    /// An investment can change hands while it is Active on a Funded invoice.
+    fn load_tradable(env: &Env, investment_id: &BytesN<32>) -> Result<Investment, QuickLendXError> {
+        let investment = InvestmentStorage::get_investment(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if investment.status != InvestmentStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let invoice = InvoiceStorage::get_invoice(env, &investment.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        Ok(investment)
+    }
+}
@@ -2,17 +2,25 @@
 //! and durable per-payment storage records.
 
 use crate::audit::{log_payment_processed, log_settlement_completed};
+use crate::credit_line::CreditLines;
+use crate::distribution::ProgressiveDistribution;
 use crate::errors::QuickLendXError;
-use crate::events::{emit_invoice_settled, emit_partial_payment};
-use crate::investment::{InvestmentStatus, InvestmentStorage};
+use crate::events::{
+    emit_early_payment_discount, emit_invoice_settled, emit_late_interest_paid,
+    emit_partial_payment, emit_payer_approval_set,
+};
+use crate::installment::{Installment, InstallmentPlans};
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
 use crate::invoice::{
     Invoice, InvoiceStatus, InvoiceStorage, PaymentRecord as InvoicePaymentRecord,
 };
+use crate::late_payment::LatePaymentInterest;
 use crate::notifications::NotificationSystem;
-use crate::{defaults::DEFAULT_GRACE_PERIOD, events::emit_invoice_settled_final};
-use crate::events::TOPIC_INVOICE_SETTLED_FINAL;
 use crate::payments::transfer_funds;
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Vec};
+use crate::pricing::TimeBasedPricing;
+use crate::receipt::Receipts;
+use crate::reverse_factoring::ReverseFactoringStorage;
+use soroban_sdk::{contracttype, symbol_short, token, Address, BytesN, Env, Map, String, Vec};
 
 const MAX_INLINE_PAYMENT_HISTORY: u32 = 32;
 
@@ -22,6 +30,22 @@ enum SettlementDataKey {
     PaymentCount(BytesN<32>),
     Payment(BytesN<32>, u32),
     PaymentNonce(BytesN<32>, Address, String),
+    ApprovedPayer(BytesN<32>, Address),
+    ThirdPartyPaid(BytesN<32>),
+}
+
+/// Party a payment was recorded from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PaymentParty {
+    /// The issuing business, as the invoice's obligor
+    Business,
+    /// The buyer of a reverse-factored invoice, as its obligor
+    Buyer,
+    /// The invoice's debtor, paying the factor directly
+    Debtor,
+    /// A payer the business approved for the invoice
+    Delegate,
 }
 
 /// Durable payment record stored per invoice/payment-index.
@@ -29,6 +53,7 @@ enum SettlementDataKey {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SettlementPaymentRecord {
     pub payer: Address,
+    pub party: PaymentParty,
     pub amount: i128,
     pub timestamp: u64,
     pub nonce: String,
@@ -44,11 +69,14 @@ pub struct Progress {
     pub progress_percent: u32,
     pub payment_count: u32,
     pub status: InvoiceStatus,
+    /// Installment schedule, empty when the invoice has none
+    pub installments: Vec<Installment>,
 }
 
 /// Record a partial payment. If total reaches invoice total, settlement is finalized.
 ///
-/// Business authorization is required and payer is recorded as the business address.
+/// Authorization of the invoice's obligor is required and it is recorded as the payer:
+/// the business, or the buyer of a reverse-factored invoice.
 pub fn process_partial_payment(
     env: &Env,
     invoice_id: &BytesN<32>,
@@ -57,14 +85,59 @@ pub fn process_partial_payment(
 ) -> Result<(), QuickLendXError> {
     let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
-    let payer = invoice.business.clone();
+    let payer = ReverseFactoringStorage::obligor(env, &invoice);
+    process_payment_from(env, invoice_id, &payer, payment_amount, transaction_id)
+}
 
-    let progress = record_payment(
+/// Record a partial payment from any accepted payer: the obligor, the invoice's debtor or
+/// a payer the business approved. If total reaches invoice total, settlement is finalized.
+///
+/// Third-party payments are transferred into the contract when they are recorded and used
+/// when the invoice settles, ahead of the obligor, who covers the rest.
+pub fn process_payment_from(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    payment_amount: i128,
+    transaction_id: String,
+) -> Result<(), QuickLendXError> {
+    apply_and_settle(env, invoice_id, payer, payment_amount, transaction_id, true)
+}
+
+/// `process_payment_from` for callers that required the payer's authorization once for
+/// several payments.
+pub fn process_authorized_payment(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    payment_amount: i128,
+    transaction_id: String,
+) -> Result<(), QuickLendXError> {
+    apply_and_settle(
         env,
         invoice_id,
-        &payer,
+        payer,
+        payment_amount,
+        transaction_id,
+        false,
+    )
+}
+
+fn apply_and_settle(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    payment_amount: i128,
+    transaction_id: String,
+    require_payer_auth: bool,
+) -> Result<(), QuickLendXError> {
+    let progress = apply_payment(
+        env,
+        invoice_id,
+        payer,
         payment_amount,
         transaction_id.clone(),
+        require_payer_auth,
     )?;
 
     // Backward-compatible event used across existing tests/consumers.
@@ -84,13 +157,60 @@ pub fn process_partial_payment(
     Ok(())
 }
 
+/// Check, without writing anything, that `payer` can pay `amount` towards an invoice now:
+/// what `record_payment` validates, plus the balances and allowances of whoever the
+/// payment pulls funds from, either right away through progressive distribution or at
+/// the settlement it completes. Returns the amount that would be applied.
+///
+/// # Errors
+/// * `InvalidAmount` if `amount` is not positive
+/// * `InvoiceNotFound` if the invoice does not exist
+/// * `InvalidStatus` if the invoice is not Funded or nothing is left to pay
+/// * `NotBusinessOwner` if `payer` may not pay the invoice
+/// * `OperationNotAllowed` if the payer already used `payment_nonce` on the invoice, or an
+///   allowance does not cover the funds pulled
+/// * `InsufficientFunds` if a balance does not cover the funds pulled
+pub fn check_payment(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    amount: i128,
+    payment_nonce: &String,
+) -> Result<i128, QuickLendXError> {
+    let (invoice, party, remaining_due) =
+        validate_payment(env, invoice_id, payer, amount, payment_nonce)?;
+    let applied_amount = amount.min(remaining_due);
+    let third_party = matches!(party, PaymentParty::Debtor | PaymentParty::Delegate);
+    if third_party || ProgressiveDistribution::is_enabled(env, invoice_id) {
+        ensure_funds_available(env, &invoice.currency, payer, applied_amount)?;
+    }
+    if applied_amount == remaining_due {
+        let mut obligor_amount = obligor_settlement_amount(env, &invoice)?;
+        if third_party {
+            obligor_amount = obligor_amount.saturating_sub(applied_amount);
+        }
+        let obligor = ReverseFactoringStorage::obligor(env, &invoice);
+        ensure_funds_available(env, &invoice.currency, &obligor, obligor_amount)?;
+    }
+    Ok(applied_amount)
+}
+
 /// Record a payment attempt with capping, replay protection, and durable storage.
 ///
 /// - Rejects amount <= 0
 /// - Rejects missing invoices
 /// - Rejects payments to non-payable invoice states
-/// - Caps applied amount so `total_paid` never exceeds `total_due`
+/// - Rejects payers other than the obligor, the debtor and approved payers
+/// - Accrues late-payment interest on the unpaid amount up to the payment
+/// - Caps applied amount so `total_paid` never exceeds `total_due`, which includes
+///   late-payment interest
 /// - Enforces nonce uniqueness per `(invoice, payer, nonce)` if nonce is non-empty
+/// - Marks the installments the payments now cover as paid on time or late
+/// - Pays the amount out to investors right away on invoices with progressive distribution,
+///   unless it completes the invoice, restoring the credit of a line the invoice was
+///   drawn on
+/// - Otherwise transfers a debtor's or approved payer's amount into the contract, to be
+///   used at settlement
 pub fn record_payment(
     env: &Env,
     invoice_id: &BytesN<32>,
@@ -98,35 +218,63 @@ pub fn record_payment(
     amount: i128,
     payment_nonce: String,
 ) -> Result<Progress, QuickLendXError> {
+    apply_payment(env, invoice_id, payer, amount, payment_nonce, true)
+}
+
+/// Checks `check_payment` and `apply_payment` share: returns the invoice, the party `payer`
+/// pays as and what is left due.
+fn validate_payment(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    amount: i128,
+    payment_nonce: &String,
+) -> Result<(Invoice, PaymentParty, i128), QuickLendXError> {
     if amount <= 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
-
-    let mut invoice =
+    let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
     ensure_payable_status(&invoice)?;
-
-    if *payer != invoice.business {
-        return Err(QuickLendXError::NotBusinessOwner);
-    }
-    payer.require_auth();
-
-    if payment_nonce.len() > 0 {
+    let party = payment_party(env, &invoice, payer)?;
+    if !payment_nonce.is_empty() {
         let nonce_key = SettlementDataKey::PaymentNonce(
             invoice_id.clone(),
             payer.clone(),
             payment_nonce.clone(),
         );
-        let seen: bool = env.storage().persistent().get(&nonce_key).unwrap_or(false);
-        if seen {
+        if env.storage().persistent().get(&nonce_key).unwrap_or(false) {
             return Err(QuickLendXError::OperationNotAllowed);
         }
     }
-
-    let remaining_due = compute_remaining_due(&invoice)?;
+    let remaining_due = compute_remaining_due(env, &invoice)?;
     if remaining_due <= 0 {
         return Err(QuickLendXError::InvalidStatus);
     }
+    Ok((invoice, party, remaining_due))
+}
+
+/// `record_payment`, minus the payer's authorization for payments pulled from an
+/// allowance the payer granted up front.
+fn apply_payment(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    amount: i128,
+    payment_nonce: String,
+    require_payer_auth: bool,
+) -> Result<Progress, QuickLendXError> {
+    let (mut invoice, party, remaining_due) =
+        validate_payment(env, invoice_id, payer, amount, &payment_nonce)?;
+    if require_payer_auth {
+        payer.require_auth();
+    }
+
+    let late_interest = LatePaymentInterest::checkpoint(env, &invoice);
+    let total_due = invoice
+        .amount
+        .checked_add(late_interest)
+        .ok_or(QuickLendXError::InvalidAmount)?;
 
     let applied_amount = if amount > remaining_due {
         remaining_due
@@ -143,7 +291,7 @@ pub fn record_payment(
         .checked_add(applied_amount)
         .ok_or(QuickLendXError::InvalidAmount)?;
 
-    if new_total_paid > invoice.amount {
+    if new_total_paid > total_due {
         return Err(QuickLendXError::InvalidAmount);
     }
 
@@ -151,6 +299,7 @@ pub fn record_payment(
     let timestamp = env.ledger().timestamp();
     let payment_record = SettlementPaymentRecord {
         payer: payer.clone(),
+        party: party.clone(),
         amount: applied_amount,
         timestamp,
         nonce: payment_nonce.clone(),
@@ -176,7 +325,23 @@ pub fn record_payment(
         );
     }
 
+    if ProgressiveDistribution::is_enabled(env, invoice_id) && new_total_paid < total_due {
+        // Paid out right away; the payment completing the invoice is left to settlement
+        ProgressiveDistribution::distribute(env, &invoice, payer, applied_amount)?;
+    } else if matches!(party, PaymentParty::Debtor | PaymentParty::Delegate) {
+        let contract = env.current_contract_address();
+        transfer_funds(env, &invoice.currency, payer, &contract, applied_amount)?;
+        let mut paid = third_party_paid(env, invoice_id);
+        let previous = paid.get(payer.clone()).unwrap_or(0);
+        paid.set(payer.clone(), previous.saturating_add(applied_amount));
+        env.storage().persistent().set(
+            &SettlementDataKey::ThirdPartyPaid(invoice_id.clone()),
+            &paid,
+        );
+    }
+
     invoice.total_paid = new_total_paid;
+    InstallmentPlans::record_payment(env, invoice_id, new_total_paid);
     update_inline_payment_history(
         &mut invoice,
         applied_amount,
@@ -221,10 +386,10 @@ pub fn settle_invoice(
     let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
     ensure_payable_status(&invoice)?;
-    let payer = invoice.business.clone();
-    payer.require_auth();
+    // Payer authorization is enforced by `record_payment`.
+    let payer = ReverseFactoringStorage::obligor(env, &invoice);
 
-    let remaining_due = compute_remaining_due(&invoice)?;
+    let remaining_due = compute_remaining_due(env, &invoice)?;
     let applied_preview = if payment_amount > remaining_due {
         remaining_due
     } else {
@@ -240,10 +405,9 @@ pub fn settle_invoice(
         .checked_add(applied_preview)
         .ok_or(QuickLendXError::InvalidAmount)?;
 
-    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id)
-        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    let funded_total = active_principal(env, invoice_id)?;
 
-    if projected_total < invoice.amount || projected_total < investment.amount {
+    if projected_total < total_due(env, &invoice)? || projected_total < funded_total {
         return Err(QuickLendXError::PaymentTooLow);
     }
 
@@ -252,16 +416,86 @@ pub fn settle_invoice(
     settle_invoice_internal(env, invoice_id)
 }
 
-/// Returns aggregate payment progress for an invoice.
+/// Settle an invoice by paying everything still due from its obligor's token allowance.
+///
+/// The obligor's signature is not required: callers must have checked that the obligor
+/// opted into having its allowance debited.
+///
+/// # Errors
+/// * `InvoiceNotFound` if the invoice does not exist
+/// * `InvalidStatus` if the invoice is not Funded or nothing is left to pay
+/// * `InsufficientFunds` or `OperationNotAllowed` if the obligor's balance or allowance
+///   does not cover what settlement pulls from it
+pub fn settle_from_allowance(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    ensure_payable_status(&invoice)?;
+    let payer = ReverseFactoringStorage::obligor(env, &invoice);
+    let remaining_due = compute_remaining_due(env, &invoice)?;
+
+    let nonce = make_settlement_nonce(env);
+    apply_payment(env, invoice_id, &payer, remaining_due, nonce, false)?;
+    settle_invoice_internal(env, invoice_id)
+}
+
+/// What settling the invoice now, once everything due is paid, would pull from its
+/// obligor: what settlement pays out after any early-payment discount, less third-party
+/// payments held by the contract.
+pub fn obligor_settlement_amount(env: &Env, invoice: &Invoice) -> Result<i128, QuickLendXError> {
+    let payouts = settlement_payouts(env, invoice, total_due(env, invoice)?)?;
+    let mut amount = payouts
+        .investor_return
+        .checked_add(payouts.platform_fee)
+        .ok_or(QuickLendXError::InvalidAmount)?;
+    for (_, paid) in third_party_paid(env, &invoice.id).iter() {
+        amount = amount.saturating_sub(paid);
+    }
+    Ok(amount.max(0))
+}
+
+/// Approve or revoke a third-party payer for an invoice (business only).
+pub fn set_approved_payer(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    approved: bool,
+) -> Result<(), QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    invoice.business.require_auth();
+
+    let key = SettlementDataKey::ApprovedPayer(invoice_id.clone(), payer.clone());
+    if approved {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+    emit_payer_approval_set(env, invoice_id, payer, approved);
+    Ok(())
+}
+
+/// Whether the business approved `payer` to pay the invoice.
+pub fn is_approved_payer(env: &Env, invoice_id: &BytesN<32>, payer: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&SettlementDataKey::ApprovedPayer(
+            invoice_id.clone(),
+            payer.clone(),
+        ))
+        .unwrap_or(false)
+}
+
+/// Returns aggregate payment progress for an invoice; `total_due` includes late-payment
+/// interest accrued so far.
 pub fn get_invoice_progress(
     env: &Env,
     invoice_id: &BytesN<32>,
 ) -> Result<Progress, QuickLendXError> {
     let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
-    let total_due = invoice.amount;
+    let total_due = total_due(env, &invoice)?;
     let total_paid = invoice.total_paid;
-    let remaining_due = compute_remaining_due(&invoice)?;
+    let remaining_due = compute_remaining_due(env, &invoice)?;
 
     let progress_percent = if total_due <= 0 {
         0
@@ -288,6 +522,7 @@ pub fn get_invoice_progress(
         progress_percent,
         payment_count: get_payment_count_internal(env, invoice_id),
         status: invoice.status,
+        installments: InstallmentPlans::get_schedule(env, invoice_id),
     })
 }
 
@@ -345,45 +580,40 @@ fn settle_invoice_internal(env: &Env, invoice_id: &BytesN<32>) -> Result<(), Qui
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
     ensure_payable_status(&invoice)?;
 
-    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id)
-        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    let investments = active_investments(env, invoice_id);
+    let funded_total = active_principal(env, invoice_id)?;
+    let late_interest = LatePaymentInterest::current(env, &invoice);
 
-    if invoice.total_paid < invoice.amount || invoice.total_paid < investment.amount {
+    if invoice.total_paid < total_due(env, &invoice)? || invoice.total_paid < funded_total {
         return Err(QuickLendXError::PaymentTooLow);
     }
 
-    let investor_address = invoice
-        .investor
-        .clone()
-        .ok_or(QuickLendXError::NotInvestor)?;
-
-    let (investor_return, platform_fee) = match crate::fees::FeeManager::calculate_platform_fee(
-        env,
-        investment.amount,
-        invoice.total_paid,
-    ) {
-        Ok(result) => result,
-        // Backward-compatible fallback for environments/tests without fee config.
-        Err(QuickLendXError::StorageKeyNotFound) => {
-            crate::profits::calculate_profit(env, investment.amount, invoice.total_paid)
-        }
-        Err(error) => return Err(error),
-    };
-
     let business_address = invoice.business.clone();
-    transfer_funds(
-        env,
-        &invoice.currency,
-        &business_address,
-        &investor_address,
+    // Funds are pulled from the obligor (the buyer of a reverse-factored invoice)
+    let payer = ReverseFactoringStorage::obligor(env, &invoice);
+    let SettlementPayouts {
+        payouts,
         investor_return,
-    )?;
+        platform_fee,
+        early_payment_discount,
+    } = settlement_payouts(env, &invoice, invoice.total_paid)?;
+
+    let collected = investor_return
+        .checked_add(platform_fee)
+        .ok_or(QuickLendXError::InvalidAmount)?;
+    let source = collect_settlement_funds(env, &invoice, &payer, collected)?;
+    for (investment, share_return) in payouts.iter() {
+        if share_return > 0 {
+            // The position's receipt holders are paid, not necessarily the original investor
+            Receipts::pay_holders(env, &invoice.currency, &source, &investment, share_return)?;
+        }
+    }
 
     if platform_fee > 0 {
         let fee_recipient = crate::fees::FeeManager::route_platform_fee(
             env,
             &invoice.currency,
-            &business_address,
+            &source,
             platform_fee,
         )?;
         crate::events::emit_platform_fee_routed(env, invoice_id, &fee_recipient, platform_fee);
@@ -399,9 +629,12 @@ fn settle_invoice_internal(env: &Env, invoice_id: &BytesN<32>) -> Result<(), Qui
         InvoiceStorage::add_to_status_invoices(env, &invoice.status, invoice_id);
     }
 
-    let mut updated_investment = investment;
-    updated_investment.status = InvestmentStatus::Completed;
-    InvestmentStorage::update_investment(env, &updated_investment);
+    for mut investment in investments.iter() {
+        investment.status = InvestmentStatus::Completed;
+        InvestmentStorage::update_investment(env, &investment);
+        Receipts::redeem(env, &investment.investment_id);
+    }
+    CreditLines::release_draw(env, invoice_id);
 
     log_settlement_completed(
         env,
@@ -411,6 +644,12 @@ fn settle_invoice_internal(env: &Env, invoice_id: &BytesN<32>) -> Result<(), Qui
     );
 
     emit_invoice_settled(env, &invoice, investor_return, platform_fee);
+    if early_payment_discount > 0 {
+        emit_early_payment_discount(env, invoice_id, &payer, early_payment_discount);
+    }
+    if late_interest > 0 {
+        emit_late_interest_paid(env, invoice_id, &payer, late_interest);
+    }
     emit_invoice_settled_final(env, invoice_id, invoice.total_paid, paid_at);
 
     let _ = NotificationSystem::notify_payment_received(env, &invoice, invoice.total_paid);
@@ -418,6 +657,217 @@ fn settle_invoice_internal(env: &Env, invoice_id: &BytesN<32>) -> Result<(), Qui
     Ok(())
 }
 
+/// What settling an invoice pays out of `total_paid`.
+struct SettlementPayouts {
+    /// Each active position's return not paid out yet
+    payouts: Vec<(Investment, i128)>,
+    investor_return: i128,
+    platform_fee: i128,
+    /// Unearned return left to the business for settling a time-priced invoice early
+    early_payment_discount: i128,
+}
+
+/// Split `total_paid` across the active positions pro rata by principal, the last one
+/// absorbing any rounding remainder, then apply the early-payment discount and net out
+/// what progressive distribution already paid.
+fn settlement_payouts(
+    env: &Env,
+    invoice: &Invoice,
+    total_paid: i128,
+) -> Result<SettlementPayouts, QuickLendXError> {
+    let investments = active_investments(env, &invoice.id);
+    let funded_total = active_principal(env, &invoice.id)?;
+    let mut settlement = SettlementPayouts {
+        payouts: Vec::new(env),
+        investor_return: 0,
+        platform_fee: 0,
+        early_payment_discount: 0,
+    };
+    let mut distributed: i128 = 0;
+    let last_index = investments.len().saturating_sub(1);
+
+    for (index, investment) in investments.iter().enumerate() {
+        let share = if index as u32 == last_index {
+            total_paid
+                .checked_sub(distributed)
+                .ok_or(QuickLendXError::InvalidAmount)?
+        } else {
+            total_paid
+                .checked_mul(investment.amount)
+                .and_then(|v| v.checked_div(funded_total))
+                .ok_or(QuickLendXError::InvalidAmount)?
+        };
+        distributed = distributed
+            .checked_add(share)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+
+        // Settling a time-priced invoice early leaves the unearned return to the business
+        let (share, discount) = TimeBasedPricing::early_settlement_split(
+            env,
+            &invoice.id,
+            invoice.due_date,
+            investment.amount,
+            funded_total,
+            investment.funded_at,
+            share,
+        );
+        // What was already paid out progressively cannot be discounted
+        let paid_out = ProgressiveDistribution::distributed(env, &investment.investment_id);
+        let discount = discount.saturating_sub(paid_out.saturating_sub(share).max(0));
+        let share = share.max(paid_out);
+        settlement.early_payment_discount = settlement
+            .early_payment_discount
+            .checked_add(discount)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+
+        let (share_return, share_fee) =
+            ProgressiveDistribution::outstanding_return_and_fee(env, &investment, share)?;
+
+        settlement
+            .payouts
+            .push_back((investment.clone(), share_return));
+
+        settlement.investor_return = settlement
+            .investor_return
+            .checked_add(share_return)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+        settlement.platform_fee = settlement
+            .platform_fee
+            .checked_add(share_fee)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+    }
+    Ok(settlement)
+}
+
+/// Account settlement funds are paid out of. Without third-party payments that is the
+/// obligor; otherwise the contract pays out of the third parties' payments it holds, with
+/// the obligor's remainder pulled in. Held payments beyond what settlement pays out, after
+/// an early-payment discount, go back to their payers.
+fn collect_settlement_funds(
+    env: &Env,
+    invoice: &Invoice,
+    obligor: &Address,
+    collected: i128,
+) -> Result<Address, QuickLendXError> {
+    let paid = third_party_paid(env, &invoice.id);
+    if paid.is_empty() {
+        return Ok(obligor.clone());
+    }
+
+    let contract = env.current_contract_address();
+    let mut remaining = collected.max(0);
+    for (payer, amount) in paid.iter() {
+        let used = amount.min(remaining);
+        remaining -= used;
+        if amount > used {
+            transfer_funds(env, &invoice.currency, &contract, &payer, amount - used)?;
+        }
+    }
+    if remaining > 0 {
+        transfer_funds(env, &invoice.currency, obligor, &contract, remaining)?;
+    }
+    env.storage()
+        .persistent()
+        .remove(&SettlementDataKey::ThirdPartyPaid(invoice.id.clone()));
+    Ok(contract)
+}
+
+/// Return the third-party payments the contract holds for an invoice that will not settle,
+/// as when it defaults or is refunded.
+pub fn return_third_party_payments(env: &Env, invoice: &Invoice) -> Result<(), QuickLendXError> {
+    let key = SettlementDataKey::ThirdPartyPaid(invoice.id.clone());
+    let paid = third_party_paid(env, &invoice.id);
+    if paid.is_empty() {
+        return Ok(());
+    }
+    let contract = env.current_contract_address();
+    for (payer, amount) in paid.iter() {
+        if amount > 0 {
+            transfer_funds(env, &invoice.currency, &contract, &payer, amount)?;
+        }
+    }
+    env.storage().persistent().remove(&key);
+    Ok(())
+}
+
+/// Errors as `transfer_funds` would if `holder` could not pay `amount` to the contract.
+fn ensure_funds_available(
+    env: &Env,
+    currency: &Address,
+    holder: &Address,
+    amount: i128,
+) -> Result<(), QuickLendXError> {
+    let contract = env.current_contract_address();
+    if amount <= 0 || *holder == contract {
+        return Ok(());
+    }
+    let token_client = token::Client::new(env, currency);
+    if token_client.balance(holder) < amount {
+        return Err(QuickLendXError::InsufficientFunds);
+    }
+    if token_client.allowance(holder, &contract) < amount {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+    Ok(())
+}
+
+/// Party `payer` pays the invoice as; errors unless it is the obligor, the debtor or an
+/// approved payer.
+fn payment_party(
+    env: &Env,
+    invoice: &Invoice,
+    payer: &Address,
+) -> Result<PaymentParty, QuickLendXError> {
+    if *payer == ReverseFactoringStorage::obligor(env, invoice) {
+        return Ok(if *payer == invoice.business {
+            PaymentParty::Business
+        } else {
+            PaymentParty::Buyer
+        });
+    }
+    if invoice.debtor.as_ref() == Some(payer) {
+        return Ok(PaymentParty::Debtor);
+    }
+    if is_approved_payer(env, &invoice.id, payer) {
+        return Ok(PaymentParty::Delegate);
+    }
+    Err(QuickLendXError::NotBusinessOwner)
+}
+
+/// Amounts the contract holds from third-party payers until the invoice settles.
+fn third_party_paid(env: &Env, invoice_id: &BytesN<32>) -> Map<Address, i128> {
+    env.storage()
+        .persistent()
+        .get(&SettlementDataKey::ThirdPartyPaid(invoice_id.clone()))
+        .unwrap_or_else(|| Map::new(env))
+}
+
+/// Active investments funding the invoice (one, or several when syndicated).
+fn active_investments(env: &Env, invoice_id: &BytesN<32>) -> Vec<Investment> {
+    let mut active = Vec::new(env);
+    for investment in InvestmentStorage::get_investments_by_invoice(env, invoice_id).iter() {
+        if investment.status == InvestmentStatus::Active {
+            active.push_back(investment);
+        }
+    }
+    active
+}
+
+/// Total principal of the active investments; errors when the invoice has none.
+fn active_principal(env: &Env, invoice_id: &BytesN<32>) -> Result<i128, QuickLendXError> {
+    let investments = active_investments(env, invoice_id);
+    if investments.is_empty() {
+        return Err(QuickLendXError::StorageKeyNotFound);
+    }
+    let mut total: i128 = 0;
+    for investment in investments.iter() {
+        total = total
+            .checked_add(investment.amount)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+    }
+    Ok(total)
+}
+
 fn ensure_invoice_exists(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
     if InvoiceStorage::get_invoice(env, invoice_id).is_none() {
         return Err(QuickLendXError::InvoiceNotFound);
@@ -441,21 +891,30 @@ fn ensure_payable_status(invoice: &Invoice) -> Result<(), QuickLendXError> {
     Ok(())
 }
 
-fn compute_remaining_due(invoice: &Invoice) -> Result<i128, QuickLendXError> {
+/// Invoice amount plus the late-payment interest owed on it.
+fn total_due(env: &Env, invoice: &Invoice) -> Result<i128, QuickLendXError> {
     if invoice.amount <= 0 {
         return Err(QuickLendXError::InvoiceAmountInvalid);
     }
 
+    invoice
+        .amount
+        .checked_add(LatePaymentInterest::current(env, invoice))
+        .ok_or(QuickLendXError::InvalidAmount)
+}
+
+fn compute_remaining_due(env: &Env, invoice: &Invoice) -> Result<i128, QuickLendXError> {
+    let total_due = total_due(env, invoice)?;
+
     if invoice.total_paid < 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
 
-    if invoice.total_paid >= invoice.amount {
+    if invoice.total_paid >= total_due {
         return Ok(0);
     }
 
-    invoice
-        .amount
+    total_due
         .checked_sub(invoice.total_paid)
         .ok_or(QuickLendXError::InvalidAmount)
 }
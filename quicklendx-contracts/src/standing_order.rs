@@ -0,0 +1,387 @@
+//! Investor standing orders.
+//!
+//! A standing order is a mandate such as "bid 95% of face value on any Technology
+//! invoice under 50k USDC due within 60 days, up to 200k of total exposure". Orders are
+//! stored per investor and evaluated when `verify_invoice` moves an invoice to Verified,
+//! and again when the debtor confirms a Verified invoice; every matching order places a
+//! bid through the regular `place_bid` validation, so investor limits and
+//! `max_active_bids_per_investor` still apply. An order whose bid is rejected is skipped
+//! with an event giving the reason. Open orders are evaluated against an invoice in
+//! batches of `MAX_STANDING_ORDERS_EVALUATED`; when more are open, keepers evaluate the
+//! rest through `keeper_execute_standing_orders` while the invoice stays Verified.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_standing_order_bid, emit_standing_order_cancelled, emit_standing_order_created,
+};
+use crate::invoice::{Invoice, InvoiceCategory, InvoiceStatus, InvoiceStorage};
+use crate::listing::BPS_DENOMINATOR;
+use crate::verification::{BusinessVerificationStatus, InvestorVerificationStorage};
+
+/// Maximum number of open standing orders per investor.
+pub const MAX_STANDING_ORDERS_PER_INVESTOR: u32 = 5;
+/// Maximum number of open standing orders evaluated against an invoice in one call.
+pub const MAX_STANDING_ORDERS_EVALUATED: u32 = 50;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum StandingOrderKey {
+    Order(u64),
+    ByInvestor(Address),
+    Active,
+    Counter,
+    /// Last order evaluated against an invoice, while some are left to evaluate
+    OrderCursor(BytesN<32>),
+}
+
+/// Which invoices an order bids on, and on what terms.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StandingOrderTerms {
+    /// Only invoices in this currency are considered.
+    pub currency: Address,
+    /// Accepted invoice categories; empty accepts every category.
+    pub categories: Vec<InvoiceCategory>,
+    /// Largest invoice face value the order bids on.
+    pub max_invoice_amount: i128,
+    /// Largest number of days between verification and the invoice due date.
+    pub max_days_to_due: u64,
+    /// Bid amount, in bps of the invoice amount.
+    pub advance_bps: u32,
+    /// Expected return, in bps of the invoice amount.
+    pub return_bps: u32,
+    /// Cap on the sum of open bids and unsettled investments placed by the order.
+    pub max_exposure: i128,
+    /// Only bid once the invoice's debtor has confirmed it on-chain.
+    pub require_debtor_confirmation: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StandingOrder {
+    pub order_id: u64,
+    pub investor: Address,
+    pub terms: StandingOrderTerms,
+    /// Bids placed by this order that may still count towards its exposure.
+    pub bid_ids: Vec<BytesN<32>>,
+    pub active: bool,
+    pub created_at: u64,
+}
+
+impl StandingOrder {
+    /// Bid amount and expected return this order offers on an invoice.
+    pub fn bid_terms(&self, invoice_amount: i128) -> (i128, i128) {
+        (
+            invoice_amount.saturating_mul(self.terms.advance_bps as i128) / BPS_DENOMINATOR,
+            invoice_amount.saturating_mul(self.terms.return_bps as i128) / BPS_DENOMINATOR,
+        )
+    }
+}
+
+pub struct StandingOrderStorage;
+
+impl StandingOrderStorage {
+    pub fn get_order(env: &Env, order_id: u64) -> Option<StandingOrder> {
+        env.storage()
+            .persistent()
+            .get(&StandingOrderKey::Order(order_id))
+    }
+
+    pub fn get_orders_by_investor(env: &Env, investor: &Address) -> Vec<StandingOrder> {
+        let mut orders = Vec::new(env);
+        for order_id in Self::get_order_ids_by_investor(env, investor).iter() {
+            if let Some(order) = Self::get_order(env, order_id) {
+                orders.push_back(order);
+            }
+        }
+        orders
+    }
+
+    fn get_order_ids_by_investor(env: &Env, investor: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&StandingOrderKey::ByInvestor(investor.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_order(env: &Env, order: &StandingOrder) {
+        env.storage()
+            .persistent()
+            .set(&StandingOrderKey::Order(order.order_id), order);
+    }
+
+    fn get_active_ids(env: &Env) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&StandingOrderKey::Active)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn next_order_id(env: &Env) -> u64 {
+        let next: u64 = env
+            .storage()
+            .persistent()
+            .get(&StandingOrderKey::Counter)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage()
+            .persistent()
+            .set(&StandingOrderKey::Counter, &next);
+        next
+    }
+}
+
+pub struct StandingOrders;
+
+impl StandingOrders {
+    /// Register a standing order for a verified investor.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the investor is not verified
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    /// * `InvalidAmount` if the terms are inconsistent
+    /// * `InvalidTimestamp` if `max_days_to_due` is zero
+    /// * `OperationNotAllowed` if the investor already has the maximum number of open orders
+    pub fn create(
+        env: &Env,
+        investor: &Address,
+        terms: &StandingOrderTerms,
+    ) -> Result<u64, QuickLendXError> {
+        investor.require_auth();
+        let verification = InvestorVerificationStorage::get(env, investor)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        CurrencyWhitelist::require_allowed_currency(env, &terms.currency)?;
+        Self::validate_terms(terms)?;
+
+        let open_orders = StandingOrderStorage::get_orders_by_investor(env, investor)
+            .iter()
+            .filter(|order| order.active)
+            .count() as u32;
+        if open_orders >= MAX_STANDING_ORDERS_PER_INVESTOR {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let order = StandingOrder {
+            order_id: StandingOrderStorage::next_order_id(env),
+            investor: investor.clone(),
+            terms: terms.clone(),
+            bid_ids: Vec::new(env),
+            active: true,
+            created_at: env.ledger().timestamp(),
+        };
+        StandingOrderStorage::store_order(env, &order);
+
+        let mut investor_orders = StandingOrderStorage::get_order_ids_by_investor(env, investor);
+        investor_orders.push_back(order.order_id);
+        env.storage().persistent().set(
+            &StandingOrderKey::ByInvestor(investor.clone()),
+            &investor_orders,
+        );
+        let mut active = StandingOrderStorage::get_active_ids(env);
+        active.push_back(order.order_id);
+        env.storage()
+            .persistent()
+            .set(&StandingOrderKey::Active, &active);
+
+        emit_standing_order_created(env, &order);
+        Ok(order.order_id)
+    }
+
+    /// Cancel a standing order (owning investor only). Bids it already placed stay open.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the order does not exist
+    /// * `Unauthorized` if the caller does not own the order
+    /// * `InvalidStatus` if the order is already cancelled
+    pub fn cancel(env: &Env, investor: &Address, order_id: u64) -> Result<(), QuickLendXError> {
+        investor.require_auth();
+        let mut order = StandingOrderStorage::get_order(env, order_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if order.investor != *investor {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if !order.active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        order.active = false;
+        StandingOrderStorage::store_order(env, &order);
+
+        let active = StandingOrderStorage::get_active_ids(env);
+        let mut remaining = Vec::new(env);
+        for id in active.iter() {
+            if id != order_id {
+                remaining.push_back(id);
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&StandingOrderKey::Active, &remaining);
+
+        emit_standing_order_cancelled(env, &order);
+        Ok(())
+    }
+
+    /// Start evaluating every open order against an invoice, from the oldest one.
+    pub fn begin_evaluation(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .set(&StandingOrderKey::OrderCursor(invoice_id.clone()), &0u64);
+    }
+
+    /// Stop evaluating orders against an invoice, as once it is funded.
+    pub fn end_evaluation(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .remove(&StandingOrderKey::OrderCursor(invoice_id.clone()));
+    }
+
+    /// Whether open orders are left to evaluate against an invoice.
+    pub fn evaluation_pending(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .has(&StandingOrderKey::OrderCursor(invoice_id.clone()))
+    }
+
+    /// Active orders whose terms and remaining exposure allow a bid on the invoice, among
+    /// the next `MAX_STANDING_ORDERS_EVALUATED` open orders not evaluated against it yet.
+    ///
+    /// Open orders are evaluated in the order they were created. The invoice's cursor
+    /// moves past the orders evaluated, and is cleared once none are left; nothing is
+    /// evaluated unless `begin_evaluation` was called.
+    pub fn next_matching_orders(env: &Env, invoice: &Invoice) -> Vec<StandingOrder> {
+        let mut matches = Vec::new(env);
+        let cursor_key = StandingOrderKey::OrderCursor(invoice.id.clone());
+        let cursor: u64 = match env.storage().persistent().get(&cursor_key) {
+            Some(cursor) => cursor,
+            None => return matches,
+        };
+        let now = env.ledger().timestamp();
+        let mut evaluated = 0u32;
+        let mut last_evaluated = cursor;
+        let mut exhausted = true;
+        // Order ids only grow, so the active list stays sorted by id
+        for order_id in StandingOrderStorage::get_active_ids(env).iter() {
+            if order_id <= cursor {
+                continue;
+            }
+            if evaluated == MAX_STANDING_ORDERS_EVALUATED {
+                exhausted = false;
+                break;
+            }
+            evaluated += 1;
+            last_evaluated = order_id;
+            let order = match StandingOrderStorage::get_order(env, order_id) {
+                Some(order) => order,
+                None => continue,
+            };
+            let terms = &order.terms;
+            if order.investor == invoice.business
+                || terms.currency != invoice.currency
+                || invoice.amount > terms.max_invoice_amount
+                || (!terms.categories.is_empty() && !terms.categories.contains(&invoice.category))
+            {
+                continue;
+            }
+            let max_due = now.saturating_add(terms.max_days_to_due.saturating_mul(SECONDS_PER_DAY));
+            if invoice.due_date > max_due {
+                continue;
+            }
+            if terms.require_debtor_confirmation && !invoice.is_debtor_confirmed() {
+                continue;
+            }
+            let open_bids = Self::open_bids(env, &order);
+            let already_bidding = open_bids.iter().any(|bid_id| {
+                BidStorage::get_bid(env, &bid_id)
+                    .map(|bid| bid.invoice_id == invoice.id)
+                    .unwrap_or(false)
+            });
+            if already_bidding {
+                continue;
+            }
+            let (bid_amount, _) = order.bid_terms(invoice.amount);
+            if Self::exposure(env, &order).saturating_add(bid_amount) > terms.max_exposure {
+                continue;
+            }
+            matches.push_back(order);
+        }
+        if exhausted {
+            env.storage().persistent().remove(&cursor_key);
+        } else {
+            env.storage().persistent().set(&cursor_key, &last_evaluated);
+        }
+        matches
+    }
+
+    /// Record a bid placed on behalf of an order.
+    pub fn record_bid(env: &Env, order_id: u64, invoice_id: &BytesN<32>, bid_id: &BytesN<32>) {
+        if let Some(mut order) = StandingOrderStorage::get_order(env, order_id) {
+            order.bid_ids = Self::open_bids(env, &order);
+            order.bid_ids.push_back(bid_id.clone());
+            StandingOrderStorage::store_order(env, &order);
+            emit_standing_order_bid(env, order_id, invoice_id, bid_id);
+        }
+    }
+
+    /// Sum of the order's open bids and of its accepted bids whose invoice is still Funded.
+    pub fn exposure(env: &Env, order: &StandingOrder) -> i128 {
+        let mut total = 0i128;
+        for bid_id in Self::open_bids(env, order).iter() {
+            if let Some(bid) = BidStorage::get_bid(env, &bid_id) {
+                total = total.saturating_add(bid.bid_amount);
+            }
+        }
+        total
+    }
+
+    /// The order's bids that still count towards its exposure.
+    fn open_bids(env: &Env, order: &StandingOrder) -> Vec<BytesN<32>> {
+        let now = env.ledger().timestamp();
+        let mut open = Vec::new(env);
+        for bid_id in order.bid_ids.iter() {
+            let bid = match BidStorage::get_bid(env, &bid_id) {
+                Some(bid) => bid,
+                None => continue,
+            };
+            let counts = match bid.status {
+                BidStatus::Placed => !bid.is_expired(now),
+                BidStatus::Accepted => InvoiceStorage::get_invoice(env, &bid.invoice_id)
+                    .map(|invoice| invoice.status == InvoiceStatus::Funded)
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if counts {
+                open.push_back(bid_id);
+            }
+        }
+        open
+    }
+
+    fn validate_terms(terms: &StandingOrderTerms) -> Result<(), QuickLendXError> {
+        if terms.advance_bps == 0 || terms.advance_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.return_bps < terms.advance_bps {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.max_invoice_amount <= 0 || terms.max_exposure <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if terms.max_days_to_due == 0 {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Syndicated funding: several accepted bids jointly fund one invoice.
+//!
+//! Each participating bid is locked in its own escrow slice and becomes its own
+//! `Investment`. Settlement distributes repayments pro rata by principal and
+//! defaults/refunds apply to every participant.
+
+use crate::audit;
+use crate::bid::{BidStatus, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_bid_accepted, emit_invoice_funded, emit_invoice_syndicated};
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::notifications::NotificationSystem;
+use crate::payments::create_escrow;
+use crate::pool::InvoicePools;
+use crate::receipt::Receipts;
+use crate::sealed_bid::SealedBidding;
+use crate::vault::LiquidityVaults;
+use soroban_sdk::{Address, BytesN, Env, Vec};
+
+/// Maximum number of bids that may participate in one syndicate.
+pub const MAX_SYNDICATE_SIZE: u32 = 10;
+
+/// Accept several bids on the same invoice and fund it jointly.
+///
+/// Caller (business) must be authorized. Invoice must be Verified; every bid must be
+/// Placed, unexpired, belong to the invoice and come from a distinct investor. The
+/// combined bid amount may not exceed the invoice amount. The first bid in
+/// `bid_ids` is recorded as the lead investor on the invoice.
+///
+/// # Returns
+/// * `Ok(Vec<escrow_id>)` - One escrow slice per accepted bid, in `bid_ids` order
+///
+/// # Errors
+/// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `InvoiceAlreadyFunded`,
+///   `InvoiceNotAvailableForFunding`, `Unauthorized`
+/// * `OperationNotAllowed` if the bid list is empty, too large or has duplicates, if a
+///   bid awaits the debtor's confirmation of the invoice, if the invoice is reserved
+///   for an invoice pool, or if a vault bid would exceed the vault's business exposure cap
+/// * `InsufficientFunds` if a vault bid is no longer covered by the vault's idle liquidity
+/// * `InvalidAmount` if the combined bid amount exceeds the invoice amount
+/// * Errors from `create_escrow`
+pub fn accept_bids_and_fund(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    bid_ids: &Vec<BytesN<32>>,
+) -> Result<Vec<BytesN<32>>, QuickLendXError> {
+    let mut invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    invoice.business.require_auth();
+
+    if invoice.status != InvoiceStatus::Verified {
+        if invoice.status == InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvoiceAlreadyFunded);
+        }
+        return Err(QuickLendXError::InvoiceNotAvailableForFunding);
+    }
+
+    if bid_ids.is_empty() || bid_ids.len() > MAX_SYNDICATE_SIZE {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    SealedBidding::require_bidding_closed(env, invoice_id)?;
+    InvoicePools::require_unpooled(env, invoice_id)?;
+    BidStorage::cleanup_expired_bids(env, invoice_id);
+
+    // Validate the whole syndicate before moving any funds
+    let now = env.ledger().timestamp();
+    let mut bids = Vec::new(env);
+    let mut investors: Vec<Address> = Vec::new(env);
+    let mut total_funded: i128 = 0;
+    for (index, bid_id) in bid_ids.iter().enumerate() {
+        if bid_ids.first_index_of(&bid_id) != Some(index as u32) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let bid = BidStorage::get_bid(env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if bid.invoice_id != *invoice_id {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if bid.status != BidStatus::Placed || bid.is_expired(now) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        BidStorage::require_debtor_condition_met(env, &invoice, &bid)?;
+        if investors.contains(&bid.investor) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        investors.push_back(bid.investor.clone());
+        total_funded = total_funded
+            .checked_add(bid.bid_amount)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+        bids.push_back(bid);
+    }
+
+    if total_funded > invoice.amount {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+
+    // Lock each slice in escrow and record the matching investment
+    let mut escrow_ids = Vec::new(env);
+    for mut bid in bids.iter() {
+        LiquidityVaults::allocate(env, &invoice, &bid)?;
+        let escrow_id = create_escrow(
+            env,
+            invoice_id,
+            &bid.investor,
+            &invoice.business,
+            bid.bid_amount,
+            &invoice.currency,
+        )?;
+
+        bid.status = BidStatus::Accepted;
+        BidStorage::update_bid(env, &bid);
+
+        let investment = Investment {
+            investment_id: InvestmentStorage::generate_unique_investment_id(env),
+            invoice_id: invoice_id.clone(),
+            investor: bid.investor.clone(),
+            amount: bid.bid_amount,
+            funded_at: now,
+            status: InvestmentStatus::Active,
+            insurance: Vec::new(env),
+        };
+        InvestmentStorage::store_investment(env, &investment);
+        Receipts::mint(env, &investment, &escrow_id);
+
+        emit_bid_accepted(env, &bid, invoice_id, &invoice.business);
+        audit::log_bid_accepted(
+            env,
+            invoice_id.clone(),
+            invoice.business.clone(),
+            bid.bid_amount,
+        );
+        audit::log_escrow_created(
+            env,
+            invoice_id.clone(),
+            bid.investor.clone(),
+            bid.bid_amount,
+            escrow_id.clone(),
+        );
+        let _ = NotificationSystem::notify_bid_accepted(env, &invoice, &bid);
+
+        escrow_ids.push_back(escrow_id);
+    }
+
+    let lead = bids.get(0).ok_or(QuickLendXError::StorageKeyNotFound)?;
+    InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Verified, invoice_id);
+    invoice.mark_as_funded(env, lead.investor.clone(), total_funded, now);
+    InvoiceStorage::update_invoice(env, &invoice);
+    InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Funded, invoice_id);
+
+    emit_invoice_funded(env, invoice_id, &lead.investor, total_funded);
+    emit_invoice_syndicated(env, invoice_id, bids.len(), total_funded);
+    let _ = NotificationSystem::notify_invoice_status_changed(
+        env,
+        &invoice,
+        &InvoiceStatus::Verified,
+        &InvoiceStatus::Funded,
+    );
+
+    Ok(escrow_ids)
+}
@@ -0,0 +1,203 @@
+/// Test suite for auto-accept policies
+///
+/// Test Coverage:
+/// 1. Business policy: the first qualifying bid is funded through escrow
+/// 2. Tier gate: bids from investors below the minimum tier stay Placed
+/// 3. Precedence: an invoice policy overrides the business-wide one
+/// 4. Management: policies are queryable, validated and revocable
+use super::*;
+use crate::bid::BidStatus;
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::payments::EscrowStatus;
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+
+fn setup() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    Address,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    (env, client, admin, business, currency)
+}
+
+fn setup_investor(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    currency: &Address,
+    tier: InvestorTier,
+) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    env.as_contract(&client.address, || {
+        let mut verification = InvestorVerificationStorage::get(env, &investor).unwrap();
+        verification.tier = tier;
+        InvestorVerificationStorage::update(env, &verification);
+    });
+    token::StellarAssetClient::new(env, currency).mint(&investor, &100_000);
+    token::Client::new(env, currency).approve(&investor, &client.address, &100_000, &10_000);
+    investor
+}
+
+fn create_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    currency: &Address,
+) -> BytesN<32> {
+    let invoice_id = client.store_invoice(
+        business,
+        &10_000,
+        currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(env, "Auto-accept invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(env),
+    );
+    client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+/// Advance of at least 85% and return of at most 95% of face value, Gold tier or above.
+fn gold_policy() -> AutoAcceptPolicy {
+    AutoAcceptPolicy {
+        min_advance_bps: 8_500,
+        max_return_bps: 9_500,
+        min_investor_tier: InvestorTier::Gold,
+    }
+}
+
+#[test]
+fn test_business_policy_funds_first_qualifying_bid() {
+    let (env, client, _admin, business, currency) = setup();
+    let basic = setup_investor(&env, &client, &currency, InvestorTier::Basic);
+    let gold = setup_investor(&env, &client, &currency, InvestorTier::Gold);
+    let platinum = setup_investor(&env, &client, &currency, InvestorTier::Platinum);
+    client.set_business_auto_accept(&business, &gold_policy());
+    let invoice_id = create_invoice(&env, &client, &business, &currency);
+
+    // Basic tier, advance too low and return too high: all stay ordinary bids
+    let basic_bid = client.place_bid(&basic, &invoice_id, &9_000, &9_400);
+    let low_advance = client.place_bid(&gold, &invoice_id, &8_000, &9_000);
+    client.withdraw_bid(&low_advance);
+    let high_return = client.place_bid(&gold, &invoice_id, &9_000, &9_600);
+    client.withdraw_bid(&high_return);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+
+    let bid_id = client.place_bid(&platinum, &invoice_id, &8_500, &9_500);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(platinum.clone()));
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Accepted);
+    assert_eq!(
+        client.get_bid(&basic_bid).unwrap().status,
+        BidStatus::Placed
+    );
+    assert_eq!(client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+    assert_eq!(
+        token::Client::new(&env, &currency).balance(&client.address),
+        8_500
+    );
+
+    // Later qualifying bids find the invoice already funded
+    assert_eq!(
+        client.try_place_bid(&gold, &invoice_id, &9_000, &9_400),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_invoice_policy_overrides_business_policy() {
+    let (env, client, _admin, business, currency) = setup();
+    let silver = setup_investor(&env, &client, &currency, InvestorTier::Silver);
+    client.set_business_auto_accept(&business, &gold_policy());
+    let invoice_id = create_invoice(&env, &client, &business, &currency);
+    let other_invoice = create_invoice(&env, &client, &business, &currency);
+
+    let relaxed = AutoAcceptPolicy {
+        min_advance_bps: 8_000,
+        max_return_bps: 9_500,
+        min_investor_tier: InvestorTier::Basic,
+    };
+    client.set_invoice_auto_accept(&invoice_id, &relaxed);
+    assert_eq!(client.get_invoice_auto_accept(&invoice_id), Some(relaxed));
+    assert_eq!(
+        client.get_invoice_auto_accept(&other_invoice),
+        Some(gold_policy())
+    );
+
+    client.place_bid(&silver, &other_invoice, &9_000, &9_400);
+    assert_eq!(
+        client.get_invoice(&other_invoice).status,
+        InvoiceStatus::Verified
+    );
+    client.place_bid(&silver, &invoice_id, &8_000, &9_000);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+}
+
+#[test]
+fn test_policies_are_validated_and_revocable() {
+    let (env, client, _admin, business, currency) = setup();
+    let gold = setup_investor(&env, &client, &currency, InvestorTier::Gold);
+    let invoice_id = create_invoice(&env, &client, &business, &currency);
+
+    for (min_advance_bps, max_return_bps) in [(0, 9_000), (10_001, 12_000), (9_000, 9_000)] {
+        let policy = AutoAcceptPolicy {
+            min_advance_bps,
+            max_return_bps,
+            min_investor_tier: InvestorTier::Basic,
+        };
+        assert_eq!(
+            client.try_set_business_auto_accept(&business, &policy),
+            Err(Ok(QuickLendXError::InvalidAmount))
+        );
+    }
+    assert_eq!(
+        client.try_clear_business_auto_accept(&business),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    client.set_business_auto_accept(&business, &gold_policy());
+    assert_eq!(
+        client.get_business_auto_accept(&business),
+        Some(gold_policy())
+    );
+    client.clear_business_auto_accept(&business);
+    assert!(client.get_business_auto_accept(&business).is_none());
+    assert!(client.get_invoice_auto_accept(&invoice_id).is_none());
+
+    client.set_invoice_auto_accept(&invoice_id, &gold_policy());
+    client.clear_invoice_auto_accept(&invoice_id);
+
+    // With every policy revoked, a qualifying bid waits for manual acceptance
+    let bid_id = client.place_bid(&gold, &invoice_id, &9_000, &9_400);
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Placed);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        client.try_set_invoice_auto_accept(&invoice_id, &gold_policy()),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
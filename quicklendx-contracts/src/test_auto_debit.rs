@@ -0,0 +1,193 @@
+/// Test suite for pull-based settlement through auto-debit
+///
+/// Test Coverage:
+/// 1. Collection: a keeper settles a due invoice from the business's allowance
+/// 2. Payers: recorded payments are collected from whoever made them
+/// 3. Failed pulls: recorded, notified as overdue, and retried once covered
+/// 4. Validation: opt-in, due date and invoice status
+use super::*;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Debit {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    debtor: Address,
+    currency: Address,
+}
+
+fn setup() -> Debit {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    let debtor = Address::generate(&env);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor, &debtor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Debit {
+        env,
+        client,
+        business,
+        investor,
+        debtor,
+        currency,
+    }
+}
+
+/// A 10,000 invoice owed by `d.debtor`, funded with 9,000. Returns it with its due date.
+fn funded_invoice(d: &Debit) -> (BytesN<32>, u64) {
+    let due_date = d.env.ledger().timestamp() + 30 * 86_400;
+    let invoice_id = d.client.store_invoice(
+        &d.business,
+        &10_000,
+        &d.currency,
+        &due_date,
+        &String::from_str(&d.env, "Subscription"),
+        &InvoiceCategory::Services,
+        &Vec::new(&d.env),
+    );
+    d.client.set_invoice_debtor(&invoice_id, &d.debtor);
+    d.client.verify_invoice(&invoice_id);
+    let bid_id = d
+        .client
+        .place_bid(&d.investor, &invoice_id, &9_000, &10_000);
+    d.client.accept_bid(&invoice_id, &bid_id);
+    (invoice_id, due_date)
+}
+
+#[test]
+fn test_keeper_collects_due_invoice() {
+    let d = setup();
+    let token_client = token::Client::new(&d.env, &d.currency);
+    d.client.set_auto_debit(&d.business, &true);
+    assert!(d.client.is_auto_debit_enabled(&d.business));
+    let (invoice_id, due_date) = funded_invoice(&d);
+    let business_before = token_client.balance(&d.business);
+    let investor_before = token_client.balance(&d.investor);
+
+    d.env.ledger().set_timestamp(due_date);
+    assert!(d.client.collect_due(&invoice_id));
+
+    assert_eq!(
+        d.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+    assert_eq!(business_before - token_client.balance(&d.business), 10_000);
+    let (investor_return, _) = d.client.calculate_profit(&9_000, &10_000);
+    assert_eq!(
+        token_client.balance(&d.investor) - investor_before,
+        investor_return
+    );
+    assert_eq!(d.client.get_collection_failures(&invoice_id).len(), 0);
+}
+
+#[test]
+fn test_collection_includes_recorded_payments() {
+    let d = setup();
+    let token_client = token::Client::new(&d.env, &d.currency);
+    d.client.set_auto_debit(&d.business, &true);
+    let (invoice_id, due_date) = funded_invoice(&d);
+    let business_before = token_client.balance(&d.business);
+
+    d.client
+        .process_partial_payment(&invoice_id, &2_000, &String::from_str(&d.env, "part-1"));
+    d.client.process_payment_from(
+        &d.debtor,
+        &invoice_id,
+        &3_000,
+        &String::from_str(&d.env, "remit-1"),
+    );
+
+    d.env.ledger().set_timestamp(due_date + 86_400);
+    assert!(d.client.collect_due(&invoice_id));
+    assert_eq!(business_before - token_client.balance(&d.business), 7_000);
+    assert_eq!(token_client.balance(&d.debtor), 97_000);
+    assert_eq!(d.client.get_invoice(&invoice_id).total_paid, 10_000);
+}
+
+#[test]
+fn test_failed_pull_is_recorded_and_notified() {
+    let d = setup();
+    let token_client = token::Client::new(&d.env, &d.currency);
+    d.client.set_auto_debit(&d.business, &true);
+    let (invoice_id, due_date) = funded_invoice(&d);
+    token_client.approve(&d.business, &d.client.address, &4_000, &10_000);
+    let notifications_before = d.client.get_user_notifications(&d.business).len();
+
+    d.env.ledger().set_timestamp(due_date);
+    assert!(!d.client.collect_due(&invoice_id));
+
+    let failures = d.client.get_collection_failures(&invoice_id);
+    assert_eq!(failures.len(), 1);
+    let failure = failures.get(0).unwrap();
+    assert_eq!(failure.attempted_at, due_date);
+    assert_eq!(failure.amount, 10_000);
+    assert_eq!(failure.allowance, 4_000);
+    assert!(d.client.get_user_notifications(&d.business).len() > notifications_before);
+    assert_eq!(
+        d.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    // Once the allowance covers the pull, a retry settles the invoice
+    token_client.approve(&d.business, &d.client.address, &100_000, &10_000);
+    assert!(d.client.collect_due(&invoice_id));
+    assert_eq!(
+        d.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_collect_due_validation() {
+    let d = setup();
+    let (invoice_id, due_date) = funded_invoice(&d);
+
+    d.env.ledger().set_timestamp(due_date);
+    assert_eq!(
+        d.client.try_collect_due(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    d.client.set_auto_debit(&d.business, &true);
+    d.env.ledger().set_timestamp(due_date - 1);
+    assert_eq!(
+        d.client.try_collect_due(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    d.env.ledger().set_timestamp(due_date);
+    d.client.collect_due(&invoice_id);
+    assert_eq!(
+        d.client.try_collect_due(&invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    d.client.set_auto_debit(&d.business, &false);
+    assert!(!d.client.is_auto_debit_enabled(&d.business));
+}
@@ -0,0 +1,263 @@
+/// Test suite for batch payments and lump-sum allocation
+///
+/// Test Coverage:
+/// 1. Batches: payments are applied in turn and full ones settle their invoice
+/// 2. Rejections: a rejected payment is reported and leaves its invoice untouched
+/// 3. Lump sums: allocated oldest due first or smallest balance first, once per nonce
+/// 4. Validation: batch size, lump-sum amount and payers
+use super::*;
+use crate::batch_payment::{AllocationOrder, BatchPayment, MAX_BATCH_PAYMENTS};
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Batch {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Batch {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Batch {
+        env,
+        client,
+        business,
+        investor,
+        currency,
+    }
+}
+
+/// A 10,000 invoice due in `days` days, funded with 9,000.
+fn funded_invoice(b: &Batch, days: u64) -> BytesN<32> {
+    let invoice_id = b.client.store_invoice(
+        &b.business,
+        &10_000,
+        &b.currency,
+        &(b.env.ledger().timestamp() + days * DAY),
+        &String::from_str(&b.env, "Monthly supplies"),
+        &InvoiceCategory::Products,
+        &Vec::new(&b.env),
+    );
+    b.client.verify_invoice(&invoice_id);
+    let bid_id = b
+        .client
+        .place_bid(&b.investor, &invoice_id, &9_000, &10_000);
+    b.client.accept_bid(&invoice_id, &bid_id);
+    invoice_id
+}
+
+fn payment(b: &Batch, invoice_id: &BytesN<32>, amount: i128, nonce: &str) -> BatchPayment {
+    BatchPayment {
+        invoice_id: invoice_id.clone(),
+        amount,
+        nonce: String::from_str(&b.env, nonce),
+    }
+}
+
+#[test]
+fn test_batch_applies_and_settles() {
+    let b = setup();
+    let token_client = token::Client::new(&b.env, &b.currency);
+    let first = funded_invoice(&b, 30);
+    let second = funded_invoice(&b, 30);
+    let business_before = token_client.balance(&b.business);
+
+    let mut payments = Vec::new(&b.env);
+    payments.push_back(payment(&b, &first, 12_000, "batch-1"));
+    payments.push_back(payment(&b, &second, 3_000, "batch-1"));
+    let results = b.client.process_batch_payments(&b.business, &payments);
+
+    let settled = results.get(0).unwrap();
+    assert_eq!(settled.invoice_id, first);
+    assert_eq!(settled.applied, 10_000);
+    assert!(settled.settled);
+    assert_eq!(settled.error, 0);
+    let partial = results.get(1).unwrap();
+    assert_eq!(partial.applied, 3_000);
+    assert!(!partial.settled);
+
+    assert_eq!(b.client.get_invoice(&first).status, InvoiceStatus::Paid);
+    assert_eq!(b.client.get_invoice(&second).total_paid, 3_000);
+    assert_eq!(business_before - token_client.balance(&b.business), 10_000);
+}
+
+#[test]
+fn test_rejected_payments_leave_invoices_untouched() {
+    let b = setup();
+    let token_client = token::Client::new(&b.env, &b.currency);
+    let paid = funded_invoice(&b, 30);
+    let uncovered = funded_invoice(&b, 30);
+    let replayed = funded_invoice(&b, 30);
+    b.client
+        .process_partial_payment(&replayed, &1_000, &String::from_str(&b.env, "remit-7"));
+
+    // The business can no longer cover a settlement pull
+    token_client.approve(&b.business, &b.client.address, &5_000, &10_000);
+    let mut payments = Vec::new(&b.env);
+    payments.push_back(payment(&b, &paid, 2_000, "remit-8"));
+    payments.push_back(payment(&b, &uncovered, 10_000, "remit-8"));
+    payments.push_back(payment(&b, &replayed, 2_000, "remit-7"));
+    let results = b.client.process_batch_payments(&b.business, &payments);
+
+    assert_eq!(results.get(0).unwrap().applied, 2_000);
+    assert_eq!(results.get(0).unwrap().error, 0);
+    let rejected = results.get(1).unwrap();
+    assert_eq!(rejected.applied, 0);
+    assert_eq!(rejected.error, QuickLendXError::OperationNotAllowed as u32);
+    assert_eq!(
+        results.get(2).unwrap().error,
+        QuickLendXError::OperationNotAllowed as u32
+    );
+
+    let untouched = b.client.get_invoice(&uncovered);
+    assert_eq!(untouched.status, InvoiceStatus::Funded);
+    assert_eq!(untouched.total_paid, 0);
+    assert_eq!(b.client.get_invoice(&replayed).total_paid, 1_000);
+    assert_eq!(b.client.get_invoice(&paid).total_paid, 2_000);
+}
+
+#[test]
+fn test_lump_sum_pays_oldest_due_first() {
+    let b = setup();
+    let later = funded_invoice(&b, 60);
+    let sooner = funded_invoice(&b, 30);
+    let latest = funded_invoice(&b, 90);
+    let mut invoice_ids = Vec::new(&b.env);
+    for invoice_id in [&later, &sooner, &latest] {
+        invoice_ids.push_back(invoice_id.clone());
+    }
+
+    let results = b.client.allocate_payment(
+        &b.business,
+        &invoice_ids,
+        &15_000,
+        &AllocationOrder::OldestDueFirst,
+        &String::from_str(&b.env, "lump-1"),
+    );
+    assert_eq!(results.get(0).unwrap().invoice_id, sooner);
+    assert!(results.get(0).unwrap().settled);
+    assert_eq!(results.get(1).unwrap().invoice_id, later);
+    assert_eq!(results.get(1).unwrap().applied, 5_000);
+    assert_eq!(results.get(2).unwrap().invoice_id, latest);
+    assert_eq!(results.get(2).unwrap().applied, 0);
+    assert_eq!(b.client.get_invoice(&latest).total_paid, 0);
+}
+
+#[test]
+fn test_lump_sum_clears_smallest_balance_first() {
+    let b = setup();
+    let untouched = funded_invoice(&b, 30);
+    let mostly_paid = funded_invoice(&b, 60);
+    b.client
+        .process_partial_payment(&mostly_paid, &6_000, &String::from_str(&b.env, "remit-1"));
+    let mut invoice_ids = Vec::new(&b.env);
+    for invoice_id in [&untouched, &mostly_paid] {
+        invoice_ids.push_back(invoice_id.clone());
+    }
+
+    let results = b.client.allocate_payment(
+        &b.business,
+        &invoice_ids,
+        &5_000,
+        &AllocationOrder::SmallestBalanceFirst,
+        &String::from_str(&b.env, "lump-1"),
+    );
+    assert_eq!(results.get(0).unwrap().invoice_id, mostly_paid);
+    assert_eq!(results.get(0).unwrap().applied, 4_000);
+    assert!(results.get(0).unwrap().settled);
+    assert_eq!(results.get(1).unwrap().invoice_id, untouched);
+    assert_eq!(results.get(1).unwrap().applied, 1_000);
+
+    // Replaying the allocation pays nothing twice
+    let results = b.client.allocate_payment(
+        &b.business,
+        &invoice_ids,
+        &5_000,
+        &AllocationOrder::AsListed,
+        &String::from_str(&b.env, "lump-1"),
+    );
+    assert_eq!(
+        results.get(0).unwrap().error,
+        QuickLendXError::OperationNotAllowed as u32
+    );
+    assert_eq!(
+        results.get(1).unwrap().error,
+        QuickLendXError::InvalidStatus as u32
+    );
+    assert_eq!(b.client.get_invoice(&untouched).total_paid, 1_000);
+}
+
+#[test]
+fn test_batch_payment_validation() {
+    let b = setup();
+    let invoice_id = funded_invoice(&b, 30);
+    assert_eq!(
+        b.client
+            .try_process_batch_payments(&b.business, &Vec::new(&b.env)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let mut too_many = Vec::new(&b.env);
+    for _ in 0..=MAX_BATCH_PAYMENTS {
+        too_many.push_back(payment(&b, &invoice_id, 100, ""));
+    }
+    assert_eq!(
+        b.client.try_process_batch_payments(&b.business, &too_many),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    let mut invoice_ids = Vec::new(&b.env);
+    invoice_ids.push_back(invoice_id.clone());
+    assert_eq!(
+        b.client.try_allocate_payment(
+            &b.business,
+            &invoice_ids,
+            &0,
+            &AllocationOrder::AsListed,
+            &String::from_str(&b.env, "lump-0"),
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    // Someone who may not pay the invoice is rejected per payment
+    let outsider = Address::generate(&b.env);
+    let mut payments = Vec::new(&b.env);
+    payments.push_back(payment(&b, &invoice_id, 1_000, "x-1"));
+    let results = b.client.process_batch_payments(&outsider, &payments);
+    assert_eq!(
+        results.get(0).unwrap().error,
+        QuickLendXError::NotBusinessOwner as u32
+    );
+    assert_eq!(b.client.get_invoice(&invoice_id).total_paid, 0);
+}
@@ -0,0 +1,177 @@
+/// Test suite for in-place bid amendments
+///
+/// Test Coverage:
+/// 1. Amendment: terms change in place, keeping bid id, timestamp and invoice index
+/// 2. History: previous terms are recorded and capped at MAX_BID_AMENDMENTS
+/// 3. Validation: only live Placed bids, re-validated amounts and bounded expirations
+/// 4. Buy-now: an amendment reaching the buy-now price funds the invoice
+use super::*;
+use crate::bid::{BidStatus, MAX_BID_AMENDMENTS};
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+fn setup() -> (Env, QuickLendXContractClient<'static>, BytesN<32>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    token::StellarAssetClient::new(&env, &currency).mint(&investor, &100_000);
+    token::Client::new(&env, &currency).approve(&investor, &contract_id, &100_000, &10_000);
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Amended invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    (env, client, invoice_id, investor)
+}
+
+#[test]
+fn test_amend_bid_updates_in_place() {
+    let (env, client, invoice_id, investor) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_000);
+    let original = client.get_bid(&bid_id).unwrap();
+
+    env.ledger().set_timestamp(2_000);
+    let new_expiration = 2_000 + 86_400;
+    client.amend_bid(&bid_id, &8_500, &9_300, &Some(new_expiration));
+
+    let amended = client.get_bid(&bid_id).unwrap();
+    assert_eq!(amended.bid_amount, 8_500);
+    assert_eq!(amended.expected_return, 9_300);
+    assert_eq!(amended.expiration_timestamp, new_expiration);
+    assert_eq!(amended.timestamp, original.timestamp);
+    assert_eq!(amended.status, BidStatus::Placed);
+    assert_eq!(client.get_bids_for_invoice(&invoice_id).len(), 1);
+    assert_eq!(client.get_best_bid(&invoice_id).unwrap().bid_amount, 8_500);
+
+    // Without a new expiration the current one is kept
+    client.amend_bid(&bid_id, &8_200, &9_100, &None);
+    assert_eq!(
+        client.get_bid(&bid_id).unwrap().expiration_timestamp,
+        new_expiration
+    );
+
+    let history = client.get_bid_amendments(&bid_id);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(first.previous_amount, 8_000);
+    assert_eq!(first.previous_expected_return, 9_000);
+    assert_eq!(first.previous_expiration, original.expiration_timestamp);
+    assert_eq!(first.amended_at, 2_000);
+    assert_eq!(history.get(1).unwrap().previous_amount, 8_500);
+}
+
+#[test]
+fn test_amend_bid_validation() {
+    let (env, client, invoice_id, investor) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_000);
+
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &8_000, &7_000, &None),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &10_001, &11_000, &None),
+        Err(Ok(QuickLendXError::InvoiceAmountInvalid))
+    );
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &8_000, &9_000, &Some(1_000)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &8_000, &9_000, &Some(1_000 + 31 * 86_400)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    // Listing terms apply to amendments as well
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: Some(7_500),
+            max_expected_return: None,
+            max_discount_bps: None,
+            bidding_deadline: None,
+            buy_now_price: None,
+        },
+    );
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &7_000, &8_000, &None),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(client.get_bid_amendments(&bid_id).len(), 0);
+
+    for i in 0..MAX_BID_AMENDMENTS {
+        client.amend_bid(&bid_id, &(8_000 + i as i128), &9_000, &None);
+    }
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &8_500, &9_000, &None),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    client.withdraw_bid(&bid_id);
+    assert_eq!(
+        client.try_amend_bid(&bid_id, &8_500, &9_000, &None),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let expired = client.place_bid(&investor, &invoice_id, &8_000, &9_000);
+    let expiration = client.get_bid(&expired).unwrap().expiration_timestamp;
+    env.ledger().set_timestamp(expiration + 1);
+    assert_eq!(
+        client.try_amend_bid(&expired, &8_500, &9_000, &None),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_amendment_to_buy_now_price_funds_invoice() {
+    let (_env, client, invoice_id, investor) = setup();
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: None,
+            max_expected_return: None,
+            max_discount_bps: None,
+            bidding_deadline: None,
+            buy_now_price: Some(9_000),
+        },
+    );
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_000);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+
+    client.amend_bid(&bid_id, &9_000, &9_800, &None);
+
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Accepted);
+    assert_eq!(client.get_invoice_investment(&invoice_id).amount, 9_000);
+}
@@ -0,0 +1,310 @@
+/// Test suite for revolving credit lines
+///
+/// Test Coverage:
+/// 1. Draws: pledging a Verified invoice funds it through escrow and uses up credit
+/// 2. Revolving: payments collected on a drawn invoice, and refunds, restore the
+///    available credit
+/// 3. Vault lines: draws are funded out of the vault's idle liquidity
+/// 4. Validation: acceptance, limits, expiry, closing, verification and line ownership
+use super::*;
+use crate::credit_line::{CreditLineStatus, MAX_CREDIT_LINES_PER_BUSINESS};
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Facility {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    business: Address,
+    lender: Address,
+    currency: Address,
+}
+
+fn setup() -> Facility {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let lender = Address::generate(&env);
+    client.submit_investor_kyc(&lender, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&lender, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &lender] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Facility {
+        env,
+        client,
+        admin,
+        business,
+        lender,
+        currency,
+    }
+}
+
+/// 15,000 limit at a 90% advance, open for 90 days.
+fn terms(f: &Facility) -> CreditLineTerms {
+    CreditLineTerms {
+        currency: f.currency.clone(),
+        limit: 15_000,
+        advance_bps: 9_000,
+        return_bps: 10_000,
+        expires_at: f.env.ledger().timestamp() + 90 * 86400,
+    }
+}
+
+/// A line proposed by the lender and accepted by the business.
+fn open_line(f: &Facility) -> u64 {
+    let line_id = f.client.open_credit_line(&f.lender, &f.business, &terms(f));
+    f.client.accept_credit_line(&f.business, &line_id);
+    line_id
+}
+
+fn verified_invoice(f: &Facility) -> BytesN<32> {
+    let invoice_id = f.client.store_invoice(
+        &f.business,
+        &10_000,
+        &f.currency,
+        &(f.env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&f.env, "Monthly delivery"),
+        &InvoiceCategory::Services,
+        &Vec::new(&f.env),
+    );
+    f.client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+#[test]
+fn test_draw_funds_invoice_and_payments_restore_credit() {
+    let f = setup();
+    let line_id = open_line(&f);
+    let invoice_id = verified_invoice(&f);
+
+    let bid_id = f
+        .client
+        .draw_credit_line(&f.business, &line_id, &invoice_id);
+    let invoice = f.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(f.lender.clone()));
+    assert_eq!(f.client.get_escrow_details(&invoice_id).amount, 9_000);
+    assert_eq!(
+        f.client.get_bid(&bid_id).unwrap().status,
+        BidStatus::Accepted
+    );
+
+    let line = f.client.get_credit_line(&line_id).unwrap();
+    assert_eq!(line.utilized, 9_000);
+    assert_eq!(f.client.get_available_credit(&line_id), 6_000);
+    assert_eq!(f.client.get_credit_utilization(&line_id), 6_000);
+
+    // A payment that is only recorded restores nothing until settlement collects it
+    f.client
+        .process_partial_payment(&invoice_id, &4_000, &String::from_str(&f.env, "repay-1"));
+    assert_eq!(f.client.get_available_credit(&line_id), 6_000);
+    f.client.settle_invoice(&invoice_id, &6_000);
+    assert_eq!(f.client.get_available_credit(&line_id), 15_000);
+    assert_eq!(
+        f.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_draws_respect_limit_expiry_and_closing() {
+    let f = setup();
+    let line_id = open_line(&f);
+    let first = verified_invoice(&f);
+    let second = verified_invoice(&f);
+    f.client.draw_credit_line(&f.business, &line_id, &first);
+
+    // 9,000 more would exceed the 6,000 still available
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&f.business, &line_id, &second),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    let expires_at = f.env.ledger().timestamp() + 10 * 86400;
+    f.client
+        .update_credit_line(&f.lender, &line_id, &20_000, &expires_at);
+    f.env.ledger().set_timestamp(expires_at);
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&f.business, &line_id, &second),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    let line_id = open_line(&f);
+    f.client.close_credit_line(&f.lender, &line_id);
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&f.business, &line_id, &second),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(f.client.get_business_credit_lines(&f.business).len(), 2);
+    assert_eq!(f.client.get_lender_credit_lines(&f.lender).len(), 2);
+}
+
+#[test]
+fn test_vault_line_draws_on_idle_liquidity() {
+    let f = setup();
+    let curator = Address::generate(&f.env);
+    // The vault only bids on its own for Products invoices, so it leaves these alone
+    let mut categories = Vec::new(&f.env);
+    categories.push_back(InvoiceCategory::Products);
+    let policy = VaultPolicy {
+        categories,
+        max_days_to_due: 60,
+        max_business_exposure: 20_000,
+        advance_bps: 9_000,
+        return_bps: 10_000,
+    };
+    let vault_id =
+        f.client
+            .create_liquidity_vault(&f.admin, &f.currency, &curator, &policy, &50_000);
+    f.client.deposit_to_vault(&f.lender, &vault_id, &20_000);
+
+    let line_id = f
+        .client
+        .open_vault_credit_line(&curator, &vault_id, &f.business, &terms(&f));
+    f.client.accept_credit_line(&f.business, &line_id);
+    let invoice_id = verified_invoice(&f);
+    f.client
+        .draw_credit_line(&f.business, &line_id, &invoice_id);
+
+    assert_eq!(
+        f.client.get_invoice(&invoice_id).investor,
+        Some(f.client.address.clone())
+    );
+    let vault = f.client.get_liquidity_vault(&vault_id).unwrap();
+    assert_eq!(vault.idle, 11_000);
+    assert_eq!(vault.deployed, 9_000);
+    assert_eq!(
+        f.client
+            .try_update_credit_line(&f.lender, &line_id, &1_000, &5_000_000),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_credit_line_validation() {
+    let f = setup();
+    let outsider = Address::generate(&f.env);
+    assert_eq!(
+        f.client
+            .try_open_credit_line(&f.lender, &outsider, &terms(&f)),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    assert_eq!(
+        f.client
+            .try_open_credit_line(&outsider, &f.business, &terms(&f)),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    let mut bad_terms = terms(&f);
+    bad_terms.limit = 0;
+    assert_eq!(
+        f.client
+            .try_open_credit_line(&f.lender, &f.business, &bad_terms),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    // A line cannot be drawn on before its business accepts it
+    let line_id = f
+        .client
+        .open_credit_line(&f.lender, &f.business, &terms(&f));
+    let invoice_id = verified_invoice(&f);
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&f.business, &line_id, &invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        f.client.try_accept_credit_line(&outsider, &line_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    f.client.accept_credit_line(&f.business, &line_id);
+    assert_eq!(
+        f.client.try_accept_credit_line(&f.business, &line_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&outsider, &line_id, &invoice_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.try_close_credit_line(&outsider, &line_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    // Only Verified invoices can be pledged
+    f.client
+        .draw_credit_line(&f.business, &line_id, &invoice_id);
+    assert_eq!(
+        f.client
+            .try_draw_credit_line(&f.business, &line_id, &invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_collected_payments_and_refunds_restore_credit() {
+    let f = setup();
+    let line_id = open_line(&f);
+    let distributed = f.client.store_invoice(
+        &f.business,
+        &10_000,
+        &f.currency,
+        &(f.env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&f.env, "Paid out as it comes in"),
+        &InvoiceCategory::Services,
+        &Vec::new(&f.env),
+    );
+    f.client.set_progressive_distribution(&distributed, &true);
+    f.client.verify_invoice(&distributed);
+    f.client
+        .draw_credit_line(&f.business, &line_id, &distributed);
+
+    // Progressive distribution collects the payment, so the credit comes back at once
+    f.client
+        .process_partial_payment(&distributed, &4_000, &String::from_str(&f.env, "repay-1"));
+    assert_eq!(f.client.get_available_credit(&line_id), 10_000);
+
+    // Refunding the lender releases the rest of the draw
+    f.client.refund_escrow_funds(&distributed, &f.business);
+    assert_eq!(f.client.get_available_credit(&line_id), 15_000);
+}
+
+#[test]
+fn test_line_cap_counts_active_lines_only() {
+    let f = setup();
+    // Proposals the business never accepted, or that were withdrawn, take no slot
+    for _ in 0..MAX_CREDIT_LINES_PER_BUSINESS {
+        let line_id = f
+            .client
+            .open_credit_line(&f.lender, &f.business, &terms(&f));
+        f.client.close_credit_line(&f.lender, &line_id);
+    }
+    let line_id = open_line(&f);
+    assert_eq!(
+        f.client.get_credit_line(&line_id).unwrap().status,
+        CreditLineStatus::Active
+    );
+}
@@ -0,0 +1,254 @@
+/// Test suite for on-chain debtor confirmation of invoices
+///
+/// Test Coverage:
+/// 1. Confirmation: the named debtor acknowledges the invoice, recorded on the invoice
+/// 2. Conditional bids: left out of ranking, not acceptable and not funded by a buy-now
+///    price until the debtor confirms
+/// 3. Standing orders: orders requiring confirmation bid once the debtor confirms, unless
+///    a sealed auction is open
+/// 4. Validation: debtor assignment and confirmation rules
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::listing::ListingTerms;
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+
+struct Trade {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    debtor: Address,
+    currency: Address,
+}
+
+fn setup() -> Trade {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let debtor = Address::generate(&env);
+
+    Trade {
+        env,
+        client,
+        business,
+        debtor,
+        currency,
+    }
+}
+
+fn setup_investor(t: &Trade) -> Address {
+    let investor = Address::generate(&t.env);
+    t.client
+        .submit_investor_kyc(&investor, &String::from_str(&t.env, "Investor KYC"));
+    t.client.verify_investor(&investor, &50_000);
+    let contract_id = t.client.address.clone();
+    token::StellarAssetClient::new(&t.env, &t.currency).mint(&investor, &100_000);
+    token::Client::new(&t.env, &t.currency).approve(&investor, &contract_id, &100_000, &10_000);
+    investor
+}
+
+fn create_invoice(t: &Trade) -> BytesN<32> {
+    let invoice_id = t.client.store_invoice(
+        &t.business,
+        &10_000,
+        &t.currency,
+        &(t.env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&t.env, "Goods delivered"),
+        &InvoiceCategory::Products,
+        &Vec::new(&t.env),
+    );
+    t.client.set_invoice_debtor(&invoice_id, &t.debtor);
+    invoice_id
+}
+
+#[test]
+fn test_debtor_confirms_invoice() {
+    let t = setup();
+    let invoice_id = create_invoice(&t);
+    let invoice = t.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.debtor, Some(t.debtor.clone()));
+    assert!(!invoice.is_debtor_confirmed());
+
+    t.client.confirm_invoice(&invoice_id);
+    let invoice = t.client.get_invoice(&invoice_id);
+    assert!(invoice.is_debtor_confirmed());
+    assert_eq!(
+        invoice.debtor_confirmed_at,
+        Some(t.env.ledger().timestamp())
+    );
+
+    // A confirmation cannot be repeated, and the confirmed debtor cannot be replaced
+    assert_eq!(
+        t.client.try_confirm_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        t.client
+            .try_set_invoice_debtor(&invoice_id, &Address::generate(&t.env)),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_conditional_bid_waits_for_confirmation() {
+    let t = setup();
+    let invoice_id = create_invoice(&t);
+    t.client.verify_invoice(&invoice_id);
+    let cautious = setup_investor(&t);
+    let eager = setup_investor(&t);
+
+    let conditional_bid = t.client.place_bid(&cautious, &invoice_id, &9_000, &10_500);
+    let plain_bid = t.client.place_bid(&eager, &invoice_id, &9_000, &10_000);
+    t.client.set_bid_debtor_condition(&conditional_bid, &true);
+    assert!(t.client.bid_requires_debtor_confirmation(&conditional_bid));
+
+    let ranked = t.client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked.get(0).unwrap().bid_id, plain_bid);
+    assert_eq!(
+        t.client.try_accept_bid(&invoice_id, &conditional_bid),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    t.client.confirm_invoice(&invoice_id);
+    let ranked = t.client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked.get(0).unwrap().bid_id, conditional_bid);
+    assert_eq!(
+        t.client.get_best_bid(&invoice_id).unwrap().bid_id,
+        conditional_bid
+    );
+    t.client.accept_bid(&invoice_id, &conditional_bid);
+    assert_eq!(
+        t.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+}
+
+#[test]
+fn test_conditional_bid_not_bought_before_confirmation() {
+    let t = setup();
+    let invoice_id = create_invoice(&t);
+    t.client.verify_invoice(&invoice_id);
+    t.client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: None,
+            max_expected_return: None,
+            max_discount_bps: None,
+            bidding_deadline: None,
+            buy_now_price: Some(9_000),
+        },
+    );
+    let investor = setup_investor(&t);
+
+    // Meets the buy-now price, but the condition holds it back
+    let bid_id = t
+        .client
+        .place_conditional_bid(&investor, &invoice_id, &9_000, &10_000);
+    assert!(t.client.bid_requires_debtor_confirmation(&bid_id));
+    assert_eq!(
+        t.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+    assert_eq!(t.client.get_bid(&bid_id).unwrap().status, BidStatus::Placed);
+
+    t.client.confirm_invoice(&invoice_id);
+    t.client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        t.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+}
+
+fn confirmation_order_terms(t: &Trade) -> StandingOrderTerms {
+    StandingOrderTerms {
+        currency: t.currency.clone(),
+        categories: Vec::new(&t.env),
+        max_invoice_amount: 50_000,
+        max_days_to_due: 60,
+        advance_bps: 9_000,
+        return_bps: 10_000,
+        max_exposure: 50_000,
+        require_debtor_confirmation: true,
+    }
+}
+
+#[test]
+fn test_standing_order_requiring_confirmation() {
+    let t = setup();
+    let investor = setup_investor(&t);
+    t.client
+        .create_standing_order(&investor, &confirmation_order_terms(&t));
+
+    let invoice_id = create_invoice(&t);
+    t.client.verify_invoice(&invoice_id);
+    assert_eq!(t.client.get_ranked_bids(&invoice_id).len(), 0);
+
+    t.client.confirm_invoice(&invoice_id);
+    let ranked = t.client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked.get(0).unwrap().investor, investor);
+    assert_eq!(ranked.get(0).unwrap().bid_amount, 9_000);
+}
+
+#[test]
+fn test_standing_orders_wait_out_sealed_auction() {
+    let t = setup();
+    let investor = setup_investor(&t);
+    t.client
+        .create_standing_order(&investor, &confirmation_order_terms(&t));
+
+    let invoice_id = create_invoice(&t);
+    t.client.verify_invoice(&invoice_id);
+    t.client
+        .open_sealed_auction(&invoice_id, &(2 * 3600), &(2 * 3600));
+
+    // Confirming during the auction does not let the order bid in the clear
+    t.client.confirm_invoice(&invoice_id);
+    assert_eq!(t.client.get_bids_for_invoice(&invoice_id).len(), 0);
+}
+
+#[test]
+fn test_debtor_assignment_validation() {
+    let t = setup();
+    let invoice_id = t.client.store_invoice(
+        &t.business,
+        &10_000,
+        &t.currency,
+        &(t.env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&t.env, "No debtor yet"),
+        &InvoiceCategory::Products,
+        &Vec::new(&t.env),
+    );
+    assert_eq!(
+        t.client.try_confirm_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        t.client.try_set_invoice_debtor(&invoice_id, &t.business),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    t.client.cancel_invoice(&invoice_id);
+    assert_eq!(
+        t.client.try_set_invoice_debtor(&invoice_id, &t.debtor),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    let unknown = BytesN::from_array(&t.env, &[9u8; 32]);
+    assert_eq!(
+        t.client.try_confirm_invoice(&unknown),
+        Err(Ok(QuickLendXError::InvoiceNotFound))
+    );
+}
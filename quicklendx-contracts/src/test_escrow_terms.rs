@@ -0,0 +1,222 @@
+/// Test suite for escrow release conditions and timeout refunds
+///
+/// Test Coverage:
+/// 1. Debtor confirmation: funds stay held until the debtor confirms, then are released
+/// 2. Admin verification: only the admin verifying the funded invoice releases the funds
+/// 3. Timeout refund: investors reclaim held funds once the timeout has passed
+/// 4. Validation: terms are fixed before bids, and reclaims need a timeout and held funds
+use super::*;
+use crate::escrow_terms::{EscrowTerms, ReleaseCondition};
+use crate::invoice::InvoiceCategory;
+use crate::payments::EscrowStatus;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Held {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    debtor: Address,
+    currency: Address,
+}
+
+fn setup() -> Held {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    let debtor = Address::generate(&env);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Held {
+        env,
+        client,
+        business,
+        investor,
+        debtor,
+        currency,
+    }
+}
+
+/// A pending 10,000 invoice owed by `h.debtor`.
+fn create_invoice(h: &Held) -> BytesN<32> {
+    let invoice_id = h.client.store_invoice(
+        &h.business,
+        &10_000,
+        &h.currency,
+        &(h.env.ledger().timestamp() + 60 * DAY),
+        &String::from_str(&h.env, "Consignment"),
+        &InvoiceCategory::Products,
+        &Vec::new(&h.env),
+    );
+    h.client.set_invoice_debtor(&invoice_id, &h.debtor);
+    invoice_id
+}
+
+fn terms(release_condition: ReleaseCondition, refund_timeout: u64) -> EscrowTerms {
+    EscrowTerms {
+        release_condition,
+        refund_timeout,
+    }
+}
+
+fn fund(h: &Held, invoice_id: &BytesN<32>) {
+    h.client.verify_invoice(invoice_id);
+    let bid_id = h.client.place_bid(&h.investor, invoice_id, &9_000, &10_000);
+    h.client.accept_bid(invoice_id, &bid_id);
+}
+
+#[test]
+fn test_release_waits_for_debtor_confirmation() {
+    let h = setup();
+    let token_client = token::Client::new(&h.env, &h.currency);
+    let invoice_id = create_invoice(&h);
+    h.client
+        .set_escrow_terms(&invoice_id, &terms(ReleaseCondition::DebtorConfirmed, 0));
+    fund(&h, &invoice_id);
+    let business_before = token_client.balance(&h.business);
+
+    assert_eq!(
+        h.client.try_release_escrow_funds(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(h.client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+
+    // The confirmation releases the funds on its own
+    h.client.confirm_invoice(&invoice_id);
+    assert_eq!(
+        h.client.get_escrow_status(&invoice_id),
+        EscrowStatus::Released
+    );
+    assert_eq!(token_client.balance(&h.business) - business_before, 9_000);
+}
+
+#[test]
+fn test_release_on_admin_verification() {
+    let h = setup();
+    let invoice_id = create_invoice(&h);
+    h.client
+        .set_escrow_terms(&invoice_id, &terms(ReleaseCondition::InvoiceVerified, 0));
+    fund(&h, &invoice_id);
+
+    assert_eq!(
+        h.client.try_release_escrow_funds(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    h.client.verify_invoice(&invoice_id);
+    assert_eq!(
+        h.client.get_escrow_status(&invoice_id),
+        EscrowStatus::Released
+    );
+}
+
+#[test]
+fn test_investor_reclaims_after_timeout() {
+    let h = setup();
+    let token_client = token::Client::new(&h.env, &h.currency);
+    let invoice_id = create_invoice(&h);
+    h.client.set_escrow_terms(
+        &invoice_id,
+        &terms(ReleaseCondition::DebtorConfirmed, 14 * DAY),
+    );
+    fund(&h, &invoice_id);
+    let funded_at = h.env.ledger().timestamp();
+
+    h.env.ledger().set_timestamp(funded_at + 14 * DAY - 1);
+    assert_eq!(
+        h.client.try_reclaim_escrow_funds(&h.investor, &invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let outsider = Address::generate(&h.env);
+    h.env.ledger().set_timestamp(funded_at + 14 * DAY);
+    assert_eq!(
+        h.client.try_reclaim_escrow_funds(&outsider, &invoice_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    h.client.reclaim_escrow_funds(&h.investor, &invoice_id);
+    assert_eq!(token_client.balance(&h.investor), 100_000);
+    assert_eq!(
+        h.client.get_escrow_status(&invoice_id),
+        EscrowStatus::Refunded
+    );
+    assert_eq!(
+        h.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+}
+
+#[test]
+fn test_escrow_terms_validation() {
+    let h = setup();
+    let invoice_id = create_invoice(&h);
+    assert_eq!(
+        h.client.get_escrow_terms(&invoice_id),
+        terms(ReleaseCondition::OnRequest, 0)
+    );
+
+    // A debtor confirmation needs a debtor
+    let undebted = h.client.store_invoice(
+        &h.business,
+        &5_000,
+        &h.currency,
+        &(h.env.ledger().timestamp() + 60 * DAY),
+        &String::from_str(&h.env, "No debtor"),
+        &InvoiceCategory::Products,
+        &Vec::new(&h.env),
+    );
+    assert_eq!(
+        h.client
+            .try_set_escrow_terms(&undebted, &terms(ReleaseCondition::DebtorConfirmed, 0)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Investors bid on the terms they saw, so they cannot change under them
+    h.client.verify_invoice(&invoice_id);
+    let bid_id = h
+        .client
+        .place_bid(&h.investor, &invoice_id, &9_000, &10_000);
+    assert_eq!(
+        h.client
+            .try_set_escrow_terms(&invoice_id, &terms(ReleaseCondition::OnRequest, DAY)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    h.client.accept_bid(&invoice_id, &bid_id);
+
+    // Without a timeout the investor cannot reclaim, and released funds are gone
+    h.env.ledger().set_timestamp(1_000 + 365 * DAY);
+    assert_eq!(
+        h.client.try_reclaim_escrow_funds(&h.investor, &invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    h.client.release_escrow_funds(&invoice_id);
+    assert_eq!(
+        h.client.try_reclaim_escrow_funds(&h.investor, &invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
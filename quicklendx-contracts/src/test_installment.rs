@@ -0,0 +1,280 @@
+/// Test suite for installment schedules
+///
+/// Test Coverage:
+/// 1. Tracking: payments cover installments in order, on time or late
+/// 2. Overdue checks: a missed installment makes the invoice overdue and can default it
+/// 3. Amounts: shares of the invoice amount, with the last installment taking rounding
+/// 4. Validation: shares, due dates, invoice status and open bids
+use super::*;
+use crate::installment::InstallmentStatus;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Plan {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Plan {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Plan {
+        env,
+        client,
+        business,
+        investor,
+        currency,
+    }
+}
+
+fn days_from_start(days: u64) -> u64 {
+    1_000 + days * DAY
+}
+
+/// A 10,000 invoice due in 120 days.
+fn create_invoice(p: &Plan) -> BytesN<32> {
+    p.client.store_invoice(
+        &p.business,
+        &10_000,
+        &p.currency,
+        &days_from_start(120),
+        &String::from_str(&p.env, "Equipment lease"),
+        &InvoiceCategory::Services,
+        &Vec::new(&p.env),
+    )
+}
+
+/// Four quarterly installments of 25%.
+fn quarterly(p: &Plan) -> Vec<InstallmentTerms> {
+    let mut terms = Vec::new(&p.env);
+    for quarter in 1..=4 {
+        terms.push_back(InstallmentTerms {
+            due_date: days_from_start(30 * quarter),
+            share_bps: 2_500,
+        });
+    }
+    terms
+}
+
+fn fund(p: &Plan, invoice_id: &BytesN<32>) {
+    p.client.verify_invoice(invoice_id);
+    let bid_id = p.client.place_bid(&p.investor, invoice_id, &9_000, &10_000);
+    p.client.accept_bid(invoice_id, &bid_id);
+}
+
+fn statuses(p: &Plan, invoice_id: &BytesN<32>) -> Vec<InstallmentStatus> {
+    let mut statuses = Vec::new(&p.env);
+    for installment in p.client.get_installment_schedule(invoice_id).iter() {
+        statuses.push_back(installment.status);
+    }
+    statuses
+}
+
+fn pay(p: &Plan, invoice_id: &BytesN<32>, day: u64, amount: i128, reference: &str) {
+    p.env.ledger().set_timestamp(days_from_start(day));
+    p.client
+        .process_partial_payment(invoice_id, &amount, &String::from_str(&p.env, reference));
+}
+
+#[test]
+fn test_payments_cover_installments_in_order() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+    let schedule = p
+        .client
+        .set_installment_schedule(&invoice_id, &quarterly(&p));
+    assert_eq!(schedule.len(), 4);
+    assert_eq!(schedule.get(0).unwrap().amount, 2_500);
+    fund(&p, &invoice_id);
+
+    pay(&p, &invoice_id, 20, 2_500, "q1");
+    // The second installment fell due on day 60 without a payment
+    p.env.ledger().set_timestamp(days_from_start(65));
+    assert_eq!(
+        statuses(&p, &invoice_id).get(1).unwrap(),
+        InstallmentStatus::Missed
+    );
+
+    pay(&p, &invoice_id, 65, 2_500, "q2");
+    let progress = p.client.get_invoice_progress(&invoice_id);
+    assert_eq!(progress.remaining_due, 5_000);
+    assert_eq!(
+        progress.installments.get(0).unwrap().status,
+        InstallmentStatus::OnTime
+    );
+    assert_eq!(
+        progress.installments.get(1).unwrap().status,
+        InstallmentStatus::Late
+    );
+    assert_eq!(
+        progress.installments.get(1).unwrap().paid_at,
+        Some(days_from_start(65))
+    );
+    assert_eq!(
+        progress.installments.get(2).unwrap().status,
+        InstallmentStatus::Upcoming
+    );
+
+    // Paying ahead covers the remaining installments on time
+    pay(&p, &invoice_id, 80, 5_000, "q3-q4");
+    let mut expected = Vec::new(&p.env);
+    expected.push_back(InstallmentStatus::OnTime);
+    expected.push_back(InstallmentStatus::Late);
+    expected.push_back(InstallmentStatus::OnTime);
+    expected.push_back(InstallmentStatus::OnTime);
+    assert_eq!(statuses(&p, &invoice_id), expected);
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_missed_installment_makes_invoice_overdue() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+    p.client
+        .set_installment_schedule(&invoice_id, &quarterly(&p));
+    fund(&p, &invoice_id);
+
+    p.env.ledger().set_timestamp(days_from_start(29));
+    assert_eq!(p.client.check_overdue_invoices(), 0);
+
+    // Overdue well before the final due date once the first installment is missed
+    p.env.ledger().set_timestamp(days_from_start(31));
+    assert_eq!(p.client.check_overdue_invoices(), 1);
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    // A caller-chosen grace period does not cut it short
+    assert_eq!(p.client.check_overdue_invoices_grace(&0), 1);
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    // The default grace period of 7 days runs from the missed installment
+    p.env.ledger().set_timestamp(days_from_start(38));
+    p.client.check_overdue_invoices();
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Defaulted
+    );
+}
+
+#[test]
+fn test_last_installment_absorbs_rounding() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+    let mut terms = Vec::new(&p.env);
+    for (days, share_bps) in [(40, 3_333), (80, 3_333), (120, 3_334)] {
+        terms.push_back(InstallmentTerms {
+            due_date: days_from_start(days),
+            share_bps,
+        });
+    }
+    let schedule = p.client.set_installment_schedule(&invoice_id, &terms);
+    assert_eq!(schedule.get(0).unwrap().amount, 3_333);
+    assert_eq!(schedule.get(1).unwrap().amount, 3_333);
+    assert_eq!(schedule.get(2).unwrap().amount, 3_334);
+
+    // An empty schedule removes it
+    p.client
+        .set_installment_schedule(&invoice_id, &Vec::new(&p.env));
+    assert_eq!(p.client.get_installment_schedule(&invoice_id).len(), 0);
+}
+
+#[test]
+fn test_installment_schedule_validation() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+
+    let mut terms = quarterly(&p);
+    terms.set(
+        3,
+        InstallmentTerms {
+            due_date: days_from_start(120),
+            share_bps: 2_000,
+        },
+    );
+    assert_eq!(
+        p.client.try_set_installment_schedule(&invoice_id, &terms),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    let mut terms = quarterly(&p);
+    terms.set(
+        3,
+        InstallmentTerms {
+            due_date: days_from_start(121),
+            share_bps: 2_500,
+        },
+    );
+    assert_eq!(
+        p.client.try_set_installment_schedule(&invoice_id, &terms),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    let mut terms = quarterly(&p);
+    terms.set(
+        1,
+        InstallmentTerms {
+            due_date: days_from_start(30),
+            share_bps: 2_500,
+        },
+    );
+    assert_eq!(
+        p.client.try_set_installment_schedule(&invoice_id, &terms),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    // Investors bid on the schedule they saw, so it cannot change under them
+    p.client.verify_invoice(&invoice_id);
+    let bid_id = p
+        .client
+        .place_bid(&p.investor, &invoice_id, &9_000, &10_000);
+    assert_eq!(
+        p.client
+            .try_set_installment_schedule(&invoice_id, &quarterly(&p)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    p.client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        p.client
+            .try_set_installment_schedule(&invoice_id, &quarterly(&p)),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
@@ -0,0 +1,252 @@
+/// Test suite for keeper rewards on maintenance calls
+///
+/// Test Coverage:
+/// 1. Funding: the configured share of platform fees is retained in the reward pool
+/// 2. State changes: keepers are only paid when expired bids or defaults were processed
+/// 3. Limits: per-keeper rate limit, per-period cap and pool balance
+/// 4. Validation: admin only, bounty, cap, fee share and period
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::keeper::KeeperConfig;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Keeper {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Keeper {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Keeper {
+        env,
+        client,
+        admin,
+        business,
+        investor,
+        currency,
+    }
+}
+
+fn config(k: &Keeper, bounty: i128, period_cap: i128, min_interval: u64) -> KeeperConfig {
+    KeeperConfig {
+        currency: k.currency.clone(),
+        bounty,
+        fee_share_bps: 10_000,
+        min_interval,
+        period: DAY,
+        period_cap,
+    }
+}
+
+/// A verified 10,000 invoice due in 30 days.
+fn verified_invoice(k: &Keeper) -> BytesN<32> {
+    let invoice_id = k.client.store_invoice(
+        &k.business,
+        &10_000,
+        &k.currency,
+        &(k.env.ledger().timestamp() + 30 * DAY),
+        &String::from_str(&k.env, "Freight"),
+        &InvoiceCategory::Services,
+        &Vec::new(&k.env),
+    );
+    k.client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+fn funded_invoice(k: &Keeper) -> BytesN<32> {
+    let invoice_id = verified_invoice(k);
+    let bid_id = k
+        .client
+        .place_bid(&k.investor, &invoice_id, &9_000, &10_000);
+    k.client.accept_bid(&invoice_id, &bid_id);
+    invoice_id
+}
+
+/// Settle a funded invoice so its platform fee goes into the pool. Returns the fee.
+fn fund_pool(k: &Keeper) -> i128 {
+    let invoice_id = funded_invoice(k);
+    k.client.settle_invoice(&invoice_id, &10_000);
+    let (_, platform_fee) = k.client.calculate_profit(&9_000, &10_000);
+    platform_fee
+}
+
+/// A verified invoice holding one placed bid, which expires after 7 days.
+fn invoice_with_bid(k: &Keeper) -> BytesN<32> {
+    let invoice_id = verified_invoice(k);
+    k.client
+        .place_bid(&k.investor, &invoice_id, &9_000, &10_000);
+    invoice_id
+}
+
+fn advance(k: &Keeper, seconds: u64) {
+    k.env
+        .ledger()
+        .set_timestamp(k.env.ledger().timestamp() + seconds);
+}
+
+#[test]
+fn test_fee_share_funds_reward_pool() {
+    let k = setup();
+    let mut half = config(&k, 10, 100, 0);
+    half.fee_share_bps = 5_000;
+    k.client.set_keeper_config(&k.admin, &half);
+    assert_eq!(k.client.get_keeper_config(), Some(half));
+    assert_eq!(k.client.get_keeper_reward_pool(), 0);
+
+    let platform_fee = fund_pool(&k);
+    assert!(platform_fee > 0);
+    assert_eq!(k.client.get_keeper_reward_pool(), platform_fee / 2);
+}
+
+#[test]
+fn test_keeper_paid_only_for_state_changes() {
+    let k = setup();
+    let token_client = token::Client::new(&k.env, &k.currency);
+    k.client.set_keeper_config(&k.admin, &config(&k, 5, 100, 0));
+    let pool = fund_pool(&k);
+    let keeper = Address::generate(&k.env);
+
+    // Nothing to clean up yet, so nothing is paid
+    let invoice_id = invoice_with_bid(&k);
+    assert_eq!(
+        k.client.keeper_cleanup_expired_bids(&keeper, &invoice_id),
+        0
+    );
+    assert_eq!(k.client.keeper_cleanup_backups(&keeper), 0);
+    assert_eq!(k.client.keeper_check_overdue_invoices(&keeper), 0);
+    assert_eq!(token_client.balance(&keeper), 0);
+
+    advance(&k, 8 * DAY);
+    assert_eq!(
+        k.client.keeper_cleanup_expired_bids(&keeper, &invoice_id),
+        1
+    );
+    assert_eq!(token_client.balance(&keeper), 5);
+
+    // A funded invoice past its due date and grace period defaults
+    let overdue = funded_invoice(&k);
+    advance(&k, 40 * DAY);
+    assert_eq!(k.client.keeper_check_overdue_invoices(&keeper), 1);
+    assert_eq!(
+        k.client.get_invoice(&overdue).status,
+        InvoiceStatus::Defaulted
+    );
+    assert_eq!(token_client.balance(&keeper), 10);
+    assert_eq!(k.client.get_keeper_reward_pool(), pool - 10);
+}
+
+#[test]
+fn test_rate_limit_and_period_cap() {
+    let k = setup();
+    let token_client = token::Client::new(&k.env, &k.currency);
+    k.client
+        .set_keeper_config(&k.admin, &config(&k, 4, 6, DAY / 2));
+    fund_pool(&k);
+    let first = Address::generate(&k.env);
+    let second = Address::generate(&k.env);
+
+    let a = invoice_with_bid(&k);
+    let b = invoice_with_bid(&k);
+    let c = invoice_with_bid(&k);
+    advance(&k, 8 * DAY);
+    assert_eq!(k.client.keeper_cleanup_expired_bids(&first, &a), 1);
+    assert_eq!(token_client.balance(&first), 4);
+
+    // The cleanup still runs, but the same keeper is not paid again so soon
+    assert_eq!(k.client.keeper_cleanup_expired_bids(&first, &b), 1);
+    assert_eq!(token_client.balance(&first), 4);
+
+    // Only what is left of the period's cap is paid out
+    assert_eq!(k.client.keeper_cleanup_expired_bids(&second, &c), 1);
+    assert_eq!(token_client.balance(&second), 2);
+
+    // The cap resets with the next period
+    let d = invoice_with_bid(&k);
+    advance(&k, 8 * DAY);
+    assert_eq!(k.client.keeper_cleanup_expired_bids(&second, &d), 1);
+    assert_eq!(token_client.balance(&second), 6);
+}
+
+#[test]
+fn test_keeper_config_validation() {
+    let k = setup();
+    let outsider = Address::generate(&k.env);
+    assert_eq!(
+        k.client
+            .try_set_keeper_config(&outsider, &config(&k, 10, 100, 0)),
+        Err(Ok(QuickLendXError::NotAdmin))
+    );
+    assert_eq!(
+        k.client
+            .try_set_keeper_config(&k.admin, &config(&k, 0, 100, 0)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        k.client
+            .try_set_keeper_config(&k.admin, &config(&k, 10, 5, 0)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let mut too_much = config(&k, 10, 100, 0);
+    too_much.fee_share_bps = 10_001;
+    assert_eq!(
+        k.client.try_set_keeper_config(&k.admin, &too_much),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let mut no_period = config(&k, 10, 100, 0);
+    no_period.period = 0;
+    assert_eq!(
+        k.client.try_set_keeper_config(&k.admin, &no_period),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    // Without a configuration or an empty pool, cleanups run unpaid
+    let keeper = Address::generate(&k.env);
+    let unconfigured = invoice_with_bid(&k);
+    let empty_pool = invoice_with_bid(&k);
+    advance(&k, 8 * DAY);
+    assert_eq!(
+        k.client.keeper_cleanup_expired_bids(&keeper, &unconfigured),
+        1
+    );
+    k.client
+        .set_keeper_config(&k.admin, &config(&k, 10, 100, 0));
+    assert_eq!(
+        k.client.keeper_cleanup_expired_bids(&keeper, &empty_pool),
+        1
+    );
+    assert_eq!(token::Client::new(&k.env, &k.currency).balance(&keeper), 0);
+}
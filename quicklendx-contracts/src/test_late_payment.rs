@@ -0,0 +1,224 @@
+/// Test suite for late-payment interest
+///
+/// Test Coverage:
+/// 1. Accrual: interest accrues daily after the due date and shows in the amount due
+/// 2. Settlement: interest is collected from the business and shared with the platform
+/// 3. Partial payments: paying down the invoice slows accrual
+/// 4. Limits: accrual stops at the grace deadline, is capped, and needs its own config
+/// 5. Validation: admin only and rates up to 100%
+use super::*;
+use crate::fees::FeeType;
+use crate::invoice::InvoiceCategory;
+use crate::late_payment::LateInterestConfig;
+use crate::settlement::get_invoice_progress;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Overdue {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Overdue {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    client.initialize_fee_system(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Overdue {
+        env,
+        client,
+        admin,
+        business,
+        investor,
+        currency,
+    }
+}
+
+/// 1% a day, capped at `max_interest_bps` of the invoice amount.
+fn charge_late_interest(o: &Overdue, max_interest_bps: u32) {
+    o.client.set_late_interest_config(
+        &o.admin,
+        &LateInterestConfig {
+            daily_rate_bps: 100,
+            max_interest_bps,
+        },
+    );
+}
+
+/// A 10,000 invoice due in 30 days, funded with 9,000. Returns it with its due date.
+fn funded_invoice(o: &Overdue) -> (BytesN<32>, u64) {
+    let due_date = o.env.ledger().timestamp() + 30 * DAY;
+    let invoice_id = o.client.store_invoice(
+        &o.business,
+        &10_000,
+        &o.currency,
+        &due_date,
+        &String::from_str(&o.env, "Monthly delivery"),
+        &InvoiceCategory::Services,
+        &Vec::new(&o.env),
+    );
+    o.client.verify_invoice(&invoice_id);
+    let bid_id = o
+        .client
+        .place_bid(&o.investor, &invoice_id, &9_000, &10_000);
+    o.client.accept_bid(&invoice_id, &bid_id);
+    (invoice_id, due_date)
+}
+
+fn remaining_due(o: &Overdue, invoice_id: &BytesN<32>) -> i128 {
+    o.env.as_contract(&o.client.address, || {
+        get_invoice_progress(&o.env, invoice_id)
+            .unwrap()
+            .remaining_due
+    })
+}
+
+#[test]
+fn test_interest_accrues_after_due_date() {
+    let o = setup();
+    charge_late_interest(&o, 5_000);
+    let (invoice_id, due_date) = funded_invoice(&o);
+
+    o.env.ledger().set_timestamp(due_date);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 0);
+    assert_eq!(remaining_due(&o, &invoice_id), 10_000);
+
+    o.env.ledger().set_timestamp(due_date + 3 * DAY);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 300);
+    let progress = o.env.as_contract(&o.client.address, || {
+        get_invoice_progress(&o.env, &invoice_id).unwrap()
+    });
+    assert_eq!(progress.total_due, 10_300);
+    assert_eq!(progress.remaining_due, 10_300);
+}
+
+#[test]
+fn test_settlement_collects_interest_for_investor_and_platform() {
+    let o = setup();
+    let token_client = token::Client::new(&o.env, &o.currency);
+    charge_late_interest(&o, 5_000);
+    let (invoice_id, due_date) = funded_invoice(&o);
+    o.env.ledger().set_timestamp(due_date + 3 * DAY);
+
+    assert_eq!(
+        o.client.try_settle_invoice(&invoice_id, &10_000),
+        Err(Ok(QuickLendXError::PaymentTooLow))
+    );
+
+    let business_before = token_client.balance(&o.business);
+    let investor_before = token_client.balance(&o.investor);
+    o.client.settle_invoice(&invoice_id, &10_300);
+    assert_eq!(business_before - token_client.balance(&o.business), 10_300);
+    // The platform takes its 2% of the 1,300 profit, interest included
+    assert_eq!(token_client.balance(&o.investor) - investor_before, 10_274);
+
+    let invoice = o.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.total_paid, 10_300);
+    // Interest stops once the invoice is paid
+    o.env.ledger().set_timestamp(due_date + 5 * DAY);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 300);
+}
+
+#[test]
+fn test_partial_payments_reduce_accrual() {
+    let o = setup();
+    charge_late_interest(&o, 5_000);
+    let (invoice_id, due_date) = funded_invoice(&o);
+
+    // An hour late accrues 4
+    o.env.ledger().set_timestamp(due_date + 3_600);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 4);
+
+    o.env.ledger().set_timestamp(due_date + 2 * DAY);
+    o.client
+        .process_partial_payment(&invoice_id, &5_000, &String::from_str(&o.env, "late-1"));
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 200);
+
+    // The remaining 5,000 accrues 50 a day
+    o.env.ledger().set_timestamp(due_date + 4 * DAY);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 300);
+    assert_eq!(remaining_due(&o, &invoice_id), 5_300);
+
+    o.client
+        .process_partial_payment(&invoice_id, &5_300, &String::from_str(&o.env, "late-2"));
+    let invoice = o.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.total_paid, 10_300);
+}
+
+#[test]
+fn test_interest_limits() {
+    let o = setup();
+    let (invoice_id, due_date) = funded_invoice(&o);
+
+    // The flat LatePayment fee structure does not make interest accrue
+    o.client
+        .update_fee_structure(&o.admin, &FeeType::LatePayment, &100, &50, &5_000, &true);
+    o.env.ledger().set_timestamp(due_date + 3 * DAY);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 0);
+
+    // Accrual stops at the 7-day grace deadline
+    charge_late_interest(&o, 5_000);
+    o.env.ledger().set_timestamp(due_date + 20 * DAY);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 700);
+    assert_eq!(remaining_due(&o, &invoice_id), 10_700);
+
+    // Capped at 5% of the invoice amount
+    charge_late_interest(&o, 500);
+    assert_eq!(o.client.get_late_payment_interest(&invoice_id), 500);
+}
+
+#[test]
+fn test_late_interest_config_validation() {
+    let o = setup();
+    let config = LateInterestConfig {
+        daily_rate_bps: 100,
+        max_interest_bps: 1_000,
+    };
+    assert_eq!(
+        o.client
+            .try_set_late_interest_config(&Address::generate(&o.env), &config),
+        Err(Ok(QuickLendXError::NotAdmin))
+    );
+    let mut too_high = config.clone();
+    too_high.max_interest_bps = 10_001;
+    assert_eq!(
+        o.client.try_set_late_interest_config(&o.admin, &too_high),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(o.client.get_late_interest_config(), None);
+    o.client.set_late_interest_config(&o.admin, &config);
+    assert_eq!(o.client.get_late_interest_config(), Some(config));
+}
@@ -0,0 +1,211 @@
+/// Test suite for business-defined listing terms
+///
+/// Test Coverage:
+/// 1. Enforcement: minimum advance, return/discount caps and deadline at place_bid
+/// 2. Buy-now: a qualifying bid funds the invoice through escrow immediately
+/// 3. Validation: inconsistent terms and closed invoices are rejected
+use super::*;
+use crate::bid::BidStatus;
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::payments::EscrowStatus;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+fn setup() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    BytesN<32>,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    token::StellarAssetClient::new(&env, &currency).mint(&investor, &100_000);
+    token::Client::new(&env, &currency).approve(&investor, &contract_id, &100_000, &10_000);
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Listed invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    (env, client, invoice_id, investor, currency)
+}
+
+fn no_terms() -> ListingTerms {
+    ListingTerms {
+        min_advance: None,
+        max_expected_return: None,
+        max_discount_bps: None,
+        bidding_deadline: None,
+        buy_now_price: None,
+    }
+}
+
+#[test]
+fn test_bids_violating_terms_are_rejected() {
+    let (env, client, invoice_id, investor, _currency) = setup();
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: Some(8_000),
+            max_expected_return: Some(9_500),
+            max_discount_bps: Some(1_500),
+            bidding_deadline: Some(2_000),
+            ..no_terms()
+        },
+    );
+
+    // Below the minimum advance
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_id, &7_000, &8_000),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    // Above the maximum expected return
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_id, &9_000, &9_600),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    // 8,000 on a 10,000 face value is a 20% discount, above the 15% cap
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_id, &8_000, &9_000),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    client.place_bid(&investor, &invoice_id, &8_500, &9_400);
+
+    env.ledger().set_timestamp(2_001);
+    client.clear_listing_terms(&invoice_id);
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            bidding_deadline: Some(2_500),
+            ..no_terms()
+        },
+    );
+    env.ledger().set_timestamp(2_501);
+    let late_investor = Address::generate(&env);
+    client.submit_investor_kyc(&late_investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&late_investor, &50_000);
+    assert_eq!(
+        client.try_place_bid(&late_investor, &invoice_id, &8_500, &9_400),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_buy_now_bid_funds_immediately() {
+    let (env, client, invoice_id, investor, currency) = setup();
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: Some(8_000),
+            buy_now_price: Some(9_000),
+            ..no_terms()
+        },
+    );
+
+    // Below buy-now: stays a regular bid
+    let regular = client.place_bid(&investor, &invoice_id, &8_500, &9_400);
+    assert_eq!(client.get_invoice(&invoice_id).status, InvoiceStatus::Verified);
+    client.withdraw_bid(&regular);
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &9_800);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.funded_amount, 9_000);
+    assert_eq!(invoice.investor, Some(investor.clone()));
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Accepted);
+    assert_eq!(client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+    assert_eq!(client.get_invoice_investment(&invoice_id).amount, 9_000);
+    assert_eq!(
+        token::Client::new(&env, &currency).balance(&client.address),
+        9_000
+    );
+}
+
+#[test]
+fn test_listing_terms_validation() {
+    let (env, client, invoice_id, investor, _currency) = setup();
+
+    assert_eq!(
+        client.try_set_listing_terms(
+            &invoice_id,
+            &ListingTerms {
+                min_advance: Some(9_000),
+                buy_now_price: Some(8_000),
+                ..no_terms()
+            },
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_listing_terms(
+            &invoice_id,
+            &ListingTerms {
+                min_advance: Some(10_001),
+                ..no_terms()
+            },
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_listing_terms(
+            &invoice_id,
+            &ListingTerms {
+                max_discount_bps: Some(10_001),
+                ..no_terms()
+            },
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_listing_terms(
+            &invoice_id,
+            &ListingTerms {
+                bidding_deadline: Some(env.ledger().timestamp()),
+                ..no_terms()
+            },
+        ),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert!(client.get_listing_terms(&invoice_id).is_none());
+
+    let terms = ListingTerms {
+        min_advance: Some(5_000),
+        ..no_terms()
+    };
+    client.set_listing_terms(&invoice_id, &terms);
+    assert_eq!(client.get_listing_terms(&invoice_id), Some(terms.clone()));
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &9_800);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        client.try_set_listing_terms(&invoice_id, &terms),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
@@ -0,0 +1,244 @@
+/// Test suite for milestone-based escrow releases
+///
+/// Test Coverage:
+/// 1. Staged releases: each approval releases its milestone and is audited
+/// 2. Refunds: only the balance still held goes back; the released part stays owed
+/// 3. Approvers: each milestone is released once, by its own approver
+/// 4. Validation: project invoices only, amounts, debtors and splits after a release
+use super::*;
+use crate::audit::AuditOperation;
+use crate::invoice::InvoiceCategory;
+use crate::milestone::{MilestoneApprover, MilestoneTerms};
+use crate::payments::EscrowStatus;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Project {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    business: Address,
+    investor: Address,
+    debtor: Address,
+    currency: Address,
+}
+
+fn setup() -> Project {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    let debtor = Address::generate(&env);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Project {
+        env,
+        client,
+        admin,
+        business,
+        investor,
+        debtor,
+        currency,
+    }
+}
+
+/// A 10,000 invoice of `category` owed by `p.debtor`, funded with 9,000. Returns it
+/// with its escrow id.
+fn funded_invoice(p: &Project, category: InvoiceCategory) -> (BytesN<32>, BytesN<32>) {
+    let invoice_id = p.client.store_invoice(
+        &p.business,
+        &10_000,
+        &p.currency,
+        &(p.env.ledger().timestamp() + 90 * 86_400),
+        &String::from_str(&p.env, "Tooling build-out"),
+        &category,
+        &Vec::new(&p.env),
+    );
+    p.client.set_invoice_debtor(&invoice_id, &p.debtor);
+    p.client.verify_invoice(&invoice_id);
+    let bid_id = p
+        .client
+        .place_bid(&p.investor, &invoice_id, &9_000, &10_000);
+    p.client.accept_bid(&invoice_id, &bid_id);
+    let escrow_id = p.client.get_escrow_details(&invoice_id).escrow_id;
+    (invoice_id, escrow_id)
+}
+
+/// 3,000 on the investor's approval, 4,000 on the admin's, 2,000 on the debtor's.
+fn three_stages(p: &Project) -> Vec<MilestoneTerms> {
+    let mut terms = Vec::new(&p.env);
+    for (amount, approver) in [
+        (3_000, MilestoneApprover::Investor),
+        (4_000, MilestoneApprover::Admin),
+        (2_000, MilestoneApprover::Debtor),
+    ] {
+        terms.push_back(MilestoneTerms { amount, approver });
+    }
+    terms
+}
+
+#[test]
+fn test_milestones_release_in_stages() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let (invoice_id, escrow_id) = funded_invoice(&p, InvoiceCategory::Manufacturing);
+    p.client
+        .set_escrow_milestones(&escrow_id, &three_stages(&p));
+    let business_before = token_client.balance(&p.business);
+
+    // The escrow no longer goes out in one transfer
+    assert_eq!(
+        p.client.try_release_escrow_funds(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    p.client.approve_milestone(&p.investor, &escrow_id, &0);
+    assert_eq!(token_client.balance(&p.business) - business_before, 3_000);
+    assert_eq!(p.client.get_escrow_held_balance(&escrow_id), 6_000);
+    assert_eq!(p.client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+    let milestones = p.client.get_escrow_milestones(&escrow_id);
+    assert_eq!(milestones.get(0).unwrap().released_at, Some(1_000));
+    assert_eq!(milestones.get(1).unwrap().released_at, None);
+
+    p.client.approve_milestone(&p.debtor, &escrow_id, &2);
+    p.client.approve_milestone(&p.admin, &escrow_id, &1);
+    assert_eq!(token_client.balance(&p.business) - business_before, 9_000);
+    assert_eq!(p.client.get_escrow_held_balance(&escrow_id), 0);
+    assert_eq!(
+        p.client.get_escrow_status(&invoice_id),
+        EscrowStatus::Released
+    );
+    // Each release is audited on its own
+    let releases = p
+        .client
+        .get_audit_entries_by_operation(&AuditOperation::EscrowReleased);
+    assert_eq!(releases.len(), 3);
+}
+
+#[test]
+fn test_refund_returns_only_the_held_balance() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let (invoice_id, escrow_id) = funded_invoice(&p, InvoiceCategory::Consulting);
+    p.client
+        .set_escrow_milestones(&escrow_id, &three_stages(&p));
+    p.client.approve_milestone(&p.investor, &escrow_id, &0);
+    let investor_before = token_client.balance(&p.investor);
+
+    p.client.refund_escrow_funds(&invoice_id, &p.business);
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 6_000);
+    assert_eq!(p.client.get_escrow_held_balance(&escrow_id), 0);
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+    assert_eq!(
+        p.client.try_approve_milestone(&p.admin, &escrow_id, &1),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // The released 3,000 stays owed by the business, and the position open until repaid
+    let obligation = p.client.get_recourse_obligation(&invoice_id).unwrap();
+    assert_eq!(obligation.total_amount, 3_000);
+    assert_eq!(
+        p.client.get_invoice_investment(&invoice_id).status,
+        InvestmentStatus::Active
+    );
+    p.client.repay_recourse(&p.business, &invoice_id, &3_000);
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 9_000);
+    assert_eq!(
+        p.client.get_invoice_investment(&invoice_id).status,
+        InvestmentStatus::Completed
+    );
+}
+
+#[test]
+fn test_milestones_need_their_own_approver() {
+    let p = setup();
+    let (_, escrow_id) = funded_invoice(&p, InvoiceCategory::Manufacturing);
+    p.client
+        .set_escrow_milestones(&escrow_id, &three_stages(&p));
+
+    assert_eq!(
+        p.client.try_approve_milestone(&p.admin, &escrow_id, &0),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        p.client.try_approve_milestone(&p.investor, &escrow_id, &2),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        p.client.try_approve_milestone(&p.admin, &escrow_id, &3),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    p.client.approve_milestone(&p.admin, &escrow_id, &1);
+    assert_eq!(
+        p.client.try_approve_milestone(&p.admin, &escrow_id, &1),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_milestone_validation() {
+    let p = setup();
+    let (_, services_escrow) = funded_invoice(&p, InvoiceCategory::Services);
+    assert_eq!(
+        p.client
+            .try_set_escrow_milestones(&services_escrow, &three_stages(&p)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let (_, escrow_id) = funded_invoice(&p, InvoiceCategory::Manufacturing);
+    let mut short = three_stages(&p);
+    short.set(
+        2,
+        MilestoneTerms {
+            amount: 1_000,
+            approver: MilestoneApprover::Debtor,
+        },
+    );
+    assert_eq!(
+        p.client.try_set_escrow_milestones(&escrow_id, &short),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        p.client
+            .try_set_escrow_milestones(&escrow_id, &Vec::new(&p.env)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    // The split can be replaced until part of it is released
+    p.client
+        .set_escrow_milestones(&escrow_id, &three_stages(&p));
+    p.client
+        .set_escrow_milestones(&escrow_id, &three_stages(&p));
+    p.client.approve_milestone(&p.investor, &escrow_id, &0);
+    assert_eq!(
+        p.client
+            .try_set_escrow_milestones(&escrow_id, &three_stages(&p)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
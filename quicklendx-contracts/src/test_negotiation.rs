@@ -0,0 +1,239 @@
+/// Test suite for counter-offer negotiation
+///
+/// Test Coverage:
+/// 1. Agreement: accepting a counter-offer funds the invoice at the agreed terms
+/// 2. Turns: parties alternate, only the business opens, outsiders are rejected
+/// 3. Bounds: round limit and offer expiry
+/// 4. Rejection: ends the negotiation and leaves the bid unchanged
+/// 5. Validation: agreed terms are re-checked like a new bid, including listing terms
+use super::*;
+use crate::bid::BidStatus;
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::listing::ListingTerms;
+use crate::negotiation::{NegotiationParty, NegotiationStatus, MAX_NEGOTIATION_ROUNDS};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const HOUR: u64 = 60 * 60;
+
+fn setup() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    Address,
+    Address,
+    Address,
+    BytesN<32>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    token::StellarAssetClient::new(&env, &currency).mint(&investor, &100_000);
+    token::Client::new(&env, &currency).approve(&investor, &contract_id, &100_000, &10_000);
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Negotiated invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    (env, client, business, investor, currency, invoice_id)
+}
+
+#[test]
+fn test_investor_accepts_business_counter_offer() {
+    let (env, client, business, investor, currency, invoice_id) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+
+    let offer = client.counter_offer(&business, &bid_id, &8_500, &9_300, &(24 * HOUR));
+    assert_eq!(offer.round, 1);
+    assert_eq!(offer.proposed_by, NegotiationParty::Business);
+    assert_eq!(offer.expires_at, 1_000 + 24 * HOUR);
+
+    client.accept_counter_offer(&investor, &bid_id);
+
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.status, BidStatus::Accepted);
+    assert_eq!(bid.bid_amount, 8_500);
+    assert_eq!(bid.expected_return, 9_300);
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.funded_amount, 8_500);
+    assert_eq!(client.get_invoice_investment(&invoice_id).amount, 8_500);
+    assert_eq!(
+        token::Client::new(&env, &currency).balance(&client.address),
+        8_500
+    );
+    assert_eq!(
+        client.get_negotiation(&bid_id).unwrap().status,
+        NegotiationStatus::Accepted
+    );
+    assert_eq!(
+        client.try_counter_offer(&business, &bid_id, &8_600, &9_300, &HOUR),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_parties_alternate_and_business_accepts_investor_counter() {
+    let (env, client, business, investor, _currency, invoice_id) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+    let outsider = Address::generate(&env);
+
+    // Only the business can open a negotiation; outsiders never take part
+    assert_eq!(
+        client.try_counter_offer(&investor, &bid_id, &8_000, &9_400, &HOUR),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_counter_offer(&outsider, &bid_id, &8_000, &9_400, &HOUR),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    client.counter_offer(&business, &bid_id, &8_800, &9_200, &HOUR);
+    // Nobody answers their own offer
+    assert_eq!(
+        client.try_counter_offer(&business, &bid_id, &8_900, &9_200, &HOUR),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_accept_counter_offer(&business, &bid_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    // Proposed terms are validated like a bid
+    assert_eq!(
+        client.try_counter_offer(&investor, &bid_id, &8_500, &8_000, &HOUR),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    let offer = client.counter_offer(&investor, &bid_id, &8_400, &9_300, &HOUR);
+    assert_eq!(offer.round, 2);
+    assert_eq!(offer.proposed_by, NegotiationParty::Investor);
+
+    client.accept_counter_offer(&business, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    assert_eq!(investment.amount, 8_400);
+    assert_eq!(client.get_bid(&bid_id).unwrap().expected_return, 9_300);
+}
+
+#[test]
+fn test_round_limit_and_offer_expiry() {
+    let (env, client, business, investor, _currency, invoice_id) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+
+    assert_eq!(
+        client.try_counter_offer(&business, &bid_id, &8_500, &9_300, &60),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_counter_offer(&business, &bid_id, &8_500, &9_300, &(8 * 24 * HOUR)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    for round in 1..=MAX_NEGOTIATION_ROUNDS {
+        let caller = if round % 2 == 1 { &business } else { &investor };
+        client.counter_offer(caller, &bid_id, &(8_000 + round as i128), &9_300, &HOUR);
+    }
+    let caller = if MAX_NEGOTIATION_ROUNDS % 2 == 1 {
+        &investor
+    } else {
+        &business
+    };
+    assert_eq!(
+        client.try_counter_offer(caller, &bid_id, &8_500, &9_300, &HOUR),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Once the last offer expires it cannot be accepted, but the business may start over
+    env.ledger().set_timestamp(1_000 + HOUR + 1);
+    assert_eq!(
+        client.try_accept_counter_offer(caller, &bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let offer = client.counter_offer(&business, &bid_id, &8_700, &9_300, &HOUR);
+    assert_eq!(offer.round, 1);
+}
+
+#[test]
+fn test_rejection_keeps_original_bid() {
+    let (env, client, business, investor, _currency, invoice_id) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+    client.counter_offer(&business, &bid_id, &8_500, &9_300, &HOUR);
+
+    assert_eq!(
+        client.try_reject_counter_offer(&Address::generate(&env), &bid_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    client.reject_counter_offer(&investor, &bid_id);
+
+    assert_eq!(
+        client.get_negotiation(&bid_id).unwrap().status,
+        NegotiationStatus::Rejected
+    );
+    assert_eq!(
+        client.try_accept_counter_offer(&investor, &bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.status, BidStatus::Placed);
+    assert_eq!(bid.bid_amount, 8_000);
+
+    // The original bid can still be accepted as placed
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(client.get_invoice_investment(&invoice_id).amount, 8_000);
+}
+
+#[test]
+fn test_accepted_terms_are_checked_against_listing_terms() {
+    let (_env, client, business, investor, _currency, invoice_id) = setup();
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+    client.counter_offer(&business, &bid_id, &8_500, &9_300, &HOUR);
+
+    // The listing was tightened after the offer was made
+    client.set_listing_terms(
+        &invoice_id,
+        &ListingTerms {
+            min_advance: Some(9_000),
+            max_expected_return: None,
+            max_discount_bps: None,
+            bidding_deadline: None,
+            buy_now_price: None,
+        },
+    );
+    assert_eq!(
+        client.try_accept_counter_offer(&investor, &bid_id),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.get_negotiation(&bid_id).unwrap().status,
+        NegotiationStatus::Open
+    );
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.status, BidStatus::Placed);
+    assert_eq!(bid.bid_amount, 8_000);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+}
@@ -0,0 +1,320 @@
+/// Test suite for tranched invoice pools
+///
+/// Test Coverage:
+/// 1. Funding: filling both tranches funds every pooled invoice through escrow and investments
+/// 2. Waterfall: settlement proceeds pay the senior tranche first, then the junior tranche
+/// 3. Losses: defaults reduce the junior tranche before the senior one
+/// 4. Validation: pool terms, reserved invoices, subscription caps and cancellation refunds
+/// 5. Expiry: unfilled pools can be expired by anyone once the subscription deadline passes
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::pool::PoolStatus;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const SUBSCRIPTION_PERIOD: u64 = 7 * 86400;
+
+struct PoolFixture {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    currency: Address,
+    invoice_ids: Vec<BytesN<32>>,
+}
+
+/// Two Verified 10,000 invoices in a whitelisted currency.
+fn setup() -> PoolFixture {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    token::StellarAssetClient::new(&env, &currency).mint(&business, &100_000);
+    token::Client::new(&env, &currency).approve(&business, &contract_id, &100_000, &10_000);
+
+    let mut invoice_ids = Vec::new(&env);
+    for _ in 0..2 {
+        let invoice_id = client.store_invoice(
+            &business,
+            &10_000,
+            &currency,
+            &(env.ledger().timestamp() + 30 * 86400),
+            &String::from_str(&env, "Pooled invoice"),
+            &InvoiceCategory::Services,
+            &Vec::new(&env),
+        );
+        client.verify_invoice(&invoice_id);
+        invoice_ids.push_back(invoice_id);
+    }
+
+    PoolFixture {
+        env,
+        client,
+        admin,
+        currency,
+        invoice_ids,
+    }
+}
+
+fn setup_investor(f: &PoolFixture) -> Address {
+    let investor = Address::generate(&f.env);
+    f.client
+        .submit_investor_kyc(&investor, &String::from_str(&f.env, "Investor KYC"));
+    f.client.verify_investor(&investor, &50_000);
+    token::StellarAssetClient::new(&f.env, &f.currency).mint(&investor, &100_000);
+    token::Client::new(&f.env, &f.currency).approve(
+        &investor,
+        &f.client.address,
+        &100_000,
+        &10_000,
+    );
+    investor
+}
+
+/// 90% advance: an 18,000 pool split 12,600 senior at 5% / 5,400 junior at 15%.
+fn terms(f: &PoolFixture) -> PoolTerms {
+    PoolTerms {
+        currency: f.currency.clone(),
+        advance_bps: 9_000,
+        senior_bps: 7_000,
+        senior_return_bps: 500,
+        junior_return_bps: 1_500,
+        subscription_period: SUBSCRIPTION_PERIOD,
+    }
+}
+
+/// Fund a pool with one senior investor and one junior investor.
+fn fund_pool(f: &PoolFixture) -> (u64, Address, Address) {
+    let pool_id = f
+        .client
+        .create_invoice_pool(&f.admin, &f.invoice_ids, &terms(f));
+    let senior = setup_investor(f);
+    let junior = setup_investor(f);
+    f.client
+        .subscribe_to_pool(&senior, &pool_id, &TrancheKind::Senior, &12_600);
+    f.client
+        .subscribe_to_pool(&junior, &pool_id, &TrancheKind::Junior, &5_400);
+    (pool_id, senior, junior)
+}
+
+#[test]
+fn test_full_subscription_funds_pooled_invoices() {
+    let f = setup();
+    let (pool_id, senior, junior) = fund_pool(&f);
+
+    let pool = f.client.get_invoice_pool(&pool_id).unwrap();
+    assert_eq!(pool.status, PoolStatus::Funded);
+    assert_eq!(pool.total_size, 18_000);
+    assert_eq!(pool.senior.size, 12_600);
+    assert_eq!(pool.junior.size, 5_400);
+    for invoice_id in f.invoice_ids.iter() {
+        let invoice = f.client.get_invoice(&invoice_id);
+        assert_eq!(invoice.status, InvoiceStatus::Funded);
+        assert_eq!(invoice.funded_amount, 9_000);
+        assert_eq!(f.client.get_pool_of_invoice(&invoice_id), Some(pool_id));
+        assert_eq!(f.client.get_escrow_details(&invoice_id).amount, 9_000);
+    }
+    assert_eq!(
+        f.client
+            .get_pool_position(&pool_id, &senior)
+            .unwrap()
+            .senior,
+        12_600
+    );
+    assert_eq!(
+        f.client
+            .get_pool_position(&pool_id, &junior)
+            .unwrap()
+            .junior,
+        5_400
+    );
+}
+
+#[test]
+fn test_settlement_pays_senior_first_then_junior() {
+    let f = setup();
+    let token_client = token::Client::new(&f.env, &f.currency);
+    let (pool_id, senior, junior) = fund_pool(&f);
+    let (investor_return, _) = f.client.calculate_profit(&9_000, &10_000);
+
+    // The first settlement does not cover the senior entitlement: junior gets nothing yet
+    f.client
+        .settle_invoice(&f.invoice_ids.get(0).unwrap(), &10_000);
+    let waterfall = f.client.get_pool_waterfall(&pool_id);
+    assert_eq!(waterfall.collected, investor_return);
+    assert_eq!(waterfall.senior_due, 13_230);
+    assert_eq!(waterfall.senior_available, investor_return);
+    assert_eq!(waterfall.junior_available, 0);
+    assert_eq!(f.client.claim_pool_proceeds(&junior, &pool_id), 0);
+
+    let senior_before = token_client.balance(&senior);
+    assert_eq!(
+        f.client.claim_pool_proceeds(&senior, &pool_id),
+        investor_return
+    );
+    assert_eq!(
+        token_client.balance(&senior) - senior_before,
+        investor_return
+    );
+
+    // Once the senior tranche is whole, the rest goes to the junior tranche
+    f.client
+        .settle_invoice(&f.invoice_ids.get(1).unwrap(), &10_000);
+    let waterfall = f.client.get_pool_waterfall(&pool_id);
+    assert_eq!(waterfall.senior_available, 13_230);
+    assert_eq!(waterfall.junior_available, 2 * investor_return - 13_230);
+    assert_eq!(
+        f.client.claim_pool_proceeds(&senior, &pool_id),
+        13_230 - investor_return
+    );
+    let junior_before = token_client.balance(&junior);
+    f.client.claim_pool_proceeds(&junior, &pool_id);
+    assert_eq!(
+        token_client.balance(&junior) - junior_before,
+        2 * investor_return - 13_230
+    );
+}
+
+#[test]
+fn test_default_losses_hit_junior_first() {
+    let f = setup();
+    let (pool_id, senior, junior) = fund_pool(&f);
+    let (investor_return, _) = f.client.calculate_profit(&9_000, &10_000);
+
+    f.client
+        .settle_invoice(&f.invoice_ids.get(0).unwrap(), &10_000);
+    f.client.handle_default(&f.invoice_ids.get(1).unwrap());
+
+    let waterfall = f.client.get_pool_waterfall(&pool_id);
+    assert_eq!(waterfall.defaulted_principal, 9_000);
+    assert_eq!(waterfall.senior_available, investor_return);
+    assert_eq!(waterfall.junior_available, 0);
+    assert_eq!(
+        f.client.claim_pool_proceeds(&senior, &pool_id),
+        investor_return
+    );
+    assert_eq!(f.client.claim_pool_proceeds(&junior, &pool_id), 0);
+}
+
+#[test]
+fn test_pool_validation_and_cancellation() {
+    let f = setup();
+    let token_client = token::Client::new(&f.env, &f.currency);
+    let outsider = Address::generate(&f.env);
+
+    let mut bad_terms = terms(&f);
+    bad_terms.junior_return_bps = 500;
+    assert_eq!(
+        f.client
+            .try_create_invoice_pool(&f.admin, &f.invoice_ids, &bad_terms),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        f.client
+            .try_create_invoice_pool(&outsider, &f.invoice_ids, &terms(&f)),
+        Err(Ok(QuickLendXError::NotAdmin))
+    );
+
+    let pool_id = f
+        .client
+        .create_invoice_pool(&f.admin, &f.invoice_ids, &terms(&f));
+    let first = f.invoice_ids.get(0).unwrap();
+    assert_eq!(
+        f.client
+            .try_create_invoice_pool(&f.admin, &f.invoice_ids, &terms(&f)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Pooled invoices are reserved: they cannot be bid on
+    let investor = setup_investor(&f);
+    assert_eq!(
+        f.client.try_place_bid(&investor, &first, &9_000, &10_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    assert_eq!(
+        f.client
+            .try_subscribe_to_pool(&investor, &pool_id, &TrancheKind::Junior, &5_401),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        f.client.try_claim_pool_proceeds(&investor, &pool_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    let before = token_client.balance(&investor);
+    f.client
+        .subscribe_to_pool(&investor, &pool_id, &TrancheKind::Junior, &5_000);
+    assert_eq!(before - token_client.balance(&investor), 5_000);
+
+    // Cancelling refunds subscribers and releases the invoices
+    f.client.cancel_invoice_pool(&f.admin, &pool_id);
+    assert_eq!(token_client.balance(&investor), before);
+    assert_eq!(
+        f.client.get_invoice_pool(&pool_id).unwrap().status,
+        PoolStatus::Cancelled
+    );
+    assert_eq!(f.client.get_pool_of_invoice(&first), None);
+    let bid_id = f.client.place_bid(&investor, &first, &9_000, &10_000);
+    f.client.accept_bid(&first, &bid_id);
+    assert_eq!(f.client.get_invoice(&first).status, InvoiceStatus::Funded);
+}
+
+#[test]
+fn test_unfilled_pool_expires_after_deadline() {
+    let f = setup();
+    let token_client = token::Client::new(&f.env, &f.currency);
+    let mut no_period = terms(&f);
+    no_period.subscription_period = 0;
+    assert_eq!(
+        f.client
+            .try_create_invoice_pool(&f.admin, &f.invoice_ids, &no_period),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    let pool_id = f
+        .client
+        .create_invoice_pool(&f.admin, &f.invoice_ids, &terms(&f));
+    let investor = setup_investor(&f);
+    let before = token_client.balance(&investor);
+    f.client
+        .subscribe_to_pool(&investor, &pool_id, &TrancheKind::Senior, &12_600);
+    assert_eq!(
+        f.client.try_expire_invoice_pool(&pool_id),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    // Past the deadline, subscriptions close and anyone can expire the pool
+    f.env
+        .ledger()
+        .set_timestamp(f.env.ledger().timestamp() + SUBSCRIPTION_PERIOD + 1);
+    let late = setup_investor(&f);
+    assert_eq!(
+        f.client
+            .try_subscribe_to_pool(&late, &pool_id, &TrancheKind::Junior, &5_400),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    f.client.expire_invoice_pool(&pool_id);
+    assert_eq!(token_client.balance(&investor), before);
+    assert_eq!(
+        f.client.get_invoice_pool(&pool_id).unwrap().status,
+        PoolStatus::Expired
+    );
+    let first = f.invoice_ids.get(0).unwrap();
+    assert_eq!(f.client.get_pool_of_invoice(&first), None);
+    assert_eq!(
+        f.client.try_expire_invoice_pool(&pool_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
@@ -0,0 +1,185 @@
+/// Test suite for progressive distribution of partial payments
+///
+/// Test Coverage:
+/// 1. Distribution: investors are paid as partial payments arrive; settlement pays the rest
+/// 2. Platform fee: principal is recovered first and only the profit is charged
+/// 3. Default: only the principal not yet recovered is lost
+/// 4. Validation: opting in before funding and before bids only
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::recourse::FactoringMode;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Progressive {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Progressive {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Progressive {
+        env,
+        client,
+        business,
+        investor,
+        currency,
+    }
+}
+
+/// A verified 10,000 invoice due in 30 days.
+fn create_invoice(p: &Progressive) -> BytesN<32> {
+    let invoice_id = p.client.store_invoice(
+        &p.business,
+        &10_000,
+        &p.currency,
+        &(p.env.ledger().timestamp() + 30 * 86_400),
+        &String::from_str(&p.env, "Distributor order"),
+        &InvoiceCategory::Products,
+        &Vec::new(&p.env),
+    );
+    p.client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+/// Fund the invoice with 9,000 and return the investment id.
+fn fund(p: &Progressive, invoice_id: &BytesN<32>) -> BytesN<32> {
+    let bid_id = p.client.place_bid(&p.investor, invoice_id, &9_000, &10_000);
+    p.client.accept_bid(invoice_id, &bid_id);
+    p.client.get_invoice_investment(invoice_id).investment_id
+}
+
+fn pay(p: &Progressive, invoice_id: &BytesN<32>, amount: i128, reference: &str) {
+    p.client
+        .process_partial_payment(invoice_id, &amount, &String::from_str(&p.env, reference));
+}
+
+#[test]
+fn test_partial_payments_reach_investor_as_they_arrive() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let invoice_id = create_invoice(&p);
+    p.client.set_progressive_distribution(&invoice_id, &true);
+    assert!(p.client.is_progressive_distribution(&invoice_id));
+    let investment_id = fund(&p, &invoice_id);
+    let investor_before = token_client.balance(&p.investor);
+
+    pay(&p, &invoice_id, 4_000, "part-1");
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 4_000);
+    assert_eq!(p.client.get_distributed_amount(&investment_id), 4_000);
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    // The completing payment settles the invoice and pays out what is still owed
+    let business_before = token_client.balance(&p.business);
+    pay(&p, &invoice_id, 6_000, "part-2");
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+    assert_eq!(business_before - token_client.balance(&p.business), 6_000);
+    let (investor_return, _) = p.client.calculate_profit(&9_000, &10_000);
+    assert_eq!(
+        token_client.balance(&p.investor) - investor_before,
+        investor_return
+    );
+}
+
+#[test]
+fn test_fee_is_taken_from_profit_only() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let invoice_id = create_invoice(&p);
+    p.client.set_progressive_distribution(&invoice_id, &true);
+    let investment_id = fund(&p, &invoice_id);
+    let investor_before = token_client.balance(&p.investor);
+
+    // Still recovering principal: no fee
+    pay(&p, &invoice_id, 8_000, "part-1");
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 8_000);
+
+    // 500 of this payment is profit, of which the platform takes 2%
+    pay(&p, &invoice_id, 1_500, "part-2");
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 9_490);
+    assert_eq!(p.client.get_distributed_amount(&investment_id), 9_500);
+}
+
+#[test]
+fn test_default_loses_only_unrecovered_principal() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+    p.client.set_progressive_distribution(&invoice_id, &true);
+    p.client
+        .set_factoring_mode(&invoice_id, &FactoringMode::Recourse);
+    fund(&p, &invoice_id);
+
+    pay(&p, &invoice_id, 4_000, "part-1");
+    p.client.handle_default(&invoice_id);
+
+    let obligation = p.client.get_recourse_obligation(&invoice_id).unwrap();
+    assert_eq!(obligation.total_amount, 5_000);
+}
+
+#[test]
+fn test_progressive_distribution_validation() {
+    let p = setup();
+    let invoice_id = create_invoice(&p);
+    assert!(!p.client.is_progressive_distribution(&invoice_id));
+    p.client.set_progressive_distribution(&invoice_id, &true);
+    p.client.set_progressive_distribution(&invoice_id, &false);
+    assert!(!p.client.is_progressive_distribution(&invoice_id));
+
+    // Investors bid on the terms they saw, so they cannot change under them
+    let bid_id = p
+        .client
+        .place_bid(&p.investor, &invoice_id, &9_000, &10_000);
+    assert_eq!(
+        p.client
+            .try_set_progressive_distribution(&invoice_id, &true),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    p.client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        p.client
+            .try_set_progressive_distribution(&invoice_id, &true),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // Without opting in, partial payments wait for settlement
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let investor_before = token_client.balance(&p.investor);
+    pay(&p, &invoice_id, 4_000, "part-1");
+    assert_eq!(token_client.balance(&p.investor), investor_before);
+}
@@ -0,0 +1,240 @@
+/// Test suite for investment receipts
+///
+/// Test Coverage:
+/// 1. Minting: funding credits the whole principal to the investor
+/// 2. Payouts: settlement and escrow refunds are split between current holders
+/// 3. Allowances: approve / transfer_from, expiry and spending
+/// 4. Validation: balances, amounts, redeemed receipts and partial-holder listings
+use super::*;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Position {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    currency: Address,
+    invoice_id: BytesN<32>,
+    investment_id: BytesN<32>,
+}
+
+/// A 10,000 invoice funded with 9,000 by `investor`, escrow still held.
+fn setup() -> Position {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Receipt invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment_id = client.get_invoice_investment(&invoice_id).investment_id;
+
+    Position {
+        env,
+        client,
+        business,
+        investor,
+        currency,
+        invoice_id,
+        investment_id,
+    }
+}
+
+#[test]
+fn test_settlement_pays_receipt_holders_pro_rata() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let holder = Address::generate(&p.env);
+
+    let receipt = p.client.get_investment_receipt(&p.investment_id).unwrap();
+    assert_eq!(receipt.total_supply, 9_000);
+    assert_eq!(receipt.invoice_id, p.invoice_id);
+    assert!(!receipt.redeemed);
+    assert_eq!(
+        p.client.receipt_balance(&p.investment_id, &p.investor),
+        9_000
+    );
+
+    p.client
+        .receipt_transfer(&p.investment_id, &p.investor, &holder, &3_000);
+    assert_eq!(
+        p.client.receipt_balance(&p.investment_id, &p.investor),
+        6_000
+    );
+    assert_eq!(p.client.receipt_balance(&p.investment_id, &holder), 3_000);
+    assert_eq!(
+        p.client
+            .get_investment_receipt(&p.investment_id)
+            .unwrap()
+            .holders
+            .len(),
+        2
+    );
+
+    let investor_before = token_client.balance(&p.investor);
+    p.client.settle_invoice(&p.invoice_id, &10_000);
+    let (investor_return, _) = p.client.calculate_profit(&9_000, &10_000);
+    // The last holder absorbs the rounding remainder
+    let investor_share = investor_return * 6_000 / 9_000;
+    assert_eq!(
+        token_client.balance(&p.investor) - investor_before,
+        investor_share
+    );
+    assert_eq!(
+        token_client.balance(&holder),
+        investor_return - investor_share
+    );
+
+    // Settled receipts are frozen
+    assert!(
+        p.client
+            .get_investment_receipt(&p.investment_id)
+            .unwrap()
+            .redeemed
+    );
+    assert_eq!(
+        p.client
+            .try_receipt_transfer(&p.investment_id, &holder, &p.investor, &1_000),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_escrow_refund_is_split_between_holders() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let holder = Address::generate(&p.env);
+    p.client
+        .receipt_transfer(&p.investment_id, &p.investor, &holder, &4_500);
+
+    let investor_before = token_client.balance(&p.investor);
+    p.client.refund_escrow_funds(&p.invoice_id, &p.business);
+    assert_eq!(token_client.balance(&holder), 4_500);
+    assert_eq!(token_client.balance(&p.investor) - investor_before, 4_500);
+}
+
+#[test]
+fn test_approve_and_transfer_from() {
+    let p = setup();
+    let spender = Address::generate(&p.env);
+    let recipient = Address::generate(&p.env);
+    let expiration = p.env.ledger().sequence() + 100;
+
+    p.client
+        .receipt_approve(&p.investment_id, &p.investor, &spender, &2_000, &expiration);
+    assert_eq!(
+        p.client
+            .receipt_allowance(&p.investment_id, &p.investor, &spender),
+        2_000
+    );
+
+    p.client
+        .receipt_transfer_from(&p.investment_id, &spender, &p.investor, &recipient, &1_500);
+    assert_eq!(
+        p.client.receipt_balance(&p.investment_id, &recipient),
+        1_500
+    );
+    assert_eq!(
+        p.client
+            .receipt_allowance(&p.investment_id, &p.investor, &spender),
+        500
+    );
+    assert_eq!(
+        p.client.try_receipt_transfer_from(
+            &p.investment_id,
+            &spender,
+            &p.investor,
+            &recipient,
+            &600,
+        ),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Allowances lapse after their expiration ledger
+    p.env
+        .ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    assert_eq!(
+        p.client
+            .receipt_allowance(&p.investment_id, &p.investor, &spender),
+        0
+    );
+    assert_eq!(
+        p.client
+            .try_receipt_approve(&p.investment_id, &p.investor, &spender, &1_000, &expiration),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+}
+
+#[test]
+fn test_receipt_transfer_validation() {
+    let p = setup();
+    let holder = Address::generate(&p.env);
+
+    assert_eq!(
+        p.client
+            .try_receipt_transfer(&p.investment_id, &p.investor, &holder, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        p.client
+            .try_receipt_transfer(&p.investment_id, &p.investor, &holder, &9_001),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    let unknown = BytesN::from_array(&p.env, &[7u8; 32]);
+    assert_eq!(
+        p.client
+            .try_receipt_transfer(&unknown, &p.investor, &holder, &1),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    // A partial holder cannot sell the whole position on the secondary market
+    p.client
+        .receipt_transfer(&p.investment_id, &p.investor, &holder, &1_000);
+    assert_eq!(
+        p.client
+            .try_list_investment(&p.investor, &p.investment_id, &8_000),
+        Err(Ok(QuickLendXError::NotInvestor))
+    );
+
+    // Holders who give away their whole balance leave the holder list
+    p.client
+        .receipt_transfer(&p.investment_id, &holder, &p.investor, &1_000);
+    let receipt = p.client.get_investment_receipt(&p.investment_id).unwrap();
+    assert_eq!(receipt.holders.len(), 1);
+    assert_eq!(receipt.holders.get(0).unwrap(), p.investor);
+}
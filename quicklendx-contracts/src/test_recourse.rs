@@ -0,0 +1,237 @@
+/// Test suite for recourse and non-recourse factoring
+///
+/// Test Coverage:
+/// 1. Recourse default: obligation opened, uploads blocked until repaid, repayments to investor
+/// 2. Non-recourse default: no obligation, uploads unaffected
+/// 3. Validation: mode changes before funding only, repayment bounds and ownership
+/// 4. Receipts: repayments are split between the receipt holders at the default
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::recourse::FactoringMode;
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+
+struct Deal {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Deal {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Deal {
+        env,
+        client,
+        business,
+        investor,
+        currency,
+    }
+}
+
+fn create_invoice(d: &Deal) -> BytesN<32> {
+    let invoice_id = d.client.store_invoice(
+        &d.business,
+        &10_000,
+        &d.currency,
+        &(d.env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&d.env, "Factored invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&d.env),
+    );
+    d.client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+fn fund(d: &Deal, invoice_id: &BytesN<32>) {
+    let bid_id = d.client.place_bid(&d.investor, invoice_id, &9_000, &10_000);
+    d.client.accept_bid(invoice_id, &bid_id);
+}
+
+#[test]
+fn test_recourse_default_blocks_uploads_until_repaid() {
+    let d = setup();
+    let token_client = token::Client::new(&d.env, &d.currency);
+    let invoice_id = create_invoice(&d);
+    d.client
+        .set_factoring_mode(&invoice_id, &FactoringMode::Recourse);
+    assert_eq!(
+        d.client.get_factoring_mode(&invoice_id),
+        FactoringMode::Recourse
+    );
+    fund(&d, &invoice_id);
+    d.client.handle_default(&invoice_id);
+
+    let obligation = d.client.get_recourse_obligation(&invoice_id).unwrap();
+    assert_eq!(obligation.business, d.business);
+    assert_eq!(obligation.total_amount, 9_000);
+    assert_eq!(obligation.outstanding(), 9_000);
+    assert_eq!(d.client.get_outstanding_recourse(&d.business).len(), 1);
+
+    let due_date = d.env.ledger().timestamp() + 30 * 86400;
+    let description = String::from_str(&d.env, "Blocked invoice");
+    assert_eq!(
+        d.client.try_store_invoice(
+            &d.business,
+            &5_000,
+            &d.currency,
+            &due_date,
+            &description,
+            &InvoiceCategory::Services,
+            &Vec::new(&d.env),
+        ),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        d.client.try_upload_invoice(
+            &d.business,
+            &5_000,
+            &d.currency,
+            &due_date,
+            &description,
+            &InvoiceCategory::Services,
+            &Vec::new(&d.env),
+        ),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let investor_before = token_client.balance(&d.investor);
+    let obligation = d.client.repay_recourse(&d.business, &invoice_id, &4_000);
+    assert_eq!(obligation.outstanding(), 5_000);
+    assert_eq!(obligation.settled_at, None);
+    assert_eq!(token_client.balance(&d.investor) - investor_before, 4_000);
+
+    let obligation = d.client.repay_recourse(&d.business, &invoice_id, &5_000);
+    assert_eq!(obligation.outstanding(), 0);
+    assert!(obligation.settled_at.is_some());
+    assert_eq!(token_client.balance(&d.investor) - investor_before, 9_000);
+    assert_eq!(d.client.get_outstanding_recourse(&d.business).len(), 0);
+
+    // Fully repaid: uploads resume, and the obligation cannot be repaid twice
+    d.client.upload_invoice(
+        &d.business,
+        &5_000,
+        &d.currency,
+        &due_date,
+        &description,
+        &InvoiceCategory::Services,
+        &Vec::new(&d.env),
+    );
+    assert_eq!(
+        d.client.try_repay_recourse(&d.business, &invoice_id, &1),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_non_recourse_default_leaves_no_obligation() {
+    let d = setup();
+    let invoice_id = create_invoice(&d);
+    assert_eq!(
+        d.client.get_factoring_mode(&invoice_id),
+        FactoringMode::NonRecourse
+    );
+    fund(&d, &invoice_id);
+    d.client.handle_default(&invoice_id);
+
+    assert!(d.client.get_recourse_obligation(&invoice_id).is_none());
+    assert_eq!(d.client.get_outstanding_recourse(&d.business).len(), 0);
+    create_invoice(&d);
+}
+
+#[test]
+fn test_factoring_mode_and_repayment_validation() {
+    let d = setup();
+    let invoice_id = create_invoice(&d);
+    d.client
+        .set_factoring_mode(&invoice_id, &FactoringMode::Recourse);
+    let bid_id = d
+        .client
+        .place_bid(&d.investor, &invoice_id, &9_000, &10_000);
+
+    // Bids were priced on recourse terms
+    assert_eq!(
+        d.client
+            .try_set_factoring_mode(&invoice_id, &FactoringMode::NonRecourse),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    d.client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        d.client
+            .try_set_factoring_mode(&invoice_id, &FactoringMode::Recourse),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        d.client
+            .try_repay_recourse(&d.business, &invoice_id, &1_000),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    d.client.handle_default(&invoice_id);
+    assert_eq!(
+        d.client
+            .try_repay_recourse(&d.investor, &invoice_id, &1_000),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        d.client.try_repay_recourse(&d.business, &invoice_id, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        d.client
+            .try_repay_recourse(&d.business, &invoice_id, &9_001),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_recourse_repayments_reach_receipt_holders() {
+    let d = setup();
+    let token_client = token::Client::new(&d.env, &d.currency);
+    let holder = Address::generate(&d.env);
+    let invoice_id = create_invoice(&d);
+    d.client
+        .set_factoring_mode(&invoice_id, &FactoringMode::Recourse);
+    fund(&d, &invoice_id);
+    let investment_id = d.client.get_invoice_investment(&invoice_id).investment_id;
+    d.client
+        .receipt_transfer(&investment_id, &d.investor, &holder, &3_000);
+
+    d.client.handle_default(&invoice_id);
+    // The receipt is frozen at the default
+    assert_eq!(
+        d.client
+            .try_receipt_transfer(&investment_id, &holder, &d.investor, &1_000),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    let investor_before = token_client.balance(&d.investor);
+    d.client.repay_recourse(&d.business, &invoice_id, &9_000);
+    assert_eq!(token_client.balance(&d.investor) - investor_before, 6_000);
+    assert_eq!(token_client.balance(&holder), 3_000);
+}
@@ -0,0 +1,194 @@
+/// Test suite for buyer-led reverse factoring
+///
+/// Test Coverage:
+/// 1. Lifecycle: buyer approves a payable, supplier sells it, invoice is funded as usual
+/// 2. Settlement: partial and final payments are pulled from the buyer, not the supplier
+/// 3. Validation: buyer verification, supplier ownership, payable status transitions
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::reverse_factoring::{PayableStatus, PayableTerms};
+use soroban_sdk::{testutils::Address as _, token, Address, Env, String};
+
+struct SupplyChain {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    buyer: Address,
+    supplier: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> SupplyChain {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let buyer = Address::generate(&env);
+    let supplier = Address::generate(&env);
+    for business in [&buyer, &supplier] {
+        client.submit_kyc_application(business, &String::from_str(&env, "Business KYC"));
+        client.verify_business(&admin, business);
+    }
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&buyer, &supplier, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    SupplyChain {
+        env,
+        client,
+        admin,
+        buyer,
+        supplier,
+        investor,
+        currency,
+    }
+}
+
+/// A Products payable due in 60 days.
+fn terms(s: &SupplyChain, supplier: &Address, amount: i128, description: &str) -> PayableTerms {
+    PayableTerms {
+        supplier: supplier.clone(),
+        amount,
+        currency: s.currency.clone(),
+        due_date: s.env.ledger().timestamp() + 60 * 86400,
+        description: String::from_str(&s.env, description),
+        category: InvoiceCategory::Products,
+    }
+}
+
+fn register(s: &SupplyChain) -> u64 {
+    s.client
+        .register_payable(&s.buyer, &terms(s, &s.supplier, 10_000, "Components order"))
+        .payable_id
+}
+
+#[test]
+fn test_supplier_sells_payable_and_buyer_settles() {
+    let s = setup();
+    let token_client = token::Client::new(&s.env, &s.currency);
+    let payable_id = register(&s);
+    assert_eq!(s.client.get_buyer_payables(&s.buyer).len(), 1);
+    assert_eq!(s.client.get_supplier_payables(&s.supplier).len(), 1);
+
+    let invoice_id = s.client.sell_payable(&s.supplier, &payable_id);
+    let payable = s.client.get_payable(&payable_id).unwrap();
+    assert_eq!(payable.status, PayableStatus::Sold);
+    assert_eq!(payable.invoice_id, Some(invoice_id.clone()));
+    assert_eq!(
+        s.client.get_invoice_payable(&invoice_id).unwrap().buyer,
+        s.buyer
+    );
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.business, s.supplier);
+    assert_eq!(invoice.amount, 10_000);
+
+    s.client.verify_invoice(&invoice_id);
+    let bid_id = s
+        .client
+        .place_bid(&s.investor, &invoice_id, &9_500, &10_000);
+    s.client.accept_bid(&invoice_id, &bid_id);
+
+    let buyer_before = token_client.balance(&s.buyer);
+    let supplier_before = token_client.balance(&s.supplier);
+    let investor_before = token_client.balance(&s.investor);
+    s.client
+        .process_partial_payment(&invoice_id, &4_000, &String::from_str(&s.env, "buyer-tx-1"));
+    s.client.settle_invoice(&invoice_id, &6_000);
+
+    let (investor_return, _) = s.client.calculate_profit(&9_500, &10_000);
+    assert_eq!(buyer_before - token_client.balance(&s.buyer), 10_000);
+    assert_eq!(token_client.balance(&s.supplier), supplier_before);
+    assert_eq!(
+        token_client.balance(&s.investor) - investor_before,
+        investor_return
+    );
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_payable_lifecycle_validation() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+
+    assert_eq!(
+        s.client.try_register_payable(
+            &outsider,
+            &terms(&s, &s.supplier, 10_000, "Components order")
+        ),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    assert_eq!(
+        s.client
+            .try_register_payable(&s.buyer, &terms(&s, &s.supplier, 0, "Components order")),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    let payable_id = register(&s);
+    assert_eq!(
+        s.client.try_sell_payable(&outsider, &payable_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_cancel_payable(&s.supplier, &payable_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    s.client.sell_payable(&s.supplier, &payable_id);
+    assert_eq!(
+        s.client.try_sell_payable(&s.supplier, &payable_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        s.client.try_cancel_payable(&s.buyer, &payable_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // A cancelled payable can no longer be sold
+    let payable_id = register(&s);
+    s.client.cancel_payable(&s.buyer, &payable_id);
+    assert_eq!(
+        s.client.get_payable(&payable_id).unwrap().status,
+        PayableStatus::Cancelled
+    );
+    assert_eq!(
+        s.client.try_sell_payable(&s.supplier, &payable_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_unverified_supplier_cannot_sell() {
+    let s = setup();
+    let supplier = Address::generate(&s.env);
+    let payable_id = s
+        .client
+        .register_payable(&s.buyer, &terms(&s, &supplier, 10_000, "Unvetted supplier"))
+        .payable_id;
+    assert_eq!(
+        s.client.try_sell_payable(&supplier, &payable_id),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+
+    s.client
+        .submit_kyc_application(&supplier, &String::from_str(&s.env, "Supplier KYC"));
+    s.client.verify_business(&s.admin, &supplier);
+    s.client.sell_payable(&supplier, &payable_id);
+}
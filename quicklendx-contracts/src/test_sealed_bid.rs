@@ -0,0 +1,258 @@
+/// Test suite for sealed-bid (commit–reveal) auctions
+///
+/// Test Coverage:
+/// 1. Lifecycle: commit, reveal and accept respect their windows
+/// 2. Integrity: reveals must match the committed hash
+/// 3. Ranking: only revealed bids are ranked; unrevealed commitments expire
+/// 4. Setup: sealed mode cannot be opened over public bids or with bad windows, and
+///    bidding reopens once an auction closes
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::sealed_bid::CommitmentStatus;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const COMMIT_WINDOW: u64 = 2 * 60 * 60;
+const REVEAL_WINDOW: u64 = 2 * 60 * 60;
+
+fn setup() -> (Env, QuickLendXContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    (env, client, admin)
+}
+
+fn setup_verified_investor(env: &Env, client: &QuickLendXContractClient) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    investor
+}
+
+/// Verified 10,000 invoice with two funded, verified investors.
+fn setup_invoice() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    BytesN<32>,
+    Address,
+    Address,
+) {
+    let (env, client, admin) = setup();
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor_a = setup_verified_investor(&env, &client);
+    let investor_b = setup_verified_investor(&env, &client);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac_client = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for investor in [&investor_a, &investor_b] {
+        sac_client.mint(investor, &100_000);
+        token_client.approve(investor, &client.address, &100_000, &10_000);
+    }
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Sealed invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    (env, client, invoice_id, investor_a, investor_b)
+}
+
+fn salt(env: &Env, seed: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[seed; 32])
+}
+
+fn commit(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    investor: &Address,
+    invoice_id: &BytesN<32>,
+    amount: i128,
+    expected_return: i128,
+    seed: u8,
+) {
+    let commitment = client.compute_bid_commitment(
+        investor,
+        invoice_id,
+        &amount,
+        &expected_return,
+        &salt(env, seed),
+    );
+    client.commit_bid(investor, invoice_id, &commitment);
+}
+
+#[test]
+fn test_sealed_auction_lifecycle() {
+    let (env, client, invoice_id, investor_a, investor_b) = setup_invoice();
+    let auction = client.open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW);
+    assert_eq!(auction.commit_deadline, 1_000 + COMMIT_WINDOW);
+    assert_eq!(
+        auction.reveal_deadline,
+        1_000 + COMMIT_WINDOW + REVEAL_WINDOW
+    );
+
+    // Plain bids are not accepted on sealed invoices
+    assert_eq!(
+        client.try_place_bid(&investor_a, &invoice_id, &8_000, &9_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    commit(&env, &client, &investor_a, &invoice_id, 8_000, 9_000, 1);
+    commit(&env, &client, &investor_b, &invoice_id, 8_500, 9_200, 2);
+    assert_eq!(client.get_bid_commitments(&invoice_id).len(), 2);
+    assert_eq!(client.get_ranked_bids(&invoice_id).len(), 0);
+
+    // Reveal is closed during the commit window
+    assert_eq!(
+        client.try_reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 1)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(auction.commit_deadline + 1);
+    assert_eq!(
+        client.try_commit_bid(&investor_a, &invoice_id, &salt(&env, 9)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let bid_a = client.reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 1));
+    let bid_b = client.reveal_bid(&investor_b, &invoice_id, &8_500, &9_200, &salt(&env, 2));
+
+    let ranked = client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), 2);
+    let commitment = client.get_bid_commitment(&invoice_id, &investor_a).unwrap();
+    assert_eq!(commitment.status, CommitmentStatus::Revealed);
+    assert_eq!(commitment.bid_id, Some(bid_a.clone()));
+
+    // Bids cannot be accepted while the reveal window is open
+    assert_eq!(
+        client.try_accept_bid(&invoice_id, &bid_b),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(auction.reveal_deadline + 1);
+    client.accept_bid(&invoice_id, &bid_b);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        crate::invoice::InvoiceStatus::Funded
+    );
+}
+
+#[test]
+fn test_reveal_must_match_commitment() {
+    let (env, client, invoice_id, investor_a, investor_b) = setup_invoice();
+    let auction = client.open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW);
+    commit(&env, &client, &investor_a, &invoice_id, 8_000, 9_000, 1);
+    env.ledger().set_timestamp(auction.commit_deadline + 1);
+
+    assert_eq!(
+        client.try_reveal_bid(&investor_a, &invoice_id, &7_000, &9_000, &salt(&env, 1)),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 2)),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    // Another investor cannot reuse someone else's commitment
+    assert_eq!(
+        client.try_reveal_bid(&investor_b, &invoice_id, &8_000, &9_000, &salt(&env, 1)),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    client.reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 1));
+    assert_eq!(
+        client.try_reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 1)),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_unrevealed_commitments_expire() {
+    let (env, client, invoice_id, investor_a, investor_b) = setup_invoice();
+    let auction = client.open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW);
+    commit(&env, &client, &investor_a, &invoice_id, 8_000, 9_000, 1);
+    commit(&env, &client, &investor_b, &invoice_id, 9_000, 9_500, 2);
+
+    env.ledger().set_timestamp(auction.commit_deadline + 1);
+    let bid_a = client.reveal_bid(&investor_a, &invoice_id, &8_000, &9_000, &salt(&env, 1));
+
+    // Nothing expires while the reveal window is open
+    assert_eq!(client.expire_bid_commitments(&invoice_id), 0);
+
+    env.ledger().set_timestamp(auction.reveal_deadline + 1);
+    assert_eq!(
+        client.try_reveal_bid(&investor_b, &invoice_id, &9_000, &9_500, &salt(&env, 2)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(client.expire_bid_commitments(&invoice_id), 1);
+    assert_eq!(
+        client
+            .get_bid_commitment(&invoice_id, &investor_b)
+            .unwrap()
+            .status,
+        CommitmentStatus::Expired
+    );
+
+    let ranked = client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked.get(0).unwrap().bid_id, bid_a);
+}
+
+#[test]
+fn test_closed_auction_reopens_bidding() {
+    let (env, client, invoice_id, investor_a, investor_b) = setup_invoice();
+    let auction = client.open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW);
+    commit(&env, &client, &investor_a, &invoice_id, 8_000, 9_000, 1);
+    assert_eq!(
+        client.try_open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Nobody revealed, so the invoice can go to a fresh auction
+    env.ledger().set_timestamp(auction.reveal_deadline + 1);
+    let second = client.open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW);
+    assert!(client
+        .get_bid_commitment(&invoice_id, &investor_a)
+        .is_none());
+    commit(&env, &client, &investor_a, &invoice_id, 8_200, 9_100, 3);
+
+    // Once that one closes too, plain bids are taken again
+    env.ledger().set_timestamp(second.reveal_deadline + 1);
+    client.place_bid(&investor_b, &invoice_id, &8_500, &9_200);
+    assert_eq!(client.get_ranked_bids(&invoice_id).len(), 1);
+}
+
+#[test]
+fn test_open_sealed_auction_validation() {
+    let (_env, client, invoice_id, investor_a, _investor_b) = setup_invoice();
+
+    assert_eq!(
+        client.try_open_sealed_auction(&invoice_id, &60, &REVEAL_WINDOW),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &(31 * 86400)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+
+    client.place_bid(&investor_a, &invoice_id, &8_000, &9_000);
+    assert_eq!(
+        client.try_open_sealed_auction(&invoice_id, &COMMIT_WINDOW, &REVEAL_WINDOW),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert!(client.get_sealed_auction(&invoice_id).is_none());
+}
@@ -0,0 +1,189 @@
+/// Test suite for the secondary market in investment positions
+///
+/// Test Coverage:
+/// 1. Trade: price moves buyer -> seller, ownership moves seller -> buyer
+/// 2. Payouts: settlement and escrow refunds follow the new holder
+/// 3. Validation: only holders list, only verified third parties buy, listings close
+use super::*;
+use crate::investment::InvestmentStatus;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+
+struct Market {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    seller: Address,
+    buyer: Address,
+    currency: Address,
+    invoice_id: BytesN<32>,
+    investment_id: BytesN<32>,
+}
+
+fn setup_investor(env: &Env, client: &QuickLendXContractClient) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    investor
+}
+
+/// A 10,000 invoice funded with 9,000 by `seller`, escrow still held.
+fn setup() -> Market {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let seller = setup_investor(&env, &client);
+    let buyer = setup_investor(&env, &client);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &seller, &buyer] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    let invoice_id = client.store_invoice(
+        &business,
+        &10_000,
+        &currency,
+        &(env.ledger().timestamp() + 30 * 86400),
+        &String::from_str(&env, "Traded invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    client.verify_invoice(&invoice_id);
+    let bid_id = client.place_bid(&seller, &invoice_id, &9_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment_id = client.get_invoice_investment(&invoice_id).investment_id;
+
+    Market {
+        env,
+        client,
+        business,
+        seller,
+        buyer,
+        currency,
+        invoice_id,
+        investment_id,
+    }
+}
+
+#[test]
+fn test_buy_investment_transfers_ownership_and_payment() {
+    let m = setup();
+    let token_client = token::Client::new(&m.env, &m.currency);
+    m.client
+        .list_investment(&m.seller, &m.investment_id, &9_300);
+    assert_eq!(m.client.get_investment_listings().len(), 1);
+
+    let seller_before = token_client.balance(&m.seller);
+    let buyer_before = token_client.balance(&m.buyer);
+    let investment = m.client.buy_investment(&m.buyer, &m.investment_id);
+
+    assert_eq!(token_client.balance(&m.seller) - seller_before, 9_300);
+    assert_eq!(buyer_before - token_client.balance(&m.buyer), 9_300);
+    assert_eq!(investment.investor, m.buyer);
+    assert_eq!(investment.status, InvestmentStatus::Active);
+    assert_eq!(
+        m.client.get_invoice(&m.invoice_id).investor,
+        Some(m.buyer.clone())
+    );
+    assert!(m
+        .client
+        .get_investments_by_investor(&m.buyer)
+        .contains(&m.investment_id));
+    assert!(!m
+        .client
+        .get_investments_by_investor(&m.seller)
+        .contains(&m.investment_id));
+    assert!(m.client.get_investment_listing(&m.investment_id).is_none());
+    assert_eq!(m.client.get_investment_listings().len(), 0);
+
+    // Settlement now pays the buyer
+    let seller_before = token_client.balance(&m.seller);
+    let buyer_before = token_client.balance(&m.buyer);
+    m.client.settle_invoice(&m.invoice_id, &10_000);
+    let (investor_return, _) = m.client.calculate_profit(&9_000, &10_000);
+    assert_eq!(
+        token_client.balance(&m.buyer) - buyer_before,
+        investor_return
+    );
+    assert_eq!(token_client.balance(&m.seller), seller_before);
+}
+
+#[test]
+fn test_escrow_refund_goes_to_new_holder() {
+    let m = setup();
+    let token_client = token::Client::new(&m.env, &m.currency);
+    m.client
+        .list_investment(&m.seller, &m.investment_id, &9_000);
+    m.client.buy_investment(&m.buyer, &m.investment_id);
+
+    let buyer_before = token_client.balance(&m.buyer);
+    m.client.refund_escrow_funds(&m.invoice_id, &m.business);
+    assert_eq!(token_client.balance(&m.buyer) - buyer_before, 9_000);
+}
+
+#[test]
+fn test_listing_and_purchase_validation() {
+    let m = setup();
+    let stranger = Address::generate(&m.env);
+
+    assert_eq!(
+        m.client
+            .try_list_investment(&m.buyer, &m.investment_id, &9_000),
+        Err(Ok(QuickLendXError::NotInvestor))
+    );
+    assert_eq!(
+        m.client
+            .try_list_investment(&m.seller, &m.investment_id, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    m.client
+        .list_investment(&m.seller, &m.investment_id, &9_000);
+    assert_eq!(
+        m.client
+            .try_list_investment(&m.seller, &m.investment_id, &9_100),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    assert_eq!(
+        m.client.try_buy_investment(&stranger, &m.investment_id),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    assert_eq!(
+        m.client.try_buy_investment(&m.seller, &m.investment_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        m.client
+            .try_cancel_investment_listing(&m.buyer, &m.investment_id),
+        Err(Ok(QuickLendXError::NotInvestor))
+    );
+
+    m.client
+        .cancel_investment_listing(&m.seller, &m.investment_id);
+    assert_eq!(
+        m.client.try_buy_investment(&m.buyer, &m.investment_id),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    // Settled positions can no longer be listed
+    m.client.settle_invoice(&m.invoice_id, &10_000);
+    assert_eq!(
+        m.client
+            .try_list_investment(&m.seller, &m.investment_id, &9_000),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
@@ -0,0 +1,387 @@
+/// Test suite for investor standing orders
+///
+/// Test Coverage:
+/// 1. Matching: verify_invoice bids on invoices that fit currency, category, size and tenor
+/// 2. Exposure: orders stop bidding at their cap and resume when bids close
+/// 3. Limits: investor investment limits and max_active_bids_per_investor still apply
+/// 4. Management: validation, per-investor listing and cancellation
+/// 5. Evaluation: a bounded batch of orders per call, with keepers evaluating the rest
+/// 6. Skips: orders whose bid is rejected are skipped with an event, leaving no bid behind
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::standing_order::{MAX_STANDING_ORDERS_EVALUATED, MAX_STANDING_ORDERS_PER_INVESTOR};
+use soroban_sdk::{
+    testutils::Address as _, testutils::Events, token, xdr, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+fn setup() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    Address,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    (env, client, admin, business, currency)
+}
+
+fn setup_investor(env: &Env, client: &QuickLendXContractClient, limit: i128) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &limit);
+    investor
+}
+
+fn store_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    currency: &Address,
+    amount: i128,
+    category: InvoiceCategory,
+    days_to_due: u64,
+) -> BytesN<32> {
+    client.store_invoice(
+        business,
+        &amount,
+        currency,
+        &(env.ledger().timestamp() + days_to_due * DAY),
+        &String::from_str(env, "Standing order invoice"),
+        &category,
+        &Vec::new(env),
+    )
+}
+
+/// 95% of face on Technology invoices up to 50,000 due within 60 days.
+fn technology_terms(env: &Env, currency: &Address, max_exposure: i128) -> StandingOrderTerms {
+    let mut categories = Vec::new(env);
+    categories.push_back(InvoiceCategory::Technology);
+    StandingOrderTerms {
+        currency: currency.clone(),
+        categories,
+        max_invoice_amount: 50_000,
+        max_days_to_due: 60,
+        advance_bps: 9_500,
+        return_bps: 10_000,
+        max_exposure,
+        require_debtor_confirmation: false,
+    }
+}
+
+#[test]
+fn test_verify_invoice_places_bids_for_matching_orders() {
+    let (env, client, admin, business, currency) = setup();
+    let investor = setup_investor(&env, &client, 50_000);
+    let order_id =
+        client.create_standing_order(&investor, &technology_terms(&env, &currency, 200_000));
+
+    let other_currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &other_currency);
+    let skipped = [
+        store_invoice(
+            &env,
+            &client,
+            &business,
+            &currency,
+            10_000,
+            InvoiceCategory::Services,
+            30,
+        ),
+        store_invoice(
+            &env,
+            &client,
+            &business,
+            &currency,
+            60_000,
+            InvoiceCategory::Technology,
+            30,
+        ),
+        store_invoice(
+            &env,
+            &client,
+            &business,
+            &currency,
+            10_000,
+            InvoiceCategory::Technology,
+            90,
+        ),
+        store_invoice(
+            &env,
+            &client,
+            &business,
+            &other_currency,
+            10_000,
+            InvoiceCategory::Technology,
+            30,
+        ),
+    ];
+    for invoice_id in skipped.iter() {
+        client.verify_invoice(invoice_id);
+        assert_eq!(client.get_bids_for_invoice(invoice_id).len(), 0);
+    }
+
+    let invoice_id = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    // Bids are placed on verification, not on upload
+    assert_eq!(client.get_bids_for_invoice(&invoice_id).len(), 0);
+    client.verify_invoice(&invoice_id);
+
+    let bids = client.get_bids_for_invoice(&invoice_id);
+    assert_eq!(bids.len(), 1);
+    let bid = bids.get(0).unwrap();
+    assert_eq!(bid.investor, investor);
+    assert_eq!(bid.bid_amount, 9_500);
+    assert_eq!(bid.expected_return, 10_000);
+    assert_eq!(client.get_standing_order_exposure(&order_id), 9_500);
+    assert_eq!(
+        client.get_standing_order(&order_id).unwrap().bid_ids,
+        Vec::from_array(&env, [bid.bid_id.clone()])
+    );
+
+    // The standing bid is an ordinary bid the business can accept
+    let token_admin = token::StellarAssetClient::new(&env, &currency);
+    token_admin.mint(&investor, &10_000);
+    token::Client::new(&env, &currency).approve(&investor, &client.address, &10_000, &10_000);
+    client.accept_bid(&invoice_id, &bid.bid_id);
+    assert_eq!(client.get_standing_order_exposure(&order_id), 9_500);
+}
+
+#[test]
+fn test_orders_respect_exposure_cap() {
+    let (env, client, _admin, business, currency) = setup();
+    let investor = setup_investor(&env, &client, 50_000);
+    let order_id =
+        client.create_standing_order(&investor, &technology_terms(&env, &currency, 15_000));
+
+    let first = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&first);
+    let second = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&second);
+    assert_eq!(client.get_bids_for_invoice(&first).len(), 1);
+    assert_eq!(client.get_bids_for_invoice(&second).len(), 0);
+
+    // Withdrawing the open bid frees the exposure for the next invoice
+    client.withdraw_bid(&client.get_bids_for_invoice(&first).get(0).unwrap().bid_id);
+    assert_eq!(client.get_standing_order_exposure(&order_id), 0);
+    let third = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&third);
+    assert_eq!(client.get_bids_for_invoice(&third).len(), 1);
+}
+
+#[test]
+fn test_orders_respect_investor_limits() {
+    let (env, client, _admin, business, currency) = setup();
+    let small_investor = setup_investor(&env, &client, 100);
+    client.create_standing_order(&small_investor, &technology_terms(&env, &currency, 200_000));
+    let investor = setup_investor(&env, &client, 50_000);
+    client.create_standing_order(&investor, &technology_terms(&env, &currency, 200_000));
+    client.set_max_active_bids_per_investor(&1);
+
+    let first = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&first);
+    let skip_topic = xdr::ScVal::Symbol(xdr::ScSymbol("so_skip".try_into().unwrap()));
+    let skipped = env
+        .events()
+        .all()
+        .events()
+        .iter()
+        .any(|event| match &event.body {
+            xdr::ContractEventBody::V0(body) => body.topics.first() == Some(&skip_topic),
+        });
+    assert!(skipped, "the rejected order must emit a skip event");
+    let bids = client.get_bids_for_invoice(&first);
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids.get(0).unwrap().investor, investor);
+
+    // The investor already has one active bid, so the next invoice gets none
+    let second = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&second);
+    assert_eq!(client.get_bids_for_invoice(&second).len(), 0);
+}
+
+#[test]
+fn test_standing_order_management() {
+    let (env, client, _admin, business, currency) = setup();
+    let investor = setup_investor(&env, &client, 50_000);
+    let unverified = Address::generate(&env);
+
+    assert_eq!(
+        client.try_create_standing_order(&unverified, &technology_terms(&env, &currency, 1_000)),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    let mut bad = technology_terms(&env, &currency, 1_000);
+    bad.return_bps = 9_000;
+    assert_eq!(
+        client.try_create_standing_order(&investor, &bad),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let mut bad = technology_terms(&env, &currency, 0);
+    bad.max_exposure = 0;
+    assert_eq!(
+        client.try_create_standing_order(&investor, &bad),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    let mut order_ids = Vec::new(&env);
+    for _ in 0..5 {
+        order_ids.push_back(
+            client.create_standing_order(&investor, &technology_terms(&env, &currency, 1_000)),
+        );
+    }
+    assert_eq!(
+        client.try_create_standing_order(&investor, &technology_terms(&env, &currency, 1_000)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let order_id = order_ids.get(0).unwrap();
+    assert_eq!(
+        client.try_cancel_standing_order(&unverified, &order_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    client.cancel_standing_order(&investor, &order_id);
+    assert!(!client.get_standing_order(&order_id).unwrap().active);
+    assert_eq!(
+        client.try_cancel_standing_order(&investor, &order_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(client.get_investor_standing_orders(&investor).len(), 5);
+
+    // Cancelling frees a slot; cancelled orders no longer bid
+    let order_id =
+        client.create_standing_order(&investor, &technology_terms(&env, &currency, 20_000));
+    for order in client.get_investor_standing_orders(&investor).iter() {
+        if order.order_id != order_id && order.active {
+            client.cancel_standing_order(&investor, &order.order_id);
+        }
+    }
+    client.cancel_standing_order(&investor, &order_id);
+    let invoice_id = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&invoice_id);
+    assert_eq!(client.get_bids_for_invoice(&invoice_id).len(), 0);
+}
+
+#[test]
+fn test_keeper_evaluates_orders_past_the_first_batch() {
+    let (env, client, admin, business, currency) = setup();
+    let other_currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &other_currency);
+
+    // More open orders than one call evaluates; only the newest one matches
+    let mut created = 0;
+    let mut investor = setup_investor(&env, &client, 50_000);
+    while created < MAX_STANDING_ORDERS_EVALUATED {
+        if created > 0 && created % MAX_STANDING_ORDERS_PER_INVESTOR == 0 {
+            investor = setup_investor(&env, &client, 50_000);
+        }
+        client.create_standing_order(&investor, &technology_terms(&env, &other_currency, 1_000));
+        created += 1;
+    }
+    let matching_investor = setup_investor(&env, &client, 50_000);
+    client.create_standing_order(
+        &matching_investor,
+        &technology_terms(&env, &currency, 20_000),
+    );
+
+    let invoice_id = store_invoice(
+        &env,
+        &client,
+        &business,
+        &currency,
+        10_000,
+        InvoiceCategory::Technology,
+        30,
+    );
+    client.verify_invoice(&invoice_id);
+    assert_eq!(client.get_bids_for_invoice(&invoice_id).len(), 0);
+
+    // A keeper evaluates the orders the verification did not reach
+    let keeper = Address::generate(&env);
+    assert_eq!(
+        client.keeper_execute_standing_orders(&keeper, &invoice_id),
+        1
+    );
+    let bids = client.get_bids_for_invoice(&invoice_id);
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids.get(0).unwrap().investor, matching_investor);
+
+    // Every order has been evaluated now
+    assert_eq!(
+        client.try_keeper_execute_standing_orders(&keeper, &invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
@@ -224,6 +224,8 @@ fn test_invoice_storage() {
                 dispute: dispute.clone(),
                 total_paid: 0,
                 payment_history: Vec::new(&env),
+                debtor: None,
+                debtor_confirmed_at: None,
             };
 
             // Test storing invoice
@@ -566,6 +568,8 @@ fn create_test_invoice(env: &Env, id: BytesN<32>, business: Address) -> Invoice
         dispute,
         total_paid: 0,
         payment_history: Vec::new(env),
+        debtor: None,
+        debtor_confirmed_at: None,
     }
 }
 
@@ -816,6 +820,8 @@ fn create_complex_invoice(env: &Env) -> Invoice {
         dispute,
         total_paid: 3000,
         payment_history: payments,
+        debtor: None,
+        debtor_confirmed_at: None,
     }
 }
 
@@ -906,6 +912,8 @@ fn test_maximum_values(env: &Env) {
         },
         total_paid: 0,
         payment_history: Vec::new(env),
+        debtor: None,
+        debtor_confirmed_at: None,
     };
 
     // Should handle maximum values without issues
@@ -0,0 +1,295 @@
+/// Test suite for syndicated funding
+///
+/// Test Coverage:
+/// 1. Funding: several bids each get their own escrow slice and investment
+/// 2. Settlement: repayments are split pro rata by principal
+/// 3. Validation: oversize, duplicate and foreign bids are rejected atomically
+/// 4. Default/Refund: every participant is defaulted or refunded
+use super::*;
+use crate::bid::BidStatus;
+use crate::investment::InvestmentStatus;
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::payments::EscrowStatus;
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+
+fn setup() -> (Env, QuickLendXContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    (env, client, admin)
+}
+
+fn setup_token(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    admin: &Address,
+    holders: &[&Address],
+) -> Address {
+    let token_admin = Address::generate(env);
+    let currency = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    client.add_currency(admin, &currency);
+    let token_client = token::Client::new(env, &currency);
+    let sac_client = token::StellarAssetClient::new(env, &currency);
+    let expiration = env.ledger().sequence() + 10_000;
+    for holder in holders.iter() {
+        sac_client.mint(holder, &100_000);
+        token_client.approve(holder, &client.address, &100_000, &expiration);
+    }
+    currency
+}
+
+fn setup_verified_business(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    admin: &Address,
+) -> Address {
+    let business = Address::generate(env);
+    client.submit_kyc_application(&business, &String::from_str(env, "Business KYC"));
+    client.verify_business(admin, &business);
+    business
+}
+
+fn setup_verified_investor(env: &Env, client: &QuickLendXContractClient) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    investor
+}
+
+fn create_verified_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    amount: i128,
+    currency: &Address,
+) -> BytesN<32> {
+    let invoice_id = client.store_invoice(
+        business,
+        &amount,
+        currency,
+        &(env.ledger().timestamp() + 86400),
+        &String::from_str(env, "Syndicated invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(env),
+    );
+    client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+/// Two investors bidding 3,000 and 6,000 on a 10,000 invoice.
+fn setup_syndicate() -> (
+    Env,
+    QuickLendXContractClient<'static>,
+    Address,
+    Address,
+    Address,
+    Address,
+    BytesN<32>,
+    Vec<BytesN<32>>,
+) {
+    let (env, client, admin) = setup();
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor_a = setup_verified_investor(&env, &client);
+    let investor_b = setup_verified_investor(&env, &client);
+    let currency = setup_token(
+        &env,
+        &client,
+        &admin,
+        &[&business, &investor_a, &investor_b],
+    );
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+
+    let bid_a = client.place_bid(&investor_a, &invoice_id, &3_000, &3_300);
+    let bid_b = client.place_bid(&investor_b, &invoice_id, &6_000, &6_600);
+    let mut bid_ids = Vec::new(&env);
+    bid_ids.push_back(bid_a);
+    bid_ids.push_back(bid_b);
+
+    (
+        env, client, business, investor_a, investor_b, currency, invoice_id, bid_ids,
+    )
+}
+
+#[test]
+fn test_syndicated_funding_creates_slice_per_investor() {
+    let (env, client, _business, investor_a, investor_b, currency, invoice_id, bid_ids) =
+        setup_syndicate();
+    let token_client = token::Client::new(&env, &currency);
+
+    let escrow_ids = client.accept_syndicated_bids(&invoice_id, &bid_ids);
+    assert_eq!(escrow_ids.len(), 2);
+    assert_eq!(token_client.balance(&client.address), 9_000);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.funded_amount, 9_000);
+    assert_eq!(invoice.investor, Some(investor_a.clone()));
+
+    let investments = client.get_invoice_investments(&invoice_id);
+    assert_eq!(investments.len(), 2);
+    assert_eq!(investments.get(0).unwrap().investor, investor_a);
+    assert_eq!(investments.get(0).unwrap().amount, 3_000);
+    assert_eq!(investments.get(1).unwrap().investor, investor_b);
+    assert_eq!(investments.get(1).unwrap().amount, 6_000);
+
+    let escrows = client.get_invoice_escrows(&invoice_id);
+    assert_eq!(escrows.len(), 2);
+    for escrow in escrows.iter() {
+        assert_eq!(escrow.status, EscrowStatus::Held);
+    }
+    for bid_id in bid_ids.iter() {
+        assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Accepted);
+    }
+}
+
+#[test]
+fn test_syndicated_settlement_pays_pro_rata() {
+    let (env, client, _business, investor_a, investor_b, currency, invoice_id, bid_ids) =
+        setup_syndicate();
+    let token_client = token::Client::new(&env, &currency);
+    client.accept_syndicated_bids(&invoice_id, &bid_ids);
+
+    let before_a = token_client.balance(&investor_a);
+    let before_b = token_client.balance(&investor_b);
+
+    client.settle_invoice(&invoice_id, &10_000);
+
+    // 10,000 * 3,000 / 9,000 = 3,333; the last participant takes the remainder.
+    let (return_a, _) = client.calculate_profit(&3_000, &3_333);
+    let (return_b, _) = client.calculate_profit(&6_000, &6_667);
+    assert_eq!(token_client.balance(&investor_a) - before_a, return_a);
+    assert_eq!(token_client.balance(&investor_b) - before_b, return_b);
+
+    assert_eq!(client.get_invoice(&invoice_id).status, InvoiceStatus::Paid);
+    for investment in client.get_invoice_investments(&invoice_id).iter() {
+        assert_eq!(investment.status, InvestmentStatus::Completed);
+    }
+}
+
+#[test]
+fn test_syndicate_rejects_bids_exceeding_invoice_amount() {
+    let (env, client, admin) = setup();
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor_a = setup_verified_investor(&env, &client);
+    let investor_b = setup_verified_investor(&env, &client);
+    let currency = setup_token(
+        &env,
+        &client,
+        &admin,
+        &[&business, &investor_a, &investor_b],
+    );
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+
+    let mut bid_ids = Vec::new(&env);
+    bid_ids.push_back(client.place_bid(&investor_a, &invoice_id, &6_000, &6_600));
+    bid_ids.push_back(client.place_bid(&investor_b, &invoice_id, &6_000, &6_600));
+
+    let result = client.try_accept_syndicated_bids(&invoice_id, &bid_ids);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+    assert_eq!(client.get_invoice_investments(&invoice_id).len(), 0);
+}
+
+#[test]
+fn test_syndicate_rejects_duplicate_empty_and_foreign_bids() {
+    let (env, client, business, investor_a, _investor_b, currency, invoice_id, bid_ids) =
+        setup_syndicate();
+
+    let empty: Vec<BytesN<32>> = Vec::new(&env);
+    assert_eq!(
+        client.try_accept_syndicated_bids(&invoice_id, &empty),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let mut duplicated = bid_ids.clone();
+    duplicated.push_back(bid_ids.get(0).unwrap());
+    assert_eq!(
+        client.try_accept_syndicated_bids(&invoice_id, &duplicated),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let other_invoice = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let mut foreign = bid_ids.clone();
+    foreign.push_back(client.place_bid(&investor_a, &other_invoice, &1_000, &1_100));
+    assert_eq!(
+        client.try_accept_syndicated_bids(&invoice_id, &foreign),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    client.accept_syndicated_bids(&invoice_id, &bid_ids);
+    assert_eq!(
+        client.try_accept_syndicated_bids(&invoice_id, &bid_ids),
+        Err(Ok(QuickLendXError::InvoiceAlreadyFunded))
+    );
+}
+
+#[test]
+fn test_syndicated_escrow_release_pays_business_every_slice() {
+    let (env, client, business, _investor_a, _investor_b, currency, invoice_id, bid_ids) =
+        setup_syndicate();
+    let token_client = token::Client::new(&env, &currency);
+    let before = token_client.balance(&business);
+    client.accept_syndicated_bids(&invoice_id, &bid_ids);
+
+    client.release_escrow_funds(&invoice_id);
+
+    assert_eq!(token_client.balance(&business) - before, 9_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    for escrow in client.get_invoice_escrows(&invoice_id).iter() {
+        assert_eq!(escrow.status, EscrowStatus::Released);
+    }
+    assert!(client.try_release_escrow_funds(&invoice_id).is_err());
+}
+
+#[test]
+fn test_syndicated_default_marks_every_investment() {
+    let (_env, client, _business, _investor_a, _investor_b, _currency, invoice_id, bid_ids) =
+        setup_syndicate();
+    client.accept_syndicated_bids(&invoice_id, &bid_ids);
+
+    client.handle_default(&invoice_id);
+
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Defaulted
+    );
+    for investment in client.get_invoice_investments(&invoice_id).iter() {
+        assert_eq!(investment.status, InvestmentStatus::Defaulted);
+    }
+}
+
+#[test]
+fn test_syndicated_refund_returns_every_slice() {
+    let (env, client, business, investor_a, investor_b, currency, invoice_id, bid_ids) =
+        setup_syndicate();
+    let token_client = token::Client::new(&env, &currency);
+    let before_a = token_client.balance(&investor_a);
+    let before_b = token_client.balance(&investor_b);
+    client.accept_syndicated_bids(&invoice_id, &bid_ids);
+
+    client.refund_escrow_funds(&invoice_id, &business);
+
+    assert_eq!(token_client.balance(&investor_a), before_a);
+    assert_eq!(token_client.balance(&investor_b), before_b);
+    assert_eq!(token_client.balance(&client.address), 0);
+    for escrow in client.get_invoice_escrows(&invoice_id).iter() {
+        assert_eq!(escrow.status, EscrowStatus::Refunded);
+    }
+    for investment in client.get_invoice_investments(&invoice_id).iter() {
+        assert_eq!(investment.status, InvestmentStatus::Refunded);
+    }
+    for bid_id in bid_ids.iter() {
+        assert_eq!(
+            client.get_bid(&bid_id).unwrap().status,
+            BidStatus::Cancelled
+        );
+    }
+}
@@ -0,0 +1,204 @@
+/// Test suite for third-party payers in settlement
+///
+/// Test Coverage:
+/// 1. Debtor payments: the debtor can pay the factor directly
+/// 2. Mixed payers: each third party's share is taken when recorded and used at settlement
+/// 3. Defaults: held third-party payments go back to their payers
+/// 4. Replay protection: payment nonces stay unique per payer
+/// 5. Authorization: only the obligor, the debtor and approved payers can pay
+use super::*;
+use crate::invoice::InvoiceCategory;
+use crate::settlement::PaymentParty;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+struct Payers {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    business: Address,
+    investor: Address,
+    debtor: Address,
+    delegate: Address,
+    currency: Address,
+}
+
+fn setup() -> Payers {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+    let debtor = Address::generate(&env);
+    let delegate = Address::generate(&env);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor, &debtor, &delegate] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Payers {
+        env,
+        client,
+        business,
+        investor,
+        debtor,
+        delegate,
+        currency,
+    }
+}
+
+/// A 10,000 invoice owed by `p.debtor`, funded with 9,000.
+fn funded_invoice(p: &Payers) -> BytesN<32> {
+    let invoice_id = p.client.store_invoice(
+        &p.business,
+        &10_000,
+        &p.currency,
+        &(p.env.ledger().timestamp() + 30 * 86_400),
+        &String::from_str(&p.env, "Wholesale order"),
+        &InvoiceCategory::Products,
+        &Vec::new(&p.env),
+    );
+    p.client.set_invoice_debtor(&invoice_id, &p.debtor);
+    p.client.verify_invoice(&invoice_id);
+    let bid_id = p
+        .client
+        .place_bid(&p.investor, &invoice_id, &9_000, &10_000);
+    p.client.accept_bid(&invoice_id, &bid_id);
+    invoice_id
+}
+
+fn tx(p: &Payers, reference: &str) -> String {
+    String::from_str(&p.env, reference)
+}
+
+#[test]
+fn test_debtor_pays_factor_directly() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let invoice_id = funded_invoice(&p);
+    let business_before = token_client.balance(&p.business);
+    let investor_before = token_client.balance(&p.investor);
+
+    p.client
+        .process_payment_from(&p.debtor, &invoice_id, &10_000, &tx(&p, "remit-1"));
+
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+    assert_eq!(token_client.balance(&p.debtor), 90_000);
+    assert_eq!(token_client.balance(&p.business), business_before);
+    let (investor_return, _) = p.client.calculate_profit(&9_000, &10_000);
+    assert_eq!(
+        token_client.balance(&p.investor) - investor_before,
+        investor_return
+    );
+    let records = p.client.get_payment_records(&invoice_id, &0, &10);
+    assert_eq!(records.get(0).unwrap().payer, p.debtor);
+    assert_eq!(records.get(0).unwrap().party, PaymentParty::Debtor);
+}
+
+#[test]
+fn test_each_payer_pays_its_recorded_share() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let invoice_id = funded_invoice(&p);
+    p.client.set_approved_payer(&invoice_id, &p.delegate, &true);
+    assert!(p.client.is_approved_payer(&invoice_id, &p.delegate));
+    let business_before = token_client.balance(&p.business);
+
+    p.client
+        .process_payment_from(&p.delegate, &invoice_id, &4_000, &tx(&p, "parent-1"));
+    // Taken when recorded, so revoking the allowance afterwards changes nothing
+    assert_eq!(token_client.balance(&p.delegate), 96_000);
+    token_client.approve(&p.delegate, &p.client.address, &0, &10_000);
+    p.client
+        .process_partial_payment(&invoice_id, &6_000, &tx(&p, "own-1"));
+
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+    assert_eq!(token_client.balance(&p.delegate), 96_000);
+    assert_eq!(business_before - token_client.balance(&p.business), 6_000);
+    let records = p.client.get_payment_records(&invoice_id, &0, &10);
+    assert_eq!(records.get(0).unwrap().party, PaymentParty::Delegate);
+    assert_eq!(records.get(1).unwrap().party, PaymentParty::Business);
+}
+
+#[test]
+fn test_default_returns_held_payments() {
+    let p = setup();
+    let token_client = token::Client::new(&p.env, &p.currency);
+    let invoice_id = funded_invoice(&p);
+    p.client
+        .process_payment_from(&p.debtor, &invoice_id, &3_000, &tx(&p, "remit-1"));
+    assert_eq!(token_client.balance(&p.debtor), 97_000);
+
+    // Past the due date and the 7-day grace period
+    p.env.ledger().set_timestamp(1_000 + 40 * 86_400);
+    p.client.check_overdue_invoices();
+    assert_eq!(
+        p.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Defaulted
+    );
+    assert_eq!(token_client.balance(&p.debtor), 100_000);
+}
+
+#[test]
+fn test_nonces_are_unique_per_payer() {
+    let p = setup();
+    let invoice_id = funded_invoice(&p);
+
+    p.client
+        .process_payment_from(&p.debtor, &invoice_id, &1_000, &tx(&p, "tx-1"));
+    assert_eq!(
+        p.client
+            .try_process_payment_from(&p.debtor, &invoice_id, &1_000, &tx(&p, "tx-1")),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    // Another payer may reuse the same reference
+    p.client
+        .process_partial_payment(&invoice_id, &1_000, &tx(&p, "tx-1"));
+    assert_eq!(p.client.get_invoice(&invoice_id).total_paid, 2_000);
+}
+
+#[test]
+fn test_only_accepted_payers_can_pay() {
+    let p = setup();
+    let invoice_id = funded_invoice(&p);
+    let outsider = Address::generate(&p.env);
+    assert_eq!(
+        p.client
+            .try_process_payment_from(&outsider, &invoice_id, &1_000, &tx(&p, "x")),
+        Err(Ok(QuickLendXError::NotBusinessOwner))
+    );
+
+    p.client.set_approved_payer(&invoice_id, &p.delegate, &true);
+    p.client
+        .set_approved_payer(&invoice_id, &p.delegate, &false);
+    assert!(!p.client.is_approved_payer(&invoice_id, &p.delegate));
+    assert_eq!(
+        p.client
+            .try_process_payment_from(&p.delegate, &invoice_id, &1_000, &tx(&p, "x")),
+        Err(Ok(QuickLendXError::NotBusinessOwner))
+    );
+    assert_eq!(p.client.get_invoice(&invoice_id).total_paid, 0);
+}
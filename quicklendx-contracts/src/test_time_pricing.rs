@@ -0,0 +1,225 @@
+/// Test suite for time-based pricing and early-payment discounts
+///
+/// Test Coverage:
+/// 1. Accrual: early settlement pays investors principal plus the return accrued by day
+/// 2. Minimum fee: very early settlement still pays the minimum fee
+/// 3. EarlyPayment fee structure: keeps a prepayment fee out of the discount
+/// 4. Validation and due-date behaviour: no discount at maturity, pricing locked once bid
+/// 5. Batch payments: an early payment only needs to cover the discounted settlement
+use super::*;
+use crate::batch_payment::BatchPayment;
+use crate::fees::FeeType;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+struct Market {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    business: Address,
+    investor: Address,
+    currency: Address,
+}
+
+fn setup() -> Market {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    let sac = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    for holder in [&business, &investor] {
+        sac.mint(holder, &100_000);
+        token_client.approve(holder, &contract_id, &100_000, &10_000);
+    }
+
+    Market {
+        env,
+        client,
+        admin,
+        business,
+        investor,
+        currency,
+    }
+}
+
+/// 36.5% a year is 0.1% of the principal per day: 9 a day on 9,000.
+fn pricing() -> TimePricing {
+    TimePricing {
+        annual_rate_bps: 3_650,
+        min_fee: 100,
+    }
+}
+
+/// A 10,000 invoice due in 90 days, time-priced and funded with 9,000.
+fn funded_invoice(m: &Market) -> BytesN<32> {
+    let invoice_id = m.client.store_invoice(
+        &m.business,
+        &10_000,
+        &m.currency,
+        &(m.env.ledger().timestamp() + 90 * DAY),
+        &String::from_str(&m.env, "Quarterly services"),
+        &InvoiceCategory::Services,
+        &Vec::new(&m.env),
+    );
+    m.client.set_invoice_time_pricing(&invoice_id, &pricing());
+    m.client.verify_invoice(&invoice_id);
+    let bid_id = m
+        .client
+        .place_bid(&m.investor, &invoice_id, &9_000, &10_000);
+    m.client.accept_bid(&invoice_id, &bid_id);
+    invoice_id
+}
+
+/// Settle after `days` and return what the business paid.
+fn settle_after(m: &Market, invoice_id: &BytesN<32>, days: u64) -> i128 {
+    let token_client = token::Client::new(&m.env, &m.currency);
+    m.env
+        .ledger()
+        .set_timestamp(m.env.ledger().timestamp() + days * DAY);
+    let before = token_client.balance(&m.business);
+    m.client.settle_invoice(invoice_id, &10_000);
+    before - token_client.balance(&m.business)
+}
+
+#[test]
+fn test_early_settlement_pays_accrued_return() {
+    let m = setup();
+    let token_client = token::Client::new(&m.env, &m.currency);
+    let invoice_id = funded_invoice(&m);
+    let investor_before = token_client.balance(&m.investor);
+
+    // 20 days at 9 a day: the business pays 9,180 instead of 10,000
+    assert_eq!(settle_after(&m, &invoice_id, 20), 9_180);
+    let (investor_return, _) = m.client.calculate_profit(&9_000, &9_180);
+    assert_eq!(
+        token_client.balance(&m.investor) - investor_before,
+        investor_return
+    );
+    let invoice = m.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.total_paid, 10_000);
+}
+
+#[test]
+fn test_minimum_fee_applies_to_very_early_settlement() {
+    let m = setup();
+    let invoice_id = funded_invoice(&m);
+    assert_eq!(
+        m.client.get_invoice_time_pricing(&invoice_id),
+        Some(pricing())
+    );
+    // One day accrues 9, below the 100 minimum fee
+    assert_eq!(settle_after(&m, &invoice_id, 1), 9_100);
+}
+
+#[test]
+fn test_early_payment_fee_structure_scales_discount() {
+    let m = setup();
+    m.client.initialize_fee_system(&m.admin);
+    m.client.update_fee_structure(
+        &m.admin,
+        &FeeType::EarlyPayment,
+        &1_000,
+        &0,
+        &1_000_000,
+        &true,
+    );
+    let invoice_id = funded_invoice(&m);
+
+    // 10% of the 820 unearned stays with the investor as a prepayment fee
+    assert_eq!(settle_after(&m, &invoice_id, 20), 9_262);
+}
+
+#[test]
+fn test_batch_payment_checked_against_discounted_settlement() {
+    let m = setup();
+    let token_client = token::Client::new(&m.env, &m.currency);
+    let invoice_id = funded_invoice(&m);
+    m.env
+        .ledger()
+        .set_timestamp(m.env.ledger().timestamp() + 20 * DAY);
+
+    // An allowance covering the 9,180 settlement is enough to pay the full 10,000
+    token_client.approve(&m.business, &m.client.address, &9_180, &10_000);
+    let mut payments = Vec::new(&m.env);
+    payments.push_back(BatchPayment {
+        invoice_id: invoice_id.clone(),
+        amount: 10_000,
+        nonce: String::from_str(&m.env, "early-1"),
+    });
+    let result = m
+        .client
+        .process_batch_payments(&m.business, &payments)
+        .get(0)
+        .unwrap();
+    assert_eq!(result.error, 0);
+    assert!(result.settled);
+    assert_eq!(token_client.allowance(&m.business, &m.client.address), 0);
+}
+
+#[test]
+fn test_no_discount_at_maturity_and_pricing_validation() {
+    let m = setup();
+    let invoice_id = funded_invoice(&m);
+    assert_eq!(
+        m.client
+            .try_set_invoice_time_pricing(&invoice_id, &pricing()),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(settle_after(&m, &invoice_id, 90), 10_000);
+
+    let invoice_id = m.client.store_invoice(
+        &m.business,
+        &10_000,
+        &m.currency,
+        &(m.env.ledger().timestamp() + 90 * DAY),
+        &String::from_str(&m.env, "Quarterly services"),
+        &InvoiceCategory::Services,
+        &Vec::new(&m.env),
+    );
+    let mut bad_pricing = pricing();
+    bad_pricing.annual_rate_bps = 0;
+    assert_eq!(
+        m.client
+            .try_set_invoice_time_pricing(&invoice_id, &bad_pricing),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    bad_pricing = pricing();
+    bad_pricing.min_fee = 10_000;
+    assert_eq!(
+        m.client
+            .try_set_invoice_time_pricing(&invoice_id, &bad_pricing),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    // Investors bid on the pricing they saw, so it cannot change under them
+    m.client.verify_invoice(&invoice_id);
+    m.client
+        .place_bid(&m.investor, &invoice_id, &9_000, &10_000);
+    assert_eq!(
+        m.client
+            .try_set_invoice_time_pricing(&invoice_id, &pricing()),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
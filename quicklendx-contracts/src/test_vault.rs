@@ -0,0 +1,290 @@
+/// Test suite for passive liquidity vaults
+///
+/// Test Coverage:
+/// 1. Auto-funding: a vault bids on matching invoices at verification; settlement returns
+///    flow into the share price
+/// 2. Policy: category, tenor and business exposure limits decide which invoices get bids;
+///    a matching vault that cannot bid emits a skip event
+/// 3. Losses: a default writes the vault's outstanding principal off the share price
+/// 4. Validation: idle caps, idle-limited withdrawals, depositor KYC and curator rights
+use super::*;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::{
+    testutils::Address as _, testutils::Events, token, xdr, Address, BytesN, Env, String, Vec,
+};
+
+struct VaultFixture {
+    env: Env,
+    client: QuickLendXContractClient<'static>,
+    admin: Address,
+    curator: Address,
+    business: Address,
+    currency: Address,
+}
+
+fn setup() -> VaultFixture {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_currency(&admin, &currency);
+    token::StellarAssetClient::new(&env, &currency).mint(&business, &100_000);
+    token::Client::new(&env, &currency).approve(&business, &contract_id, &100_000, &10_000);
+    let curator = Address::generate(&env);
+
+    VaultFixture {
+        env,
+        client,
+        admin,
+        curator,
+        business,
+        currency,
+    }
+}
+
+fn setup_depositor(f: &VaultFixture) -> Address {
+    let depositor = Address::generate(&f.env);
+    f.client
+        .submit_investor_kyc(&depositor, &String::from_str(&f.env, "Investor KYC"));
+    f.client.verify_investor(&depositor, &50_000);
+    token::StellarAssetClient::new(&f.env, &f.currency).mint(&depositor, &100_000);
+    token::Client::new(&f.env, &f.currency).approve(
+        &depositor,
+        &f.client.address,
+        &100_000,
+        &10_000,
+    );
+    depositor
+}
+
+/// Services invoices due within 60 days, 9,000 per business, bidding 90% for 100%.
+fn policy(f: &VaultFixture) -> VaultPolicy {
+    let mut categories = Vec::new(&f.env);
+    categories.push_back(InvoiceCategory::Services);
+    VaultPolicy {
+        categories,
+        max_days_to_due: 60,
+        max_business_exposure: 9_000,
+        advance_bps: 9_000,
+        return_bps: 10_000,
+    }
+}
+
+fn create_vault(f: &VaultFixture) -> u64 {
+    f.client
+        .create_liquidity_vault(&f.admin, &f.currency, &f.curator, &policy(f), &50_000)
+}
+
+fn verified_invoice(f: &VaultFixture, category: InvoiceCategory, days: u64) -> BytesN<32> {
+    let invoice_id = f.client.store_invoice(
+        &f.business,
+        &10_000,
+        &f.currency,
+        &(f.env.ledger().timestamp() + days * 86400),
+        &String::from_str(&f.env, "Consulting services"),
+        &category,
+        &Vec::new(&f.env),
+    );
+    f.client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+fn vault_bids(f: &VaultFixture, invoice_id: &BytesN<32>) -> Vec<Bid> {
+    let mut bids = Vec::new(&f.env);
+    for bid in f.client.get_ranked_bids(invoice_id).iter() {
+        if bid.investor == f.client.address {
+            bids.push_back(bid);
+        }
+    }
+    bids
+}
+
+/// Whether the last contract call emitted an event with `topic`.
+fn emitted(f: &VaultFixture, topic: &str) -> bool {
+    let topic = xdr::ScVal::Symbol(xdr::ScSymbol(topic.try_into().unwrap()));
+    f.env
+        .events()
+        .all()
+        .events()
+        .iter()
+        .any(|event| match &event.body {
+            xdr::ContractEventBody::V0(body) => body.topics.first() == Some(&topic),
+        })
+}
+
+#[test]
+fn test_vault_funds_invoice_and_settlement_raises_share_price() {
+    let f = setup();
+    let token_client = token::Client::new(&f.env, &f.currency);
+    let vault_id = create_vault(&f);
+    let depositor = setup_depositor(&f);
+    assert_eq!(
+        f.client.deposit_to_vault(&depositor, &vault_id, &20_000),
+        20_000
+    );
+
+    let invoice_id = verified_invoice(&f, InvoiceCategory::Services, 30);
+    let bids = vault_bids(&f, &invoice_id);
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids.get(0).unwrap().bid_amount, 9_000);
+
+    f.client
+        .accept_bid(&invoice_id, &bids.get(0).unwrap().bid_id);
+    assert_eq!(f.client.get_escrow_details(&invoice_id).amount, 9_000);
+    let vault = f.client.get_liquidity_vault(&vault_id).unwrap();
+    assert_eq!(vault.idle, 11_000);
+    assert_eq!(vault.deployed, 9_000);
+    assert_eq!(vault.net_asset_value(), 20_000);
+
+    f.client.settle_invoice(&invoice_id, &10_000);
+    let (investor_return, _) = f.client.calculate_profit(&9_000, &10_000);
+    let vault = f.client.get_liquidity_vault(&vault_id).unwrap();
+    assert_eq!(vault.deployed, 0);
+    assert_eq!(vault.idle, 11_000 + investor_return);
+    assert_eq!(
+        f.client.get_vault_share_value(&vault_id, &depositor),
+        11_000 + investor_return
+    );
+
+    let before = token_client.balance(&depositor);
+    assert_eq!(
+        f.client.withdraw_from_vault(&depositor, &vault_id, &20_000),
+        11_000 + investor_return
+    );
+    assert_eq!(
+        token_client.balance(&depositor) - before,
+        11_000 + investor_return
+    );
+    assert_eq!(f.client.get_vault_shares(&vault_id, &depositor), 0);
+}
+
+#[test]
+fn test_vault_policy_selects_invoices() {
+    let f = setup();
+    let vault_id = create_vault(&f);
+    let depositor = setup_depositor(&f);
+    f.client.deposit_to_vault(&depositor, &vault_id, &30_000);
+
+    // Wrong category and too long a tenor get no vault bid
+    let goods = verified_invoice(&f, InvoiceCategory::Products, 30);
+    assert!(!emitted(&f, "vlt_skip"));
+    assert_eq!(vault_bids(&f, &goods).len(), 0);
+    let long_dated = verified_invoice(&f, InvoiceCategory::Services, 90);
+    assert_eq!(vault_bids(&f, &long_dated).len(), 0);
+
+    // Once 9,000 is deployed with the business, its exposure cap is reached
+    let first = verified_invoice(&f, InvoiceCategory::Services, 30);
+    let bid = vault_bids(&f, &first).get(0).unwrap();
+    f.client.accept_bid(&first, &bid.bid_id);
+    assert_eq!(
+        f.client.get_liquidity_vault(&vault_id).unwrap().deployed,
+        9_000
+    );
+    let second = verified_invoice(&f, InvoiceCategory::Services, 30);
+    assert!(emitted(&f, "vlt_skip"));
+    assert_eq!(vault_bids(&f, &second).len(), 0);
+
+    // The curator can widen the policy for invoices verified later
+    let mut wider = policy(&f);
+    wider.max_business_exposure = 18_000;
+    f.client
+        .set_vault_policy(&f.curator, &vault_id, &wider, &50_000);
+    let third = verified_invoice(&f, InvoiceCategory::Services, 30);
+    assert_eq!(vault_bids(&f, &third).len(), 1);
+}
+
+#[test]
+fn test_default_loss_reduces_share_price() {
+    let f = setup();
+    let vault_id = create_vault(&f);
+    let depositor = setup_depositor(&f);
+    f.client.deposit_to_vault(&depositor, &vault_id, &20_000);
+
+    let invoice_id = verified_invoice(&f, InvoiceCategory::Services, 30);
+    let bid = vault_bids(&f, &invoice_id).get(0).unwrap();
+    f.client.accept_bid(&invoice_id, &bid.bid_id);
+    f.client.handle_default(&invoice_id);
+
+    let vault = f.client.get_liquidity_vault(&vault_id).unwrap();
+    assert_eq!(vault.deployed, 0);
+    assert_eq!(vault.net_asset_value(), 11_000);
+    assert_eq!(
+        f.client.get_vault_share_value(&vault_id, &depositor),
+        11_000
+    );
+
+    // New deposits buy shares at the reduced price
+    let late = setup_depositor(&f);
+    assert_eq!(f.client.deposit_to_vault(&late, &vault_id, &11_000), 20_000);
+}
+
+#[test]
+fn test_vault_validation() {
+    let f = setup();
+    let outsider = Address::generate(&f.env);
+    let mut bad_policy = policy(&f);
+    bad_policy.return_bps = 8_000;
+    assert_eq!(
+        f.client.try_create_liquidity_vault(
+            &f.admin,
+            &f.currency,
+            &f.curator,
+            &bad_policy,
+            &50_000
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        f.client.try_create_liquidity_vault(
+            &outsider,
+            &f.currency,
+            &f.curator,
+            &policy(&f),
+            &50_000
+        ),
+        Err(Ok(QuickLendXError::NotAdmin))
+    );
+
+    let vault_id = create_vault(&f);
+    assert_eq!(
+        f.client.try_deposit_to_vault(&outsider, &vault_id, &1_000),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+    assert_eq!(
+        f.client
+            .try_set_vault_policy(&outsider, &vault_id, &policy(&f), &50_000),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    let depositor = setup_depositor(&f);
+    f.client.deposit_to_vault(&depositor, &vault_id, &10_000);
+    assert_eq!(
+        f.client
+            .try_deposit_to_vault(&depositor, &vault_id, &40_001),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Withdrawals are limited to idle liquidity while principal is deployed
+    let invoice_id = verified_invoice(&f, InvoiceCategory::Services, 30);
+    let bid = vault_bids(&f, &invoice_id).get(0).unwrap();
+    f.client.accept_bid(&invoice_id, &bid.bid_id);
+    assert_eq!(
+        f.client
+            .try_withdraw_from_vault(&depositor, &vault_id, &10_000),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    assert_eq!(
+        f.client.withdraw_from_vault(&depositor, &vault_id, &1_000),
+        1_000
+    );
+}
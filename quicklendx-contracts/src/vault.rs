@@ -0,0 +1,625 @@
+//! Passive liquidity vaults.
+//!
+//! Verified investors deposit a whitelisted currency into a vault and receive vault
+//! shares. The vault's curator (or the admin) sets an allocation policy: invoice
+//! categories, maximum tenor, maximum exposure to a single business and the advance and
+//! return the vault bids at. When `verify_invoice` moves a matching invoice to Verified,
+//! the vault bids on it out of its idle liquidity; the bid is funded like any other, with
+//! the contract holding the receipt on the vault's behalf.
+//!
+//! Settlement proceeds paid to that receipt return to the vault's idle balance, principal
+//! first, and default losses write the outstanding principal off, so both flow into the
+//! share price (net asset value / total shares). Deposits are capped by the vault's
+//! `max_idle` and withdrawals can only draw on idle liquidity.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::admin::AdminStorage;
+use crate::audit;
+use crate::bid::{Bid, BidStatus, BidStorage};
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_bid_placed, emit_vault_bid, emit_vault_bid_skipped, emit_vault_created,
+    emit_vault_deposit, emit_vault_funded, emit_vault_loss, emit_vault_policy_set,
+    emit_vault_proceeds, emit_vault_withdrawal,
+};
+use crate::investment::Investment;
+use crate::invoice::{Invoice, InvoiceCategory, InvoiceStatus};
+use crate::listing::{Listing, BPS_DENOMINATOR};
+use crate::notifications::NotificationSystem;
+use crate::payments::transfer_funds;
+use crate::pool::PoolStorage;
+use crate::sealed_bid::SealedBidding;
+use crate::verification::{BusinessVerificationStatus, InvestorVerificationStorage};
+
+/// Maximum number of vaults evaluated on each invoice verification.
+pub const MAX_VAULTS: u32 = 20;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum VaultKey {
+    Vault(u64),
+    VaultShares(u64, Address),
+    /// Bid placed by a vault -> vault id
+    VaultBid(BytesN<32>),
+    /// Invoice the vault funded -> allocation
+    VaultAllocation(BytesN<32>),
+    VaultExposure(u64, Address),
+    VaultList,
+    VaultCounter,
+}
+
+/// Which invoices a vault funds, and on what terms.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultPolicy {
+    /// Eligible categories; empty means any category.
+    pub categories: Vec<InvoiceCategory>,
+    pub max_days_to_due: u64,
+    /// Cap on the principal outstanding with a single business.
+    pub max_business_exposure: i128,
+    /// Bid amount, in bps of the invoice face value.
+    pub advance_bps: u32,
+    /// Expected return, in bps of the invoice face value.
+    pub return_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidityVault {
+    pub vault_id: u64,
+    pub currency: Address,
+    pub curator: Address,
+    pub policy: VaultPolicy,
+    /// Deposits are refused once idle liquidity would exceed this cap.
+    pub max_idle: i128,
+    pub total_shares: i128,
+    /// Liquidity held by the contract and available for bids and withdrawals.
+    pub idle: i128,
+    /// Principal outstanding in funded invoices.
+    pub deployed: i128,
+    pub created_at: u64,
+}
+
+impl LiquidityVault {
+    pub fn net_asset_value(&self) -> i128 {
+        self.idle.saturating_add(self.deployed)
+    }
+
+    /// Current value of `shares`, rounded down.
+    pub fn share_value(&self, shares: i128) -> i128 {
+        if self.total_shares == 0 {
+            return 0;
+        }
+        shares.saturating_mul(self.net_asset_value()) / self.total_shares
+    }
+
+    /// Bid amount and expected return the vault offers on an invoice.
+    pub fn bid_terms(&self, invoice_amount: i128) -> (i128, i128) {
+        (
+            invoice_amount.saturating_mul(self.policy.advance_bps as i128) / BPS_DENOMINATOR,
+            invoice_amount.saturating_mul(self.policy.return_bps as i128) / BPS_DENOMINATOR,
+        )
+    }
+}
+
+/// Principal a vault has outstanding in one funded invoice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultAllocation {
+    pub vault_id: u64,
+    pub business: Address,
+    pub principal_outstanding: i128,
+}
+
+pub struct VaultStorage;
+
+impl VaultStorage {
+    pub fn get_vault(env: &Env, vault_id: u64) -> Option<LiquidityVault> {
+        env.storage().persistent().get(&VaultKey::Vault(vault_id))
+    }
+
+    pub fn get_vault_ids(env: &Env) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&VaultKey::VaultList)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn get_shares(env: &Env, vault_id: u64, holder: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&VaultKey::VaultShares(vault_id, holder.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn get_allocation(env: &Env, invoice_id: &BytesN<32>) -> Option<VaultAllocation> {
+        env.storage()
+            .persistent()
+            .get(&VaultKey::VaultAllocation(invoice_id.clone()))
+    }
+
+    pub fn get_business_exposure(env: &Env, vault_id: u64, business: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&VaultKey::VaultExposure(vault_id, business.clone()))
+            .unwrap_or(0)
+    }
+
+    fn store_vault(env: &Env, vault: &LiquidityVault) {
+        env.storage()
+            .persistent()
+            .set(&VaultKey::Vault(vault.vault_id), vault);
+    }
+
+    fn set_shares(env: &Env, vault_id: u64, holder: &Address, shares: i128) {
+        let key = VaultKey::VaultShares(vault_id, holder.clone());
+        if shares == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &shares);
+        }
+    }
+
+    fn add_business_exposure(env: &Env, vault_id: u64, business: &Address, delta: i128) {
+        let exposure = Self::get_business_exposure(env, vault_id, business)
+            .saturating_add(delta)
+            .max(0);
+        env.storage().persistent().set(
+            &VaultKey::VaultExposure(vault_id, business.clone()),
+            &exposure,
+        );
+    }
+
+    fn next_vault_id(env: &Env) -> u64 {
+        let next: u64 = env
+            .storage()
+            .persistent()
+            .get(&VaultKey::VaultCounter)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage()
+            .persistent()
+            .set(&VaultKey::VaultCounter, &next);
+        next
+    }
+}
+
+pub struct LiquidityVaults;
+
+impl LiquidityVaults {
+    /// Open a vault in a whitelisted currency (admin only).
+    ///
+    /// # Errors
+    /// * `NotAdmin` if `admin` is not the admin
+    /// * `InvalidCurrency` if the currency is not whitelisted
+    /// * `InvalidAmount` / `InvalidTimestamp` if the policy or `max_idle` is invalid
+    /// * `OperationNotAllowed` if `MAX_VAULTS` vaults already exist
+    pub fn create(
+        env: &Env,
+        admin: &Address,
+        currency: &Address,
+        curator: &Address,
+        policy: &VaultPolicy,
+        max_idle: i128,
+    ) -> Result<LiquidityVault, QuickLendXError> {
+        admin.require_auth();
+        AdminStorage::require_admin(env, admin)?;
+        CurrencyWhitelist::require_allowed_currency(env, currency)?;
+        Self::validate_policy(policy)?;
+        if max_idle <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let mut vault_ids = VaultStorage::get_vault_ids(env);
+        if vault_ids.len() >= MAX_VAULTS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let vault = LiquidityVault {
+            vault_id: VaultStorage::next_vault_id(env),
+            currency: currency.clone(),
+            curator: curator.clone(),
+            policy: policy.clone(),
+            max_idle,
+            total_shares: 0,
+            idle: 0,
+            deployed: 0,
+            created_at: env.ledger().timestamp(),
+        };
+        VaultStorage::store_vault(env, &vault);
+        vault_ids.push_back(vault.vault_id);
+        env.storage()
+            .persistent()
+            .set(&VaultKey::VaultList, &vault_ids);
+
+        emit_vault_created(env, &vault);
+        Ok(vault)
+    }
+
+    /// Replace a vault's allocation policy and idle cap (curator or admin). Invoices
+    /// already funded are not affected.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the vault does not exist
+    /// * `Unauthorized` if `caller` is neither the curator nor the admin
+    /// * `InvalidAmount` / `InvalidTimestamp` if the policy or `max_idle` is invalid
+    pub fn set_policy(
+        env: &Env,
+        caller: &Address,
+        vault_id: u64,
+        policy: &VaultPolicy,
+        max_idle: i128,
+    ) -> Result<(), QuickLendXError> {
+        caller.require_auth();
+        let mut vault =
+            VaultStorage::get_vault(env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if vault.curator != *caller && !AdminStorage::is_admin(env, caller) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        Self::validate_policy(policy)?;
+        if max_idle <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        vault.policy = policy.clone();
+        vault.max_idle = max_idle;
+        VaultStorage::store_vault(env, &vault);
+        emit_vault_policy_set(env, vault_id, caller);
+        Ok(())
+    }
+
+    /// Deposit into a vault (verified investor) and receive shares at the current share
+    /// price.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified` / `KYCAlreadyPending` if the depositor is not verified
+    /// * `StorageKeyNotFound` if the vault does not exist
+    /// * `InvalidAmount` if `amount` is not positive or buys no shares
+    /// * `OperationNotAllowed` if idle liquidity would exceed the vault's `max_idle`
+    /// * `InvalidStatus` if the vault has outstanding shares but no assets left
+    /// * Any error from the token transfer
+    pub fn deposit(
+        env: &Env,
+        depositor: &Address,
+        vault_id: u64,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        depositor.require_auth();
+        let verification = InvestorVerificationStorage::get(env, depositor)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {}
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+        let mut vault =
+            VaultStorage::get_vault(env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let idle = vault
+            .idle
+            .checked_add(amount)
+            .ok_or(QuickLendXError::InvalidAmount)?;
+        if idle > vault.max_idle {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let nav = vault.net_asset_value();
+        let shares = if vault.total_shares == 0 {
+            amount
+        } else if nav <= 0 {
+            // Every asset was written off; new money would only pay the old holders
+            return Err(QuickLendXError::InvalidStatus);
+        } else {
+            amount
+                .checked_mul(vault.total_shares)
+                .map(|v| v / nav)
+                .ok_or(QuickLendXError::InvalidAmount)?
+        };
+        if shares <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        transfer_funds(
+            env,
+            &vault.currency,
+            depositor,
+            &env.current_contract_address(),
+            amount,
+        )?;
+        vault.idle = idle;
+        vault.total_shares = vault.total_shares.saturating_add(shares);
+        VaultStorage::store_vault(env, &vault);
+        let balance = VaultStorage::get_shares(env, vault_id, depositor).saturating_add(shares);
+        VaultStorage::set_shares(env, vault_id, depositor, balance);
+
+        emit_vault_deposit(env, vault_id, depositor, amount, shares);
+        Ok(shares)
+    }
+
+    /// Redeem vault shares for their current value, paid out of idle liquidity.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the vault does not exist
+    /// * `InvalidAmount` if `shares` is not positive or is worth nothing
+    /// * `InsufficientFunds` if `holder` has fewer shares, or the vault does not have
+    ///   enough idle liquidity to pay them out
+    /// * Any error from the token transfer
+    pub fn withdraw(
+        env: &Env,
+        holder: &Address,
+        vault_id: u64,
+        shares: i128,
+    ) -> Result<i128, QuickLendXError> {
+        holder.require_auth();
+        let mut vault =
+            VaultStorage::get_vault(env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if shares <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let balance = VaultStorage::get_shares(env, vault_id, holder);
+        if shares > balance {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        let amount = vault.share_value(shares);
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if amount > vault.idle {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+
+        vault.idle -= amount;
+        vault.total_shares -= shares;
+        VaultStorage::store_vault(env, &vault);
+        VaultStorage::set_shares(env, vault_id, holder, balance - shares);
+        transfer_funds(
+            env,
+            &vault.currency,
+            &env.current_contract_address(),
+            holder,
+            amount,
+        )?;
+
+        emit_vault_withdrawal(env, vault_id, holder, shares, amount);
+        Ok(amount)
+    }
+
+    /// Place a bid from the first vault whose policy matches a Verified invoice and that
+    /// has the idle liquidity for it. The bid is held by the contract on the vault's
+    /// behalf; the liquidity is only committed once the bid is accepted. A vault whose
+    /// policy matches but that cannot bid is skipped with an event.
+    ///
+    /// Returns the bid and whether it meets the invoice's buy-now price.
+    pub fn bid_on_invoice(env: &Env, invoice: &Invoice) -> Option<(Bid, bool)> {
+        let contract_address = env.current_contract_address();
+        if invoice.status != InvoiceStatus::Verified
+            || SealedBidding::is_bidding_open(env, &invoice.id)
+            || PoolStorage::get_pool_for_invoice(env, &invoice.id).is_some()
+            || !CurrencyWhitelist::is_allowed_currency(env, &invoice.currency)
+        {
+            return None;
+        }
+        // A single open vault bid per invoice keeps the receipt attributable to one vault
+        let already_bidding = BidStorage::get_bids_by_investor(env, &invoice.id, &contract_address)
+            .iter()
+            .any(|bid| bid.status == BidStatus::Placed);
+        if already_bidding {
+            return None;
+        }
+
+        let now = env.ledger().timestamp();
+        for vault_id in VaultStorage::get_vault_ids(env).iter() {
+            let vault = match VaultStorage::get_vault(env, vault_id) {
+                Some(vault) => vault,
+                None => continue,
+            };
+            if !Self::matches_policy(&vault, invoice, now) {
+                continue;
+            }
+            let (bid_amount, expected_return) = vault.bid_terms(invoice.amount);
+            let checked = Self::check_capacity(env, &vault, &invoice.business, bid_amount)
+                .and_then(|_| Listing::check_bid(env, invoice, bid_amount, expected_return));
+            let buy_now = match checked {
+                Ok(buy_now) => buy_now,
+                Err(error) => {
+                    emit_vault_bid_skipped(env, vault_id, &invoice.id, error);
+                    continue;
+                }
+            };
+
+            let bid_id = BidStorage::generate_unique_bid_id(env);
+            let bid = Bid {
+                bid_id: bid_id.clone(),
+                invoice_id: invoice.id.clone(),
+                investor: contract_address.clone(),
+                bid_amount,
+                expected_return,
+                timestamp: now,
+                status: BidStatus::Placed,
+                expiration_timestamp: Bid::default_expiration_with_env(env, now),
+            };
+            BidStorage::store_bid(env, &bid);
+            BidStorage::add_bid_to_invoice(env, &invoice.id, &bid_id);
+            env.storage()
+                .persistent()
+                .set(&VaultKey::VaultBid(bid_id.clone()), &vault_id);
+
+            emit_bid_placed(env, &bid);
+            audit::log_bid_placed(
+                env,
+                invoice.id.clone(),
+                contract_address.clone(),
+                bid_amount,
+                bid_id.clone(),
+            );
+            let _ = NotificationSystem::notify_bid_received(env, invoice, &bid);
+            emit_vault_bid(env, vault_id, &bid);
+            return Some((bid, buy_now));
+        }
+        None
+    }
+
+    /// Attribute a bid placed outside `bid_on_invoice`, such as a credit line draw, to a
+    /// vault so that funding it commits the vault's liquidity.
+    pub fn register_bid(env: &Env, vault_id: u64, bid_id: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .set(&VaultKey::VaultBid(bid_id.clone()), &vault_id);
+    }
+
+    /// Check, without committing anything, that the vault behind a bid can fund it.
+    /// Returns that vault, or `None` for bids that were not placed by a vault.
+    ///
+    /// # Errors
+    /// * `InsufficientFunds` if the vault no longer has the idle liquidity for the bid
+    /// * `OperationNotAllowed` if funding would exceed the vault's business exposure cap
+    pub fn check_allocation(
+        env: &Env,
+        invoice: &Invoice,
+        bid: &Bid,
+    ) -> Result<Option<LiquidityVault>, QuickLendXError> {
+        let vault_id: u64 = match env
+            .storage()
+            .persistent()
+            .get(&VaultKey::VaultBid(bid.bid_id.clone()))
+        {
+            Some(vault_id) => vault_id,
+            None => return Ok(None),
+        };
+        let vault =
+            VaultStorage::get_vault(env, vault_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Self::check_capacity(env, &vault, &invoice.business, bid.bid_amount)?;
+        Ok(Some(vault))
+    }
+
+    /// Commit a vault's liquidity to a bid being accepted. Does nothing for bids that
+    /// were not placed by a vault.
+    ///
+    /// # Errors
+    /// Same as `check_allocation`.
+    pub fn allocate(env: &Env, invoice: &Invoice, bid: &Bid) -> Result<(), QuickLendXError> {
+        let mut vault = match Self::check_allocation(env, invoice, bid)? {
+            Some(vault) => vault,
+            None => return Ok(()),
+        };
+        let vault_id = vault.vault_id;
+
+        vault.idle -= bid.bid_amount;
+        vault.deployed = vault.deployed.saturating_add(bid.bid_amount);
+        VaultStorage::store_vault(env, &vault);
+        VaultStorage::add_business_exposure(env, vault_id, &invoice.business, bid.bid_amount);
+        env.storage().persistent().set(
+            &VaultKey::VaultAllocation(invoice.id.clone()),
+            &VaultAllocation {
+                vault_id,
+                business: invoice.business.clone(),
+                principal_outstanding: bid.bid_amount,
+            },
+        );
+        emit_vault_funded(env, vault_id, bid);
+        Ok(())
+    }
+
+    /// Credit a payment received on a vault-held receipt to the vault. Principal is
+    /// repaid first; anything above it is the vault's return.
+    pub fn record_proceeds(env: &Env, invoice_id: &BytesN<32>, amount: i128) {
+        let mut allocation = match VaultStorage::get_allocation(env, invoice_id) {
+            Some(allocation) => allocation,
+            None => return,
+        };
+        let mut vault = match VaultStorage::get_vault(env, allocation.vault_id) {
+            Some(vault) => vault,
+            None => return,
+        };
+        let principal = amount.min(allocation.principal_outstanding).max(0);
+        allocation.principal_outstanding -= principal;
+        vault.deployed = vault.deployed.saturating_sub(principal).max(0);
+        vault.idle = vault.idle.saturating_add(amount);
+        VaultStorage::store_vault(env, &vault);
+        VaultStorage::add_business_exposure(env, vault.vault_id, &allocation.business, -principal);
+        env.storage()
+            .persistent()
+            .set(&VaultKey::VaultAllocation(invoice_id.clone()), &allocation);
+        emit_vault_proceeds(env, vault.vault_id, invoice_id, amount, principal);
+    }
+
+    /// Write off the principal still outstanding in a defaulted vault investment.
+    pub fn record_loss(env: &Env, investment: &Investment) {
+        if investment.investor != env.current_contract_address() {
+            return;
+        }
+        let mut allocation = match VaultStorage::get_allocation(env, &investment.invoice_id) {
+            Some(allocation) if allocation.principal_outstanding > 0 => allocation,
+            _ => return,
+        };
+        let mut vault = match VaultStorage::get_vault(env, allocation.vault_id) {
+            Some(vault) => vault,
+            None => return,
+        };
+        let loss = allocation.principal_outstanding;
+        allocation.principal_outstanding = 0;
+        vault.deployed = vault.deployed.saturating_sub(loss).max(0);
+        VaultStorage::store_vault(env, &vault);
+        VaultStorage::add_business_exposure(env, vault.vault_id, &allocation.business, -loss);
+        env.storage().persistent().set(
+            &VaultKey::VaultAllocation(investment.invoice_id.clone()),
+            &allocation,
+        );
+        emit_vault_loss(env, vault.vault_id, &investment.invoice_id, loss);
+    }
+
+    fn matches_policy(vault: &LiquidityVault, invoice: &Invoice, now: u64) -> bool {
+        let policy = &vault.policy;
+        if vault.currency != invoice.currency
+            || (!policy.categories.is_empty() && !policy.categories.contains(&invoice.category))
+        {
+            return false;
+        }
+        let max_due = now.saturating_add(policy.max_days_to_due.saturating_mul(SECONDS_PER_DAY));
+        invoice.due_date <= max_due
+    }
+
+    /// Check that a vault has the idle liquidity and business exposure headroom for
+    /// `amount`.
+    fn check_capacity(
+        env: &Env,
+        vault: &LiquidityVault,
+        business: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if amount > vault.idle {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        let exposure = VaultStorage::get_business_exposure(env, vault.vault_id, business)
+            .saturating_add(amount);
+        if exposure > vault.policy.max_business_exposure {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+
+    fn validate_policy(policy: &VaultPolicy) -> Result<(), QuickLendXError> {
+        if policy.advance_bps == 0 || policy.advance_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if policy.return_bps < policy.advance_bps {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if policy.max_business_exposure <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if policy.max_days_to_due == 0 {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
@@ -485,7 +485,29 @@ impl InvestorVerificationStorage {
     }
 }
 
-pub fn validate_bid(
+/// One-open-bid-per-investor rule for new bids: fails with `OperationNotAllowed` while
+/// `investor` has a Placed bid on the invoice.
+pub fn require_no_open_bid(
+    env: &Env,
+    invoice: &Invoice,
+    investor: &Address,
+) -> Result<(), QuickLendXError> {
+    BidStorage::cleanup_expired_bids(env, &invoice.id);
+    let existing_bids = BidStorage::get_bids_for_invoice(env, &invoice.id);
+    for bid_id in existing_bids.iter() {
+        if let Some(existing_bid) = BidStorage::get_bid(env, &bid_id) {
+            if existing_bid.investor == *investor && existing_bid.status == BidStatus::Placed {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Amount and investor-limit checks for a bid's terms. New bids also go through
+/// `require_no_open_bid`; amended and negotiated bids do not.
+pub fn validate_bid_terms(
     env: &Env,
     invoice: &Invoice,
     bid_amount: i128,
@@ -512,19 +534,7 @@ pub fn validate_bid(
     }
 
     // Validate investor can make this investment
-    validate_investor_investment(env, investor, bid_amount)?;
-
-    BidStorage::cleanup_expired_bids(env, &invoice.id);
-    let existing_bids = BidStorage::get_bids_for_invoice(env, &invoice.id);
-    for bid_id in existing_bids.iter() {
-        if let Some(existing_bid) = BidStorage::get_bid(env, &bid_id) {
-            if existing_bid.investor == *investor && existing_bid.status == BidStatus::Placed {
-                return Err(QuickLendXError::OperationNotAllowed);
-            }
-        }
-    }
-
-    Ok(())
+    validate_investor_investment(env, investor, bid_amount)
 }
 
 pub fn submit_kyc_application(